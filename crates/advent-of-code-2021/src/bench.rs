@@ -0,0 +1,252 @@
+use std::fmt::Display;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre;
+use shared::memo::{self, Stats as MemoStats};
+
+use crate::build_env::get_build_env;
+use crate::cli::{BenchArgs, Cli};
+use crate::input::InputSource;
+use advent_of_code_2021::registry::Registry;
+use advent_of_code_2021::shared::{AnyDay, AnyParsed, CancellationToken, DayError, catch_panic};
+
+/// The pieces of a day that are timed separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match *self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part 1",
+            Phase::Part2 => "part 2",
+        };
+
+        f.pad(name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Stats {
+    min: Duration,
+    median: Duration,
+    /// Nearest-rank, so always one of the samples.
+    p95: Duration,
+    max: Duration,
+}
+
+impl Stats {
+    /// `None` when there are no samples.
+    fn new(mut samples: Vec<Duration>) -> Option<Self> {
+        samples.sort_unstable();
+
+        let min = *samples.first()?;
+        let max = *samples.last()?;
+
+        let middle = samples.len() / 2;
+
+        let median = if samples.len().is_multiple_of(2) {
+            (samples[middle - 1] + samples[middle]) / 2
+        } else {
+            samples[middle]
+        };
+
+        let p95 = samples[(samples.len() * 95).div_ceil(100) - 1];
+
+        Some(Self {
+            min,
+            median,
+            p95,
+            max,
+        })
+    }
+}
+
+/// Parts get `parsed`, which is parsed once up front, so their timings leave parsing out. Also
+/// gives what the memos did in the run.
+fn time_phase(
+    solution: &dyn AnyDay,
+    phase: Phase,
+    input: &str,
+    parsed: &AnyParsed,
+    timeout: Duration,
+) -> Result<(Duration, MemoStats), DayError> {
+    let cancel = CancellationToken::with_timeout(timeout);
+
+    memo::reset_stats();
+
+    let start = Instant::now();
+
+    let part = match phase {
+        Phase::Parse => {
+            // dropped after the clock stops
+            let _parsed = black_box(solution.parse(input))?;
+
+            return Ok((start.elapsed(), memo::take_stats()));
+        },
+        Phase::Part1 => 1,
+        Phase::Part2 => 2,
+    };
+
+    let result = black_box(solution.part(part, parsed, &cancel));
+
+    let elapsed = start.elapsed();
+
+    result.map(|_| (elapsed, memo::take_stats()))
+}
+
+/// Runs each phase of each day `args.warmup` times untimed, then `args.runs` times timed, and
+/// prints the statistics per phase. A phase that fails is printed with its error instead.
+pub fn run(
+    cli: &Cli,
+    args: &BenchArgs,
+    solutions: &Registry,
+    days: &[u32],
+    input_sources: &[InputSource],
+) -> Result<(), eyre::Report> {
+    let phases = [Phase::Parse]
+        .into_iter()
+        .chain(cli.runs_part(1).then_some(Phase::Part1))
+        .chain(cli.runs_part(2).then_some(Phase::Part2))
+        .collect::<Vec<_>>();
+
+    println!("{}", get_build_env());
+    println!(
+        "{} run(s) per phase, after {} warmup run(s)",
+        args.runs, args.warmup
+    );
+    println!();
+    println!(
+        "{:>3}  {:<6}  {:>12}  {:>12}  {:>12}  {:>12}",
+        "Day", "Phase", "min", "median", "p95", "max"
+    );
+
+    let mut failures = 0;
+
+    for (&day, input_source) in days.iter().zip(input_sources) {
+        let entry = &solutions[&day];
+
+        let input = input_source.read(entry.embedded_input)?;
+
+        // a day that fails shows up in the table, the other days still get benched
+        let parsed = match catch_panic(|| entry.solution.parse(&input)) {
+            Ok(parsed) => parsed,
+            Err(error) => {
+                print_failure(day, Phase::Parse, &error);
+                failures += 1;
+
+                continue;
+            },
+        };
+
+        for &phase in &phases {
+            // stops at the first run that fails, warmup runs leave no sample
+            let samples = (0..args.warmup + args.runs)
+                .map(|run| {
+                    catch_panic(|| {
+                        time_phase(&*entry.solution, phase, &input, &parsed, cli.timeout)
+                    })
+                    .map(|sample| (run >= args.warmup).then_some(sample))
+                })
+                .collect::<Result<Vec<_>, _>>();
+
+            let samples: Vec<(Duration, MemoStats)> = match samples {
+                Ok(samples) => samples.into_iter().flatten().collect(),
+                Err(error) => {
+                    print_failure(day, phase, &error);
+                    failures += 1;
+
+                    continue;
+                },
+            };
+
+            // every run does the same, so the memos do too
+            let memo_stats = samples.last().map(|&(_, memo_stats)| memo_stats);
+
+            let Some(stats) = Stats::new(samples.into_iter().map(|(elapsed, _)| elapsed).collect())
+            else {
+                continue;
+            };
+
+            print!(
+                "{:>3}  {:<6}  {:>12.2?}  {:>12.2?}  {:>12.2?}  {:>12.2?}",
+                day, phase, stats.min, stats.median, stats.p95, stats.max
+            );
+
+            match memo_stats {
+                Some(memo_stats) if !memo_stats.is_empty() => println!("  memo: {}", memo_stats),
+                _ => println!(),
+            }
+        }
+    }
+
+    if failures > 0 {
+        println!();
+        println!("{} phase(s) failed", failures);
+    }
+
+    Ok(())
+}
+
+fn print_failure(day: u32, phase: Phase, error: &DayError) {
+    println!("{:>3}  {:<6}  failed: {}", day, phase, error);
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::bench::Stats;
+
+    fn millis(samples: &[u64]) -> Vec<Duration> {
+        samples.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    #[test]
+    fn odd_number_of_samples() {
+        assert_eq!(
+            Some(Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_millis(3),
+                p95: Duration::from_millis(9),
+                max: Duration::from_millis(9),
+            }),
+            Stats::new(millis(&[9, 3, 1, 4, 2]))
+        );
+    }
+
+    #[test]
+    fn even_number_of_samples() {
+        assert_eq!(
+            Some(Stats {
+                min: Duration::from_millis(1),
+                median: Duration::from_micros(2500),
+                p95: Duration::from_millis(4),
+                max: Duration::from_millis(4),
+            }),
+            Stats::new(millis(&[4, 3, 2, 1]))
+        );
+    }
+
+    #[test]
+    fn p95_is_nearest_rank() {
+        let samples = (1..=100).collect::<Vec<_>>();
+
+        let stats = Stats::new(millis(&samples)).unwrap();
+
+        assert_eq!(Duration::from_millis(95), stats.p95);
+        assert_eq!(Duration::from_micros(50_500), stats.median);
+    }
+
+    #[test]
+    fn no_samples() {
+        assert_eq!(None, Stats::new(Vec::new()));
+    }
+}
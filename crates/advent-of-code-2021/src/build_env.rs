@@ -18,10 +18,12 @@ impl BuildEnv {
         self.host
     }
 
+    #[expect(unused, reason = "Library code")]
     pub fn get_target(&self) -> &'static str {
         self.target
     }
 
+    #[expect(unused, reason = "Library code")]
     pub fn get_target_cpu(&self) -> Option<&str> {
         self.target_cpu
     }
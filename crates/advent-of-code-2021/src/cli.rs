@@ -0,0 +1,387 @@
+use std::num::NonZeroUsize;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use clap::{ArgAction, Args, Parser, Subcommand};
+use color_eyre::eyre;
+
+use crate::input::InputSource;
+use crate::output::OutputFormat;
+
+const FIRST_DAY: u32 = 1;
+const LAST_DAY: u32 = 25;
+
+/// Runs the Advent of Code 2021 solutions.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Run every registered day (the default when no `--day` is given).
+    #[arg(long, global = true, conflicts_with = "days")]
+    pub all: bool,
+
+    /// Day(s) to run, e.g. `21`, `1-5` or `1,3,20-25`. Can be repeated.
+    #[arg(short, long = "day", global = true, value_name = "DAYS", value_parser = parse_day_selection)]
+    pub days: Vec<DaySelection>,
+
+    /// Only run this part of the selected days.
+    #[arg(short, long, global = true, value_parser = clap::value_parser!(u32).range(1..=2))]
+    pub part: Option<u32>,
+
+    /// Read the puzzle input from this file instead of the embedded one, `-` reads stdin. Only
+    /// valid when a single day is selected.
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_name = "FILE",
+        conflicts_with = "inputs_dir"
+    )]
+    pub input: Option<PathBuf>,
+
+    /// Read each day's puzzle input from `<DIR>/day_XX.txt` instead of the embedded ones.
+    #[arg(long, global = true, value_name = "DIR")]
+    pub inputs_dir: Option<PathBuf>,
+
+    /// How many days and parts to solve at the same time. The answers are still printed in day
+    /// order, `bench` always runs one at a time.
+    #[arg(short, long, global = true, default_value = "1")]
+    pub jobs: NonZeroUsize,
+
+    /// Stop a part that takes longer than this many seconds and report it as timed out, `0` means
+    /// no limit.
+    #[arg(long, global = true, value_name = "SECONDS", default_value = "60", value_parser = parse_timeout)]
+    pub timeout: Duration,
+
+    /// Log what the solvers are doing to stderr, `-v` for debug and `-vv` for trace. `RUST_LOG`
+    /// narrows it down per day, e.g. `RUST_LOG=advent_of_code_2021::day_12=trace`.
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// How to write the answers.
+    #[arg(short, long, value_enum, default_value_t)]
+    pub output: OutputFormat,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Time parsing and each part of the selected days over a number of runs.
+    Bench(BenchArgs),
+    /// Check the answers of the selected days against a manifest of expected answers.
+    Verify(VerifyArgs),
+}
+
+#[derive(Args, Debug, PartialEq, Eq)]
+pub struct BenchArgs {
+    /// How many timed runs to do per day.
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+    pub runs: u32,
+
+    /// How many untimed runs to do first, to warm up caches and the allocator.
+    #[arg(short, long, default_value_t = 1)]
+    pub warmup: u32,
+}
+
+#[derive(Args, Debug, PartialEq, Eq)]
+pub struct VerifyArgs {
+    /// A `.toml` or `.json` file with the expected answers, keyed by day and part.
+    pub manifest: PathBuf,
+}
+
+impl Cli {
+    /// Resolves the selection against the registered days, in day order and without duplicates.
+    ///
+    /// A range silently skips days without a solution, but asking for a single missing day is an
+    /// error, as that's most likely a typo.
+    pub fn resolve_days(&self, registered: &[u32]) -> Result<Vec<u32>, eyre::Report> {
+        if self.all || self.days.is_empty() {
+            return Ok(registered.to_vec());
+        }
+
+        let mut days = Vec::new();
+
+        for range in self.days.iter().flat_map(|selection| &selection.0) {
+            if range.start() == range.end() && !registered.contains(range.start()) {
+                return Err(eyre::eyre!(
+                    "Day {} has no registered solution",
+                    range.start()
+                ));
+            }
+
+            days.extend(registered.iter().filter(|day| range.contains(day)));
+        }
+
+        days.sort_unstable();
+        days.dedup();
+
+        Ok(days)
+    }
+
+    pub fn runs_part(&self, part: u32) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+
+    /// Where to read the input of each of the resolved `days` from.
+    pub fn input_sources(&self, days: &[u32]) -> Result<Vec<InputSource>, eyre::Report> {
+        if let Some(ref input) = self.input {
+            let &[_] = days else {
+                return Err(eyre::eyre!(
+                    "`--input` needs exactly one day, but {} were selected",
+                    days.len()
+                ));
+            };
+
+            let source = if input == Path::new("-") {
+                InputSource::Stdin
+            } else {
+                InputSource::File(input.clone())
+            };
+
+            return Ok(vec![source]);
+        }
+
+        let sources = days
+            .iter()
+            .map(|day| match self.inputs_dir {
+                Some(ref inputs_dir) => {
+                    InputSource::File(inputs_dir.join(format!("day_{:02}.txt", day)))
+                },
+                None => InputSource::Embedded,
+            })
+            .collect();
+
+        Ok(sources)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DaySelection(Vec<RangeInclusive<u32>>);
+
+fn parse_day_selection(value: &str) -> Result<DaySelection, String> {
+    value
+        .split(',')
+        .map(parse_day_range)
+        .collect::<Result<Vec<_>, _>>()
+        .map(DaySelection)
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    value
+        .parse::<f64>()
+        .map_err(|error| error.to_string())
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).map_err(|error| error.to_string()))
+        .map_err(|error| format!("`{}` is not a number of seconds: {}", value, error))
+}
+
+fn parse_day_range(value: &str) -> Result<RangeInclusive<u32>, String> {
+    let (start, end) = if let Some((start, end)) = value.split_once('-') {
+        (parse_day(start)?, parse_day(end)?)
+    } else {
+        let day = parse_day(value)?;
+
+        (day, day)
+    };
+
+    if start > end {
+        return Err(format!(
+            "`{}` is not a valid range, {} comes after {}",
+            value, start, end
+        ));
+    }
+
+    Ok(start..=end)
+}
+
+fn parse_day(value: &str) -> Result<u32, String> {
+    match value.trim().parse::<u32>() {
+        Ok(day) if (FIRST_DAY..=LAST_DAY).contains(&day) => Ok(day),
+        Ok(_) | Err(_) => Err(format!(
+            "`{}` is not a day between {} and {}",
+            value, FIRST_DAY, LAST_DAY
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use clap::Parser as _;
+    use pretty_assertions::assert_eq;
+
+    use std::num::NonZeroUsize;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use crate::cli::{BenchArgs, Cli, Command, DaySelection, VerifyArgs, parse_day_selection};
+    use crate::input::InputSource;
+    use crate::output::OutputFormat;
+
+    const REGISTERED: &[u32] = &[1, 2, 3, 19, 21, 22, 24, 25];
+
+    #[test]
+    fn parses_single_days_ranges_and_lists() {
+        assert_eq!(
+            Ok(DaySelection(vec![21..=21, 1..=5])),
+            parse_day_selection("21,1-5")
+        );
+        assert_eq!(
+            Ok(DaySelection(vec![1..=1, 3..=3, 20..=25])),
+            parse_day_selection("1,3,20-25")
+        );
+    }
+
+    #[test]
+    fn rejects_bad_days() {
+        parse_day_selection("0").unwrap_err();
+        parse_day_selection("26").unwrap_err();
+        parse_day_selection("5-1").unwrap_err();
+        parse_day_selection("five").unwrap_err();
+    }
+
+    #[test]
+    fn no_selection_runs_everything() {
+        let cli = Cli::parse_from(["aoc"]);
+
+        assert_eq!(REGISTERED, cli.resolve_days(REGISTERED).unwrap());
+        assert!(cli.runs_part(1));
+        assert!(cli.runs_part(2));
+    }
+
+    #[test]
+    fn ranges_skip_missing_days() {
+        let cli = Cli::parse_from(["aoc", "--day", "19-21", "--day", "2", "--part", "2"]);
+
+        assert_eq!(vec![2, 19, 21], cli.resolve_days(REGISTERED).unwrap());
+        assert!(!cli.runs_part(1));
+        assert!(cli.runs_part(2));
+    }
+
+    #[test]
+    fn single_missing_day_is_an_error() {
+        let cli = Cli::parse_from(["aoc", "--day", "20"]);
+
+        assert_eq!(
+            "Day 20 has no registered solution",
+            cli.resolve_days(REGISTERED).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn embedded_input_by_default() {
+        let cli = Cli::parse_from(["aoc", "--day", "1-2"]);
+
+        assert_eq!(
+            vec![InputSource::Embedded, InputSource::Embedded],
+            cli.input_sources(&[1, 2]).unwrap()
+        );
+    }
+
+    #[test]
+    fn inputs_dir_is_per_day() {
+        let cli = Cli::parse_from(["aoc", "--day", "1,21", "--inputs-dir", "inputs"]);
+
+        assert_eq!(
+            vec![
+                InputSource::File(PathBuf::from("inputs/day_01.txt")),
+                InputSource::File(PathBuf::from("inputs/day_21.txt"))
+            ],
+            cli.input_sources(&[1, 21]).unwrap()
+        );
+    }
+
+    #[test]
+    fn input_needs_a_single_day() {
+        let cli = Cli::parse_from(["aoc", "--day", "21", "--input", "-"]);
+
+        assert_eq!(vec![InputSource::Stdin], cli.input_sources(&[21]).unwrap());
+
+        let cli = Cli::parse_from(["aoc", "--day", "1-2", "--input", "my_input.txt"]);
+
+        assert_eq!(
+            "`--input` needs exactly one day, but 2 were selected",
+            cli.input_sources(&[1, 2]).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn all_conflicts_with_day() {
+        Cli::try_parse_from(["aoc", "--all", "--day", "1"]).unwrap_err();
+    }
+
+    #[test]
+    fn jobs_defaults_to_one() {
+        assert_eq!(NonZeroUsize::MIN, Cli::parse_from(["aoc"]).jobs);
+        assert_eq!(
+            NonZeroUsize::new(8),
+            Some(Cli::parse_from(["aoc", "verify", "answers.toml", "-j", "8"]).jobs)
+        );
+
+        Cli::try_parse_from(["aoc", "--jobs", "0"]).unwrap_err();
+    }
+
+    #[test]
+    fn timeout_in_seconds() {
+        assert_eq!(Duration::from_secs(60), Cli::parse_from(["aoc"]).timeout);
+        assert_eq!(
+            Duration::from_millis(1500),
+            Cli::parse_from(["aoc", "--timeout", "1.5"]).timeout
+        );
+        assert_eq!(
+            Duration::ZERO,
+            Cli::parse_from(["aoc", "bench", "--timeout", "0"]).timeout
+        );
+
+        Cli::try_parse_from(["aoc", "--timeout", "-1"]).unwrap_err();
+        Cli::try_parse_from(["aoc", "--timeout", "soon"]).unwrap_err();
+    }
+
+    #[test]
+    fn verbose_counts() {
+        assert_eq!(0, Cli::parse_from(["aoc"]).verbose);
+        assert_eq!(2, Cli::parse_from(["aoc", "-vv", "bench"]).verbose);
+    }
+
+    #[test]
+    fn output_defaults_to_text() {
+        assert_eq!(OutputFormat::Text, Cli::parse_from(["aoc"]).output);
+        assert_eq!(
+            OutputFormat::Json,
+            Cli::parse_from(["aoc", "--output", "json"]).output
+        );
+    }
+
+    #[test]
+    fn bench_takes_the_global_selection() {
+        let cli = Cli::parse_from(["aoc", "bench", "--day", "1-2", "-n", "5"]);
+
+        let Some(Command::Bench(ref bench)) = cli.command else {
+            panic!("expected the bench command");
+        };
+
+        assert_eq!(&BenchArgs { runs: 5, warmup: 1 }, bench);
+        assert_eq!(vec![1, 2], cli.resolve_days(REGISTERED).unwrap());
+
+        Cli::try_parse_from(["aoc", "bench", "--runs", "0"]).unwrap_err();
+    }
+
+    #[test]
+    fn verify_takes_a_manifest() {
+        let cli = Cli::parse_from(["aoc", "verify", "answers.toml", "--part", "1"]);
+
+        let Some(Command::Verify(ref verify)) = cli.command else {
+            panic!("expected the verify command");
+        };
+
+        assert_eq!(
+            &VerifyArgs {
+                manifest: PathBuf::from("answers.toml")
+            },
+            verify
+        );
+        assert!(!cli.runs_part(2));
+
+        Cli::try_parse_from(["aoc", "verify"]).unwrap_err();
+    }
+}
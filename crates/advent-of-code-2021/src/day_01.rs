@@ -1,54 +1,157 @@
-use crate::shared::{Day, PartSolution};
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
 
-fn parse_lines(lines: &[&str]) -> Vec<u32> {
-    lines.iter().map(|s| s.parse::<u32>().unwrap()).collect()
+use shared::parse::{ParseError, parse_token};
+
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_01/input.txt");
+
+fn parse_lines(lines: &[&str]) -> Result<Vec<u32>, ParseError> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(line_index, line)| parse_token(line_index, line, line))
+        .collect()
+}
+
+/// The sum of every `size` readings in a row, one window after the other, read as they come in.
+#[derive(Clone, Debug)]
+pub struct WindowSums<I> {
+    readings: I,
+    window: VecDeque<u32>,
+    size: usize,
+    sum: u64,
 }
 
-pub fn count_increments(list: &[u32]) -> u32 {
-    let mut count = 0;
-    for i in list.array_windows::<2>() {
-        if i[1] > i[0] {
-            count += 1;
+impl<I: Iterator<Item = u32>> WindowSums<I> {
+    pub fn new<R: IntoIterator<IntoIter = I>>(readings: R, size: NonZeroUsize) -> Self {
+        Self {
+            readings: readings.into_iter(),
+            window: VecDeque::with_capacity(size.get()),
+            size: size.get(),
+            sum: 0,
         }
     }
+}
+
+impl<I: Iterator<Item = u32>> Iterator for WindowSums<I> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let reading = self.readings.next()?;
+
+            self.window.push_back(reading);
+            self.sum += u64::from(reading);
+
+            if self.window.len() > self.size {
+                let oldest = self.window.pop_front().unwrap_or_default();
 
-    count
+                self.sum -= u64::from(oldest);
+            }
+
+            // nothing until the first window is full
+            if self.window.len() == self.size {
+                return Some(self.sum);
+            }
+        }
+    }
+}
+
+/// How the sums of the windows changed from one window to the next.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SonarReport {
+    pub increases: u64,
+    pub decreases: u64,
+    pub unchanged: u64,
+    /// The most increases in a row.
+    pub longest_increasing_run: u64,
 }
 
-pub fn count_window_of_3_increments(list: &[u32]) -> u32 {
-    let mut count = 0;
-    let mut previous_window: u32 = 0;
+impl SonarReport {
+    /// Compares every window of `window` readings with the one before it. With fewer readings
+    /// than that there's nothing to compare, and everything is 0.
+    #[must_use]
+    pub fn analyze<R: IntoIterator<Item = u32>>(readings: R, window: NonZeroUsize) -> Self {
+        let mut report = Self::default();
 
-    for i in list.array_windows::<3>() {
-        let current_window: u32 = i.iter().sum();
+        let mut previous = None;
+        let mut run = 0;
 
-        if current_window > previous_window {
-            count += 1;
+        for sum in WindowSums::new(readings, window) {
+            if let Some(previous) = previous {
+                match sum.cmp(&previous) {
+                    Ordering::Greater => {
+                        report.increases += 1;
+                        run += 1;
+                    },
+                    Ordering::Less => {
+                        report.decreases += 1;
+                        run = 0;
+                    },
+                    Ordering::Equal => {
+                        report.unchanged += 1;
+                        run = 0;
+                    },
+                }
+
+                report.longest_increasing_run = report.longest_increasing_run.max(run);
+            }
+
+            previous = Some(sum);
         }
 
-        previous_window = current_window;
+        report
     }
+}
+
+fn analyze_and_log(readings: &[u32], window: NonZeroUsize) -> SonarReport {
+    let report = SonarReport::analyze(readings.iter().copied(), window);
 
-    count - 1
+    ::tracing::event!(
+        ::tracing::Level::DEBUG,
+        "Windows of {}: {} increases, {} decreases, {} unchanged, at most {} increases in a row",
+        window,
+        report.increases,
+        report.decreases,
+        report.unchanged,
+        report.longest_increasing_run
+    );
+
+    report
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_01/input.txt").lines().collect();
+    type Parsed = Vec<u32>;
 
-        let numbers = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::U32(count_increments(&numbers))
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_01/input.txt").lines().collect();
-
-        let numbers = parse_lines(&lines);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U64(
+            analyze_and_log(parsed, NonZeroUsize::MIN).increases,
+        ))
+    }
 
-        PartSolution::U32(count_window_of_3_increments(&numbers))
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U64(
+            analyze_and_log(parsed, NonZeroUsize::new(3).unwrap()).increases,
+        ))
     }
 }
 
@@ -59,45 +162,119 @@ mod test {
     }
 
     mod part_1 {
+        use std::num::NonZeroUsize;
+
         use pretty_assertions::assert_eq;
 
         use crate::day_01::test::get_example;
-        use crate::day_01::{Solution, count_increments, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_01::{INPUT, Solution, SonarReport, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(1722), (Solution {}).part_1());
+            assert_eq!(
+                PartSolution::U64(1722),
+                solve(&Solution {}, 1, INPUT).unwrap()
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let depth_measurements = parse_lines(&lines);
+            let depth_measurements = parse_lines(&lines).unwrap();
 
-            assert_eq!(count_increments(&depth_measurements), 7);
+            assert_eq!(
+                SonarReport {
+                    increases: 7,
+                    decreases: 2,
+                    unchanged: 0,
+                    longest_increasing_run: 3
+                },
+                SonarReport::analyze(depth_measurements, NonZeroUsize::MIN)
+            );
         }
     }
     mod part_2 {
+        use std::num::NonZeroUsize;
+
         use pretty_assertions::assert_eq;
 
         use crate::day_01::test::get_example;
-        use crate::day_01::{Solution, count_window_of_3_increments, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_01::{INPUT, Solution, SonarReport, WindowSums, parse_lines};
+        use crate::shared::{PartSolution, solve};
+
+        fn window(size: usize) -> NonZeroUsize {
+            NonZeroUsize::new(size).unwrap()
+        }
 
         #[test]
         fn outcome() {
-            assert_eq!(PartSolution::U32(1748), (Solution {}).part_2());
+            assert_eq!(
+                PartSolution::U64(1748),
+                solve(&Solution {}, 2, INPUT).unwrap()
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let depth_measurements = parse_lines(&lines);
+            let depth_measurements = parse_lines(&lines).unwrap();
+
+            assert_eq!(
+                SonarReport {
+                    increases: 5,
+                    decreases: 1,
+                    unchanged: 1,
+                    longest_increasing_run: 4
+                },
+                SonarReport::analyze(depth_measurements, window(3))
+            );
+        }
+
+        #[test]
+        fn window_sums() {
+            let depth_measurements = parse_lines(&get_example()).unwrap();
+
+            assert_eq!(
+                vec![607, 618, 618, 617, 647, 716, 769, 792],
+                WindowSums::new(depth_measurements, window(3)).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn fewer_readings_than_a_window() {
+            assert_eq!(
+                SonarReport::default(),
+                SonarReport::analyze([199, 200], window(3))
+            );
+            assert_eq!(
+                SonarReport::default(),
+                SonarReport::analyze([199, 200, 208], window(3))
+            );
+            assert_eq!(
+                SonarReport::default(),
+                SonarReport::analyze([], NonZeroUsize::MIN)
+            );
+        }
+
+        #[test]
+        fn streams_readings() {
+            // never collected, the windows only hold the last 1000 readings. A window goes up
+            // by 1000 when the reading coming in is 1000 above the one going out, and down by
+            // 1000 when the reading coming in has wrapped around
+            let readings = (0..1_000_000_u32).map(|reading| reading % 2000);
 
-            assert_eq!(count_window_of_3_increments(&depth_measurements), 5);
+            assert_eq!(
+                SonarReport {
+                    increases: 500_000,
+                    decreases: 499_000,
+                    unchanged: 0,
+                    longest_increasing_run: 1000
+                },
+                SonarReport::analyze(readings, window(1000))
+            );
         }
     }
 }
@@ -1,82 +1,288 @@
-use crate::shared::{Day, PartSolution};
+use std::fmt::{self, Display};
 
-fn determine_direction(directions: &[Direction]) -> (u32, u32) {
-    let mut forward = 0;
-    let mut depth = 0;
+use hashbrown::HashMap;
+use shared::parse::{ParseError, parse_token};
 
-    for direction in directions {
-        match *direction {
-            Direction::Up(x) => depth -= x,
-            Direction::Down(x) => depth += x,
-            Direction::Forward(x) => forward += x,
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_02/input.txt");
+
+/// Where the submarine is, and which way it's pointing. Depth goes up going down, and can go
+/// negative when we surface too far.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Submarine {
+    pub position: i64,
+    pub depth: i64,
+    pub aim: i64,
+}
+
+/// What a command does to the submarine, given its amount. `None` when the submarine would go
+/// further than an `i64` reaches.
+pub type Effect = fn(Submarine, i64) -> Option<Submarine>;
+
+/// A line of the course, not yet tied to what it does.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Command {
+    /// `None` when the command didn't come from the input.
+    line_index: Option<usize>,
+    name: String,
+    amount: i64,
+}
+
+impl Command {
+    #[must_use]
+    pub fn new(name: &str, amount: i64) -> Self {
+        Self {
+            line_index: None,
+            name: name.to_owned(),
+            amount,
         }
     }
-    (forward, depth)
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
 }
 
-fn determine_direction_2(directions: &[Direction]) -> (u32, u32) {
-    let mut forward = 0;
-    let mut depth = 0;
-    let mut aim = 0;
-
-    for direction in directions {
-        match *direction {
-            Direction::Up(x) => aim -= x,
-            Direction::Down(x) => aim += x,
-            Direction::Forward(x) => {
-                forward += x;
-                depth += aim * x;
-            },
+impl Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{} {}`", self.name, self.amount)?;
+
+        match self.line_index {
+            Some(line_index) => write!(f, " on line {}", line_index + 1),
+            None => Ok(()),
         }
     }
-    (forward, depth)
 }
 
-enum Direction {
-    Up(u32),
-    Down(u32),
-    Forward(u32),
+/// The commands a submarine knows, by name.
+#[derive(Clone, Debug, Default)]
+pub struct InstructionSet {
+    effects: HashMap<String, Effect>,
 }
 
-fn parse_lines(lines: &[&str]) -> Vec<Direction> {
-    let mut instructions = Vec::new();
-    for line in lines {
-        let pieces: Vec<&str> = line.split(' ').collect();
+impl InstructionSet {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How we first thought the commands worked: `up` and `down` change the depth.
+    #[must_use]
+    pub fn part_1() -> Self {
+        Self::new()
+            .with("forward", |submarine, amount| {
+                Some(Submarine {
+                    position: submarine.position.checked_add(amount)?,
+                    ..submarine
+                })
+            })
+            .with("down", |submarine, amount| {
+                Some(Submarine {
+                    depth: submarine.depth.checked_add(amount)?,
+                    ..submarine
+                })
+            })
+            .with("up", |submarine, amount| {
+                Some(Submarine {
+                    depth: submarine.depth.checked_sub(amount)?,
+                    ..submarine
+                })
+            })
+    }
+
+    /// How the manual says they work: `up` and `down` change the aim, and `forward` dives along
+    /// it.
+    #[must_use]
+    pub fn part_2() -> Self {
+        Self::new()
+            .with("forward", |submarine, amount| {
+                Some(Submarine {
+                    position: submarine.position.checked_add(amount)?,
+                    depth: submarine
+                        .depth
+                        .checked_add(submarine.aim.checked_mul(amount)?)?,
+                    ..submarine
+                })
+            })
+            .with("down", |submarine, amount| {
+                Some(Submarine {
+                    aim: submarine.aim.checked_add(amount)?,
+                    ..submarine
+                })
+            })
+            .with("up", |submarine, amount| {
+                Some(Submarine {
+                    aim: submarine.aim.checked_sub(amount)?,
+                    ..submarine
+                })
+            })
+    }
+
+    /// Adds the command `name`, or changes what it does when we already know it.
+    #[must_use]
+    pub fn with(mut self, name: &str, effect: Effect) -> Self {
+        self.effects.insert(name.to_owned(), effect);
+        self
+    }
+
+    /// Checks that we know every command in `commands`, so the error points at the line. Made
+    /// with `Command::new`, a command has no line, and the error points at the first one.
+    ///
+    /// # Errors
+    ///
+    /// At the first command we don't know.
+    pub fn check(&self, commands: &[Command]) -> Result<(), ParseError> {
+        for command in commands {
+            if !self.effects.contains_key(&command.name) {
+                return Err(ParseError::new(
+                    command.line_index.unwrap_or_default(),
+                    0,
+                    format!("unknown command `{}`", command.name),
+                ));
+            }
+        }
+
+        Ok(())
+    }
 
-        let instruction = match (pieces[0], pieces[1].parse::<u32>().unwrap()) {
-            ("forward", x) => Direction::Forward(x),
-            ("down", x) => Direction::Down(x),
-            ("up", x) => Direction::Up(x),
-            _ => panic!("OMG what did you give me?"),
-        };
+    /// Follows `commands` from the surface, calling `on_step` with every command and where it
+    /// took us.
+    ///
+    /// # Errors
+    ///
+    /// A `DayError::NoSolution` when a command isn't in this set, or takes the submarine further
+    /// than an `i64` reaches. The commands before it have been followed.
+    pub fn run<F>(&self, commands: &[Command], mut on_step: F) -> Result<Submarine, DayError>
+    where
+        F: FnMut(&Command, &Submarine),
+    {
+        let mut submarine = Submarine::default();
+
+        for command in commands {
+            let Some(effect) = self.effects.get(&command.name) else {
+                return Err(DayError::NoSolution(format!(
+                    "{} is not a command we know",
+                    command
+                )));
+            };
+
+            submarine = effect(submarine, command.amount).ok_or_else(|| {
+                DayError::NoSolution(format!("{} takes the submarine too far", command))
+            })?;
+
+            on_step(command, &submarine);
+        }
 
-        instructions.push(instruction);
+        Ok(submarine)
     }
 
-    instructions
+    /// Where every command took us, in order.
+    ///
+    /// # Errors
+    ///
+    /// Like `run`.
+    pub fn trace(&self, commands: &[Command]) -> Result<Vec<Submarine>, DayError> {
+        let mut path = Vec::with_capacity(commands.len());
+
+        self.run(commands, |_, &submarine| path.push(submarine))?;
+
+        Ok(path)
+    }
+}
+
+/// Every line is `<command> <amount>`, or just `<command>`, which gets an amount of 0.
+fn parse_lines(lines: &[&str]) -> Result<Vec<Command>, ParseError> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(line_index, line)| {
+            let (name, amount) = match line.split_once(' ') {
+                Some((name, amount)) => (name, parse_token(line_index, line, amount)?),
+                None => (*line, 0),
+            };
+
+            if name.is_empty() {
+                return Err(ParseError::new(
+                    line_index,
+                    0,
+                    format!("expected `<command> <amount>`, got `{}`", line),
+                ));
+            }
+
+            Ok(Command {
+                line_index: Some(line_index),
+                name: name.to_owned(),
+                amount,
+            })
+        })
+        .collect()
+}
+
+fn follow(instruction_set: &InstructionSet, commands: &[Command]) -> Result<i64, DayError> {
+    let submarine = instruction_set.run(commands, |command, submarine| {
+        ::tracing::event!(
+            ::tracing::Level::TRACE,
+            "{} {}: {:?}",
+            command.name,
+            command.amount,
+            submarine
+        );
+    })?;
+
+    submarine
+        .position
+        .checked_mul(submarine.depth)
+        .ok_or_else(|| {
+            DayError::NoSolution(format!(
+                "{} times {} doesn't fit in an i64",
+                submarine.position, submarine.depth
+            ))
+        })
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_02/input.txt").lines().collect();
+    type Parsed = Vec<Command>;
 
-        let instructions = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let (horizontal_position, depth) = determine_direction(&instructions);
-
-        PartSolution::U32(horizontal_position * depth)
-    }
+        let commands = parse_lines(&lines)?;
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_02/input.txt").lines().collect();
+        InstructionSet::part_1().check(&commands)?;
+        InstructionSet::part_2().check(&commands)?;
 
-        let instructions = parse_lines(&lines);
+        Ok(commands)
+    }
 
-        let (horizontal_position, depth) = determine_direction_2(&instructions);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::I64(follow(
+            &InstructionSet::part_1(),
+            parsed,
+        )?))
+    }
 
-        PartSolution::U32(horizontal_position * depth)
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::I64(follow(
+            &InstructionSet::part_2(),
+            parsed,
+        )?))
     }
 }
 
@@ -90,48 +296,208 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_02::test::get_example;
-        use crate::day_02::{Solution, determine_direction, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_02::{Command, INPUT, InstructionSet, Solution, Submarine, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(2_070_300));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::I64(2_070_300)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let instructions = parse_lines(&lines);
+            let commands = parse_lines(&lines).unwrap();
+
+            let submarine = InstructionSet::part_1().run(&commands, |_, _| ()).unwrap();
+
+            assert_eq!(submarine.position, 15);
+            assert_eq!(submarine.depth, 10);
+        }
+
+        #[test]
+        fn above_the_surface() {
+            let commands = parse_lines(&["down 2", "up 5"]).unwrap();
+
+            assert_eq!(
+                Ok(Submarine {
+                    position: 0,
+                    depth: -3,
+                    aim: 0
+                }),
+                InstructionSet::part_1().run(&commands, |_, _| ())
+            );
+        }
+
+        #[test]
+        fn unknown_command() {
+            assert_eq!(
+                "Invalid input: line 2, column 1: unknown command `sideways`",
+                solve(&Solution {}, 1, "forward 5\nsideways 3")
+                    .unwrap_err()
+                    .to_string()
+            );
+
+            let commands = [Command::new("forward", 5), Command::new("sideways", 3)];
+
+            assert_eq!(
+                "No solution: `sideways 3` is not a command we know",
+                InstructionSet::part_1()
+                    .run(&commands, |_, _| ())
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
 
-            let (horizontal_position, depth) = determine_direction(&instructions);
+        #[test]
+        fn too_far() {
+            assert_eq!(
+                "No solution: `down 1` on line 2 takes the submarine too far",
+                solve(&Solution {}, 1, "down 9223372036854775807\ndown 1")
+                    .unwrap_err()
+                    .to_string()
+            );
+            assert_eq!(
+                "No solution: 2 times 9223372036854775807 doesn't fit in an i64",
+                solve(&Solution {}, 1, "down 9223372036854775807\nforward 2")
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
 
-            assert_eq!(horizontal_position, 15);
-            assert_eq!(depth, 10);
+        #[test]
+        fn invalid_amount() {
+            assert_eq!(
+                "line 1, column 9: could not parse `far`: invalid digit found in string",
+                parse_lines(&["forward far"]).unwrap_err().to_string()
+            );
         }
     }
+
     mod part_2 {
         use pretty_assertions::assert_eq;
 
         use crate::day_02::test::get_example;
-        use crate::day_02::{Solution, determine_direction_2, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_02::{Command, INPUT, InstructionSet, Solution, Submarine, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(2_078_985_210));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::I64(2_078_985_210)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let instructions = parse_lines(&lines);
+            let commands = parse_lines(&lines).unwrap();
+
+            let submarine = InstructionSet::part_2().run(&commands, |_, _| ()).unwrap();
+
+            assert_eq!(submarine.position, 15);
+            assert_eq!(submarine.depth, 60);
+        }
 
-            let (horizontal_position, depth) = determine_direction_2(&instructions);
+        #[test]
+        fn trace() {
+            let commands = parse_lines(&get_example()[..3]).unwrap();
+
+            assert_eq!(
+                Ok(vec![
+                    Submarine {
+                        position: 5,
+                        depth: 0,
+                        aim: 0
+                    },
+                    Submarine {
+                        position: 5,
+                        depth: 0,
+                        aim: 5
+                    },
+                    Submarine {
+                        position: 13,
+                        depth: 40,
+                        aim: 5
+                    },
+                ]),
+                InstructionSet::part_2().trace(&commands)
+            );
+        }
 
-            assert_eq!(horizontal_position, 15);
-            assert_eq!(depth, 60);
+        #[test]
+        fn too_far() {
+            assert_eq!(
+                "No solution: `forward 2` on line 2 takes the submarine too far",
+                solve(&Solution {}, 2, "down 9223372036854775807\nforward 2")
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
+
+        #[test]
+        fn extra_commands() {
+            let instruction_set = InstructionSet::part_2()
+                .with("backward", |submarine, amount| {
+                    Some(Submarine {
+                        position: submarine.position.checked_sub(amount)?,
+                        ..submarine
+                    })
+                })
+                .with("reset", |_, _| Some(Submarine::default()));
+
+            let commands = parse_lines(&["down 2", "forward 3", "backward 1"]).unwrap();
+
+            assert_eq!(
+                Ok(Submarine {
+                    position: 2,
+                    depth: 6,
+                    aim: 2
+                }),
+                instruction_set.run(&commands, |_, _| ())
+            );
+
+            let commands = parse_lines(&["down 2", "forward 3", "reset", "forward 1"]).unwrap();
+
+            assert_eq!(
+                Ok(Submarine {
+                    position: 1,
+                    depth: 0,
+                    aim: 0
+                }),
+                instruction_set.run(&commands, |_, _| ())
+            );
+
+            // the part 2 set doesn't know them
+            assert_eq!(
+                "No solution: `reset 0` on line 3 is not a command we know",
+                InstructionSet::part_2()
+                    .run(&commands, |_, _| ())
+                    .unwrap_err()
+                    .to_string()
+            );
+            assert_eq!(
+                "line 3, column 1: unknown command `reset`",
+                InstructionSet::part_2()
+                    .check(&commands)
+                    .unwrap_err()
+                    .to_string()
+            );
+            assert_eq!(Ok(()), instruction_set.check(&commands));
+            assert_eq!(
+                Ok(Submarine {
+                    position: -4,
+                    depth: 0,
+                    aim: 0
+                }),
+                instruction_set.run(&[Command::new("backward", 4)], |_, _| ())
+            );
         }
     }
 }
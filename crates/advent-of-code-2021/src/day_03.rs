@@ -1,122 +1,185 @@
-use crate::shared::{Day, PartSolution};
-fn parse_diagnostic_report(diagnostic_report_lines: &[u32], bits_to_consider: usize) -> (u32, u32) {
-    let mut set_count_by_column: Vec<u32> = vec![0; bits_to_consider];
-
-    for line in diagnostic_report_lines {
-        (0..bits_to_consider)
-            .filter(|i| ((line >> i) & 0b0001) == 1)
-            .for_each(|i| {
-                set_count_by_column[i] += 1;
-            });
-    }
+use shared::bitset::BitSet;
+use shared::parse::ParseError;
 
-    let half_amount_of_lines = u32::try_from(diagnostic_report_lines.len()).unwrap() / 2;
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-    let mut gamma = 0;
-    let mut epsilon = 0;
+pub const INPUT: &str = include_str!("day_03/input.txt");
 
-    (0..bits_to_consider).for_each(|i| {
-        if set_count_by_column[i] > half_amount_of_lines {
-            gamma |= 1 << i;
-        } else {
-            epsilon |= 1 << i;
-        }
-    });
+/// The lines of the diagnostic report, all of them `width` bits wide.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct DiagnosticReport {
+    width: usize,
+    lines: Vec<BitSet>,
+}
 
-    (gamma, epsilon)
+impl DiagnosticReport {
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn lines(&self) -> &[BitSet] {
+        &self.lines
+    }
 }
 
-enum Common {
-    Least,
-    Most,
+/// How many lines have a bit set at some column, and how many don't.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BitCounts {
+    pub ones: usize,
+    pub zeros: usize,
 }
 
-fn find_common(lines: &[u32], bit_index: usize, common: &Common) -> usize {
-    let mut bits = Vec::new();
+impl BitCounts {
+    fn count<'l, I: IntoIterator<Item = &'l BitSet>>(lines: I, column: usize) -> Self {
+        let mut counts = Self { ones: 0, zeros: 0 };
 
-    for line in lines {
-        bits.push((line >> bit_index) & 0b01);
-    }
+        for line in lines {
+            if line.get(column).unwrap_or_default() {
+                counts.ones += 1;
+            } else {
+                counts.zeros += 1;
+            }
+        }
 
-    let count_of_1 = bits.iter().filter(|x| **x == 1).count();
-    let count_of_0 = bits.iter().filter(|x| **x == 0).count();
+        counts
+    }
 
-    match *common {
-        Common::Least => usize::from(count_of_1 < count_of_0),
-        Common::Most => usize::from(count_of_1 >= count_of_0),
+    /// The oxygen generator criterion: keep the most common bit, `1` on a tie.
+    #[must_use]
+    pub fn most_common(self) -> bool {
+        self.ones >= self.zeros
     }
-}
 
-fn move_and_reduce(
-    filtered_lines: &[u32],
-    bits_to_consider: usize,
-    bit_index: usize,
-    common: &Common,
-) -> Vec<u32> {
-    if filtered_lines.len() == 1 {
-        return filtered_lines.to_vec();
+    /// The CO2 scrubber criterion: keep the least common bit, `0` on a tie.
+    #[must_use]
+    pub fn least_common(self) -> bool {
+        self.ones < self.zeros
     }
+}
 
-    let mut new_set: Vec<u32> = Vec::new();
+/// Every column's most common bit, `0` on a tie. The epsilon rate is its complement.
+#[must_use]
+pub fn gamma_rate(report: &DiagnosticReport) -> BitSet {
+    (0..report.width)
+        .map(|column| {
+            let counts = BitCounts::count(&report.lines, column);
 
-    let most_or_least_common =
-        find_common(filtered_lines, bits_to_consider - bit_index - 1, common);
+            counts.ones > counts.zeros
+        })
+        .collect()
+}
 
-    for line in filtered_lines {
-        if ((line >> (bits_to_consider - bit_index - 1)) & 0b01) == most_or_least_common as u32 {
-            new_set.push(*line);
+/// Goes through the columns, keeping only the lines whose bit there is the one `criterion` picks
+/// from the counts of the lines still left, until one line is left. `None` when no line is.
+#[must_use]
+pub fn filter_by_criterion<F>(report: &DiagnosticReport, criterion: F) -> Option<&BitSet>
+where
+    F: Fn(BitCounts) -> bool,
+{
+    let mut left: Vec<&BitSet> = report.lines.iter().collect();
+
+    for column in 0..report.width {
+        if left.len() <= 1 {
+            break;
         }
+
+        let keep = criterion(BitCounts::count(left.iter().copied(), column));
+
+        left.retain(|line| line.get(column) == Some(keep));
     }
 
-    move_and_reduce(&new_set, bits_to_consider, bit_index + 1, common)
+    // with all columns done, what's left is the same line
+    left.first().copied()
 }
 
-fn parse_diagnostic_report_2(
-    diagnostic_report_lines: &[u32],
-    bits_to_consider: usize,
-) -> (u32, u32) {
-    let oxygen = move_and_reduce(diagnostic_report_lines, bits_to_consider, 0, &Common::Most);
-    let co2 = move_and_reduce(diagnostic_report_lines, bits_to_consider, 0, &Common::Least);
+fn to_number(rating: &BitSet) -> Result<u64, DayError> {
+    rating
+        .to_u64()
+        .ok_or_else(|| DayError::NoSolution(format!("`{}` doesn't fit in 64 bits", rating)))
+}
 
-    (oxygen[0], co2[0])
+fn multiply(first: &BitSet, second: &BitSet) -> Result<u64, DayError> {
+    to_number(first)?
+        .checked_mul(to_number(second)?)
+        .ok_or_else(|| {
+            DayError::NoSolution(format!(
+                "`{}` times `{}` doesn't fit in 64 bits",
+                first, second
+            ))
+        })
 }
 
-fn parse_lines(lines: &[&str]) -> (Vec<u32>, usize) {
-    let mut parsed = Vec::new();
+fn parse_lines(lines: &[&str]) -> Result<DiagnosticReport, ParseError> {
+    let width = lines.first().map_or(0, |line| line.len());
 
-    for line in lines {
-        parsed.push(u32::from_str_radix(line, 2).unwrap());
-    }
+    let mut parsed = Vec::with_capacity(lines.len());
 
-    let bits_to_consider = match lines.first() {
-        Some(l) => l.len(),
-        None => 0,
-    };
+    for (line_index, line) in lines.iter().enumerate() {
+        if let Some(column_index) = line.chars().position(|c| c != '0' && c != '1') {
+            return Err(ParseError::new(
+                line_index,
+                column_index,
+                "expected only `0` and `1`".to_owned(),
+            ));
+        }
 
-    (parsed, bits_to_consider)
+        if line.len() != width {
+            return Err(ParseError::new(
+                line_index,
+                line.len().min(width),
+                format!(
+                    "expected {} bits like the first line, got {}",
+                    width,
+                    line.len()
+                ),
+            ));
+        }
+
+        parsed.push(line.chars().map(|c| c == '1').collect());
+    }
+
+    Ok(DiagnosticReport {
+        width,
+        lines: parsed,
+    })
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_03/input.txt").lines().collect();
-
-        let (diagnostic_report_lines, bits_to_consider) = parse_lines(&lines);
+    type Parsed = DiagnosticReport;
 
-        let (gamma, epsilon) = parse_diagnostic_report(&diagnostic_report_lines, bits_to_consider);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::U32(gamma * epsilon)
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_03/input.txt").lines().collect();
-
-        let (diagnostic_report_lines, bits_to_consider) = parse_lines(&lines);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let gamma = gamma_rate(parsed);
 
-        let (oxygen, co2) = parse_diagnostic_report_2(&diagnostic_report_lines, bits_to_consider);
+        Ok(PartSolution::U64(multiply(&gamma, &gamma.complement())?))
+    }
 
-        PartSolution::U32(oxygen * co2)
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let (Some(oxygen), Some(co2)) = (
+            filter_by_criterion(parsed, BitCounts::most_common),
+            filter_by_criterion(parsed, BitCounts::least_common),
+        ) else {
+            return Err(DayError::NoSolution("the report is empty".to_owned()));
+        };
+
+        Ok(PartSolution::U64(multiply(oxygen, co2)?))
     }
 }
 
@@ -130,47 +193,139 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_03::test::get_example;
-        use crate::day_03::{Solution, parse_diagnostic_report, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_03::{INPUT, Solution, gamma_rate, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(4_160_394));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U64(4_160_394)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let (instructions, bits_to_consider) = parse_lines(&lines);
+            let report = parse_lines(&lines).unwrap();
 
-            let (gamma, epsilon) = parse_diagnostic_report(&instructions, bits_to_consider);
+            let gamma = gamma_rate(&report);
 
-            assert_eq!(gamma, 22);
-            assert_eq!(epsilon, 9);
+            assert_eq!(gamma.to_u64(), Some(22));
+            assert_eq!(gamma.complement().to_u64(), Some(9));
+        }
+
+        #[test]
+        fn wider_than_64_bits() {
+            let lines = [
+                format!("{}{}", "1".repeat(60), "0".repeat(40)),
+                format!("{}{}", "1".repeat(60), "1".repeat(40)),
+                format!("{}{}", "0".repeat(60), "1".repeat(40)),
+            ];
+
+            let report = parse_lines(&lines.each_ref().map(String::as_str)).unwrap();
+
+            assert_eq!(100, report.width());
+
+            let gamma = gamma_rate(&report);
+
+            assert_eq!("1".repeat(100), gamma.to_string());
+            assert_eq!(None, gamma.to_u64());
+            assert_eq!(Some(0), gamma.complement().to_u64());
+        }
+
+        #[test]
+        fn different_widths() {
+            assert_eq!(
+                "line 2, column 4: expected 5 bits like the first line, got 3",
+                parse_lines(&["10110", "101"]).unwrap_err().to_string()
+            );
+            assert_eq!(
+                "line 3, column 6: expected 5 bits like the first line, got 6",
+                parse_lines(&["10110", "10111", "101100"])
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
+
+        #[test]
+        fn not_binary() {
+            assert_eq!(
+                "line 1, column 3: expected only `0` and `1`",
+                parse_lines(&["102"]).unwrap_err().to_string()
+            );
         }
     }
+
     mod part_2 {
         use pretty_assertions::assert_eq;
 
         use crate::day_03::test::get_example;
-        use crate::day_03::{Solution, parse_diagnostic_report_2, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_03::{BitCounts, INPUT, Solution, filter_by_criterion, parse_lines};
+        use crate::shared::{PartSolution, solve};
+
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(4_125_600));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U64(4_125_600)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let (instructions, bits_to_consider) = parse_lines(&lines);
+            let report = parse_lines(&lines).unwrap();
 
-            let (oxygen, co2) = parse_diagnostic_report_2(&instructions, bits_to_consider);
+            let oxygen = filter_by_criterion(&report, BitCounts::most_common).unwrap();
+            let co2 = filter_by_criterion(&report, BitCounts::least_common).unwrap();
 
-            assert_eq!(oxygen, 23);
-            assert_eq!(co2, 10);
+            assert_eq!(oxygen.to_u64(), Some(23));
+            assert_eq!(co2.to_u64(), Some(10));
+        }
+
+        #[test]
+        fn other_criteria() {
+            let report = parse_lines(&get_example()).unwrap();
+
+            let rating = |criterion: fn(BitCounts) -> bool| {
+                filter_by_criterion(&report, criterion)?.to_u64()
+            };
+
+            // the most common bit, but `0` on a tie
+            assert_eq!(Some(22), rating(|counts| counts.ones > counts.zeros));
+            // `1` only when at least two thirds have it
+            assert_eq!(
+                Some(4),
+                rating(|counts| 3 * counts.ones >= 2 * (counts.ones + counts.zeros))
+            );
+            assert_eq!(Some(30), rating(|_| true));
+        }
+
+        #[test]
+        fn nothing_left() {
+            let report = parse_lines(&["00", "01"]).unwrap();
+
+            // nothing starts with `1`
+            assert_eq!(None, filter_by_criterion(&report, |_| true));
+
+            // the same line twice
+            let report = parse_lines(&["01", "01"]).unwrap();
+
+            assert_eq!(
+                Some("01".to_owned()),
+                filter_by_criterion(&report, BitCounts::most_common).map(ToString::to_string)
+            );
+        }
+
+        #[test]
+        fn empty() {
+            assert_eq!(
+                "No solution: the report is empty",
+                solve(&Solution {}, 2, "").unwrap_err().to_string()
+            );
         }
     }
 }
@@ -1,210 +1,300 @@
-use std::cell::Cell;
+use std::convert::Infallible;
 
-use crate::shared::{Day, PartSolution};
+use hashbrown::HashMap;
+use shared::grid::{Coordinates, Grid};
+use shared::parse::{ParseError, blocks, get_line, parse_separated, parse_whitespace_separated};
 
-const PLAYFIELD_SIZE: usize = 5;
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-#[derive(PartialEq, Debug)]
-struct PlayfieldCell {
-    number: u32,
-    drawn: Cell<bool>,
+pub const INPUT: &str = include_str!("day_04/input.txt");
+
+/// The numbers in the order they're drawn, and the boards they're drawn for. Every board is
+/// square, and as big as the first one.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Game {
+    draws: Vec<u32>,
+    boards: Vec<Grid<u32>>,
 }
 
-impl PlayfieldCell {
-    fn new(number: u32) -> PlayfieldCell {
-        PlayfieldCell {
-            number,
-            drawn: Cell::new(false),
-        }
+impl Game {
+    #[must_use]
+    pub fn draws(&self) -> &[u32] {
+        &self.draws
+    }
+
+    #[must_use]
+    pub fn boards(&self) -> &[Grid<u32>] {
+        &self.boards
     }
 }
 
-#[derive(PartialEq, Debug)]
-struct Playfield {
-    inner: [[PlayfieldCell; PLAYFIELD_SIZE]; PLAYFIELD_SIZE],
-    don_t_consider_anymore: Cell<bool>,
+/// What counts as a win, besides a full row or column.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Rules {
+    /// A full diagonal, corner to corner.
+    pub diagonals: bool,
 }
 
-impl Playfield {
-    fn new(playfield: [[PlayfieldCell; PLAYFIELD_SIZE]; PLAYFIELD_SIZE]) -> Playfield {
-        Playfield {
-            inner: playfield,
-            don_t_consider_anymore: Cell::new(false),
-        }
-    }
+/// Board number `board` got a full line when `draw` was drawn. The score is the sum of its
+/// unmarked numbers at that point, times `draw`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Win {
+    pub board: usize,
+    pub draw: u32,
+    pub score: u64,
+}
 
-    fn mark_number(&self, number: u32) {
-        for row in &self.inner {
-            for c in row {
-                if c.number == number {
-                    c.drawn.set(true);
+/// A board while it's being played.
+#[derive(Clone, Debug)]
+struct Card {
+    numbers: Grid<u32>,
+    marked: Grid<bool>,
+    /// How many numbers are marked on every line: the rows, then the columns, then the diagonal
+    /// from the top left and the one from the top right.
+    line_marks: Vec<usize>,
+    unmarked_sum: u64,
+    won: bool,
+}
 
-                    // don't stop, we may have the same number in the same field
-                }
-            }
+impl Card {
+    fn new(numbers: Grid<u32>) -> Self {
+        Self {
+            marked: Grid::new(numbers.width(), numbers.height(), false),
+            line_marks: vec![0; numbers.height() + numbers.width() + 2],
+            unmarked_sum: numbers.iter().map(|(_, &number)| u64::from(number)).sum(),
+            numbers,
+            won: false,
         }
     }
 
-    fn is_winner(&self) -> bool {
-        // for row in &self.inner {
-        //     if row.iter().all(|f| f.drawn.get()) {
-        //         return true;
-        //     }
-        // }
+    /// The lines through `(row, column)`, with how many numbers it takes to fill them.
+    fn lines_through(&self, (row, column): Coordinates, rules: Rules) -> Vec<(usize, usize)> {
+        let width = self.numbers.width();
+        let height = self.numbers.height();
 
-        // by row
-        for row_index in 0..PLAYFIELD_SIZE {
-            let mut drawn_count: u32 = 0;
+        let mut lines = vec![(row, width), (height + column, height)];
 
-            for column_index in 0..PLAYFIELD_SIZE {
-                if self.inner[row_index][column_index].drawn.get() {
-                    drawn_count += 1;
-                }
+        // only square boards have a diagonal from corner to corner
+        if rules.diagonals && width == height {
+            if row == column {
+                lines.push((height + width, width));
             }
 
-            if drawn_count == PLAYFIELD_SIZE as u32 {
-                return true;
+            if row + column + 1 == width {
+                lines.push((height + width + 1, width));
             }
         }
 
-        // by column
-        for column_index in 0..PLAYFIELD_SIZE {
-            let mut drawn_count: u32 = 0;
+        lines
+    }
 
-            for row_index in 0..PLAYFIELD_SIZE {
-                if self.inner[row_index][column_index].drawn.get() {
-                    drawn_count += 1;
-                }
-            }
+    /// Marks the number at `coordinates`, returning whether that filled a line.
+    fn mark(&mut self, coordinates: Coordinates, rules: Rules) -> bool {
+        if self.marked[coordinates] {
+            // drawn before
+            return false;
+        }
 
-            if drawn_count == PLAYFIELD_SIZE as u32 {
-                return true;
-            }
+        self.marked[coordinates] = true;
+        self.unmarked_sum -= u64::from(self.numbers[coordinates]);
+
+        let mut full = false;
+
+        for (line, length) in self.lines_through(coordinates, rules) {
+            self.line_marks[line] += 1;
+
+            full |= self.line_marks[line] == length;
         }
 
-        false
+        full
     }
+}
 
-    fn unmarked_sum(&self) -> u32 {
-        self.inner.iter().fold(0, |acc_row, row| {
-            acc_row
-                + row
-                    .iter()
-                    .fold(0, |acc, c| acc + (if c.drawn.get() { 0 } else { c.number }))
-        })
-    }
+/// Plays bingo on a set of boards, one draw at a time.
+///
+/// Every draw only touches the places it's on, and the lines through them, so it doesn't matter
+/// how many boards there are or how big they get.
+#[derive(Clone, Debug)]
+pub struct Bingo {
+    rules: Rules,
+    cards: Vec<Card>,
+    /// Where every number is, as the board and the coordinates on it.
+    places: HashMap<u32, Vec<(usize, Coordinates)>>,
 }
 
-fn calculate_winnings(drawings: &[u32], playfields: &[Playfield]) -> (u32, u32) {
-    for drawing in drawings {
-        for playfield in playfields {
-            playfield.mark_number(*drawing);
+impl Bingo {
+    #[must_use]
+    pub fn new(boards: &[Grid<u32>], rules: Rules) -> Self {
+        let mut places: HashMap<u32, Vec<(usize, Coordinates)>> = HashMap::new();
 
-            if playfield.is_winner() {
-                return (playfield.unmarked_sum(), *drawing);
+        for (board, numbers) in boards.iter().enumerate() {
+            for (coordinates, &number) in numbers.iter() {
+                // the same number can be on a board more than once
+                places.entry(number).or_default().push((board, coordinates));
             }
         }
+
+        Self {
+            rules,
+            cards: boards.iter().cloned().map(Card::new).collect(),
+            places,
+        }
     }
 
-    panic!("No winner found")
-}
+    /// Marks `number` on every board that hasn't won yet, and returns the boards that win with
+    /// it, in board order.
+    pub fn draw(&mut self, number: u32) -> Vec<Win> {
+        let mut winners = Vec::new();
 
-fn calculate_last_winning_board_2(drawings: &[u32], playfields: &[Playfield]) -> (u32, u32) {
-    let mut last_winning_board_unmarked: u32 = 0;
-    let mut last_drawing: u32 = 0;
+        for &(board, coordinates) in self.places.get(&number).into_iter().flatten() {
+            let card = &mut self.cards[board];
 
-    for drawing in drawings {
-        for playfield in playfields {
-            if playfield.don_t_consider_anymore.get() {
+            if card.won {
                 continue;
             }
 
-            playfield.mark_number(*drawing);
-
-            if playfield.is_winner() {
-                last_winning_board_unmarked = playfield.unmarked_sum();
-                last_drawing = *drawing;
-                playfield.don_t_consider_anymore.set(true);
+            if card.mark(coordinates, self.rules) && !winners.contains(&board) {
+                winners.push(board);
             }
         }
+
+        winners.sort_unstable();
+
+        winners
+            .into_iter()
+            .map(|board| {
+                let card = &mut self.cards[board];
+
+                card.won = true;
+
+                Win {
+                    board,
+                    draw: number,
+                    score: card.unmarked_sum * u64::from(number),
+                }
+            })
+            .collect()
     }
 
-    (last_winning_board_unmarked, last_drawing)
+    /// Draws all of `draws`, and returns every win in the order they happened. Boards that never
+    /// win aren't in it.
+    pub fn play(&mut self, draws: &[u32]) -> Vec<Win> {
+        let mut wins = Vec::new();
+
+        for &draw in draws {
+            if self.cards.iter().all(|card| card.won) {
+                break;
+            }
+
+            wins.extend(self.draw(draw));
+        }
+
+        wins
+    }
 }
 
-fn parse_lines(lines: &[&str]) -> (Vec<u32>, Vec<Playfield>) {
-    let drawings = lines[0]
-        .split(',')
-        .map(|s| s.parse::<u32>().unwrap())
-        .collect();
+fn play(game: &Game, rules: Rules) -> Vec<Win> {
+    let wins = Bingo::new(&game.boards, rules).play(&game.draws);
+
+    ::tracing::event!(
+        ::tracing::Level::DEBUG,
+        "{} of {} boards win",
+        wins.len(),
+        game.boards.len()
+    );
+
+    for win in &wins {
+        ::tracing::event!(
+            ::tracing::Level::TRACE,
+            "Board {} wins with {}, scoring {}",
+            win.board + 1,
+            win.draw,
+            win.score
+        );
+    }
 
+    wins
+}
+
+fn parse_lines(lines: &[&str]) -> Result<Game, ParseError> {
     // top line is our drawings
-    let raw_playfields: Vec<_> = lines[1..lines.len()]
-        .split(|s| s.is_empty())
-        .filter(|f| !f.is_empty())
-        .collect();
-
-    let mut playfields = Vec::<Playfield>::new();
-
-    for raw_playfield in raw_playfields {
-        let mut rows: Vec<[PlayfieldCell; PLAYFIELD_SIZE]> = Vec::new();
-
-        for line in raw_playfield {
-            let row_vec: Vec<PlayfieldCell> = line
-                .split(' ')
-                .filter(|f| !f.is_empty())
-                .map(|s| s.parse::<u32>().unwrap())
-                .map(PlayfieldCell::new)
-                .collect();
-
-            let row: [PlayfieldCell; PLAYFIELD_SIZE] =
-                row_vec.try_into().unwrap_or_else(|v: Vec<PlayfieldCell>| {
-                    panic!(
-                        "Tried to parse vector of wrong size, vector length: {}",
-                        v.len()
-                    )
-                });
+    let draws = parse_separated(0, get_line(lines, 0)?, ',')?;
+
+    let mut boards: Vec<Grid<u32>> = Vec::new();
+
+    // the drawings are the first block
+    for block in blocks(lines).iter().skip(1) {
+        // the first board sets the size for all of them
+        let size = boards.first().map_or(block.lines.len(), Grid::height);
+
+        let mut rows = Vec::with_capacity(size);
+
+        for (line_index, line) in block.enumerate() {
+            let row: Vec<u32> = parse_whitespace_separated(line_index, line)?;
+
+            if row.len() != size {
+                return Err(ParseError::new(
+                    line_index,
+                    0,
+                    format!("expected {} numbers, got {}", size, row.len()),
+                ));
+            }
 
             rows.push(row);
         }
 
-        let playfield = Playfield::new(rows.try_into().unwrap_or_else(
-            |v: Vec<[PlayfieldCell; PLAYFIELD_SIZE]>| {
-                panic!(
-                    "Tried to parse vector of wrong size, vector length: {}",
-                    v.len()
-                )
-            },
-        ));
+        if rows.len() != size {
+            return Err(ParseError::new(
+                block.first_line_index + rows.len().min(size),
+                0,
+                format!("board has {} rows, expected {}", rows.len(), size),
+            ));
+        }
+
+        let board = Grid::from_rows::<Infallible>(rows)
+            .map_err(|error| ParseError::new(block.first_line_index, 0, error.to_string()))?;
 
-        playfields.push(playfield);
+        boards.push(board);
     }
 
-    (drawings, playfields)
+    Ok(Game { draws, boards })
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_04/input.txt").lines().collect();
-
-        let (drawings, play_fields) = parse_lines(&lines);
+    type Parsed = Game;
 
-        let (winning_board_sum_unmarked, drawing) = calculate_winnings(&drawings, &play_fields);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::U32(winning_board_sum_unmarked * drawing)
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_04/input.txt").lines().collect();
-
-        let (drawings, play_fields) = parse_lines(&lines);
-
-        let (last_winning_board_sum_unmarked, last_drawing) =
-            calculate_last_winning_board_2(&drawings, &play_fields);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let first = play(parsed, Rules::default())
+            .first()
+            .copied()
+            .ok_or_else(|| DayError::NoSolution("no board ever wins".to_owned()))?;
+
+        Ok(PartSolution::U64(first.score))
+    }
 
-        PartSolution::U32(last_winning_board_sum_unmarked * last_drawing)
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let last = play(parsed, Rules::default())
+            .last()
+            .copied()
+            .ok_or_else(|| DayError::NoSolution("no board ever wins".to_owned()))?;
+
+        Ok(PartSolution::U64(last.score))
     }
 }
 
@@ -216,28 +306,32 @@ mod test {
 
     mod part_1 {
         use pretty_assertions::assert_eq;
+        use shared::grid::Grid;
 
         use crate::day_04::test::get_example;
-        use crate::day_04::{Playfield, PlayfieldCell, Solution, calculate_winnings, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_04::{Bingo, INPUT, Rules, Solution, Win, parse_lines, play};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(23177));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U64(23177)
+            );
         }
 
         #[test]
         fn parses_drawings() {
             let lines = get_example();
 
-            let (drawings, _) = parse_lines(&lines);
+            let game = parse_lines(&lines).unwrap();
 
             assert_eq!(
                 vec![
                     7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24, 10, 16, 13, 6, 15, 25, 12, 22, 18,
                     20, 8, 19, 3, 26, 1
                 ],
-                drawings
+                game.draws()
             );
         }
 
@@ -245,137 +339,135 @@ mod test {
         fn example() {
             let lines = get_example();
 
-            let (_, playfields) = parse_lines(&lines);
+            let game = parse_lines(&lines).unwrap();
 
-            let playfield_1 = Playfield::new([
-                [
-                    PlayfieldCell::new(22),
-                    PlayfieldCell::new(13),
-                    PlayfieldCell::new(17),
-                    PlayfieldCell::new(11),
-                    PlayfieldCell::new(0),
-                ],
-                [
-                    PlayfieldCell::new(8),
-                    PlayfieldCell::new(2),
-                    PlayfieldCell::new(23),
-                    PlayfieldCell::new(4),
-                    PlayfieldCell::new(24),
-                ],
-                [
-                    PlayfieldCell::new(21),
-                    PlayfieldCell::new(9),
-                    PlayfieldCell::new(14),
-                    PlayfieldCell::new(16),
-                    PlayfieldCell::new(7),
-                ],
-                [
-                    PlayfieldCell::new(6),
-                    PlayfieldCell::new(10),
-                    PlayfieldCell::new(3),
-                    PlayfieldCell::new(18),
-                    PlayfieldCell::new(5),
-                ],
-                [
-                    PlayfieldCell::new(1),
-                    PlayfieldCell::new(12),
-                    PlayfieldCell::new(20),
-                    PlayfieldCell::new(15),
-                    PlayfieldCell::new(19),
-                ],
-            ]);
-
-            let playfield_2 = Playfield::new([
-                [
-                    PlayfieldCell::new(3),
-                    PlayfieldCell::new(15),
-                    PlayfieldCell::new(0),
-                    PlayfieldCell::new(2),
-                    PlayfieldCell::new(22),
-                ],
-                [
-                    PlayfieldCell::new(9),
-                    PlayfieldCell::new(18),
-                    PlayfieldCell::new(13),
-                    PlayfieldCell::new(17),
-                    PlayfieldCell::new(5),
-                ],
-                [
-                    PlayfieldCell::new(19),
-                    PlayfieldCell::new(8),
-                    PlayfieldCell::new(7),
-                    PlayfieldCell::new(25),
-                    PlayfieldCell::new(23),
-                ],
-                [
-                    PlayfieldCell::new(20),
-                    PlayfieldCell::new(11),
-                    PlayfieldCell::new(10),
-                    PlayfieldCell::new(24),
-                    PlayfieldCell::new(4),
-                ],
-                [
-                    PlayfieldCell::new(14),
-                    PlayfieldCell::new(21),
-                    PlayfieldCell::new(16),
-                    PlayfieldCell::new(12),
-                    PlayfieldCell::new(6),
-                ],
-            ]);
-
-            let playfield_3 = Playfield::new([
-                [
-                    PlayfieldCell::new(14),
-                    PlayfieldCell::new(21),
-                    PlayfieldCell::new(17),
-                    PlayfieldCell::new(24),
-                    PlayfieldCell::new(4),
-                ],
-                [
-                    PlayfieldCell::new(10),
-                    PlayfieldCell::new(16),
-                    PlayfieldCell::new(15),
-                    PlayfieldCell::new(9),
-                    PlayfieldCell::new(19),
-                ],
-                [
-                    PlayfieldCell::new(18),
-                    PlayfieldCell::new(8),
-                    PlayfieldCell::new(23),
-                    PlayfieldCell::new(26),
-                    PlayfieldCell::new(20),
-                ],
-                [
-                    PlayfieldCell::new(22),
-                    PlayfieldCell::new(11),
-                    PlayfieldCell::new(13),
-                    PlayfieldCell::new(6),
-                    PlayfieldCell::new(5),
-                ],
-                [
-                    PlayfieldCell::new(2),
-                    PlayfieldCell::new(0),
-                    PlayfieldCell::new(12),
-                    PlayfieldCell::new(3),
-                    PlayfieldCell::new(7),
+            assert_eq!(
+                vec![
+                    Grid::from_rows::<()>(vec![
+                        vec![22, 13, 17, 11, 0],
+                        vec![8, 2, 23, 4, 24],
+                        vec![21, 9, 14, 16, 7],
+                        vec![6, 10, 3, 18, 5],
+                        vec![1, 12, 20, 15, 19],
+                    ])
+                    .unwrap(),
+                    Grid::from_rows::<()>(vec![
+                        vec![3, 15, 0, 2, 22],
+                        vec![9, 18, 13, 17, 5],
+                        vec![19, 8, 7, 25, 23],
+                        vec![20, 11, 10, 24, 4],
+                        vec![14, 21, 16, 12, 6],
+                    ])
+                    .unwrap(),
+                    Grid::from_rows::<()>(vec![
+                        vec![14, 21, 17, 24, 4],
+                        vec![10, 16, 15, 9, 19],
+                        vec![18, 8, 23, 26, 20],
+                        vec![22, 11, 13, 6, 5],
+                        vec![2, 0, 12, 3, 7],
+                    ])
+                    .unwrap(),
                 ],
-            ]);
-
-            assert_eq!(playfield_1, playfields[0]);
-            assert_eq!(playfield_2, playfields[1]);
-            assert_eq!(playfield_3, playfields[2]);
+                game.boards()
+            );
         }
 
         #[test]
         fn example_3() {
             let lines = get_example();
 
-            let (drawings, play_fields) = parse_lines(&lines);
+            let game = parse_lines(&lines).unwrap();
+
+            assert_eq!(
+                Some(&Win {
+                    board: 2,
+                    draw: 24,
+                    score: 4512
+                }),
+                play(&game, Rules::default()).first()
+            );
+        }
+
+        #[test]
+        fn diagonals() {
+            let game = parse_lines(&get_example()).unwrap();
+
+            // 4, 9, 23, 11 and 2 go from the top right to the bottom left of the third board, so
+            // it now wins at 2
+            assert_eq!(
+                vec![
+                    Win {
+                        board: 2,
+                        draw: 2,
+                        score: 494
+                    },
+                    Win {
+                        board: 0,
+                        draw: 16,
+                        score: 2192
+                    },
+                    Win {
+                        board: 1,
+                        draw: 13,
+                        score: 1924
+                    },
+                ],
+                play(&game, Rules { diagonals: true })
+            );
+        }
+
+        #[test]
+        fn draws_one_at_a_time() {
+            let board = Grid::from_rows::<()>(vec![vec![1, 2], vec![3, 1]]).unwrap();
+            let other = Grid::from_rows::<()>(vec![vec![5, 6], vec![7, 8]]).unwrap();
+
+            let mut bingo = Bingo::new(&[other, board], Rules::default());
+
+            assert_eq!(Vec::<Win>::new(), bingo.draw(3));
+            // drawing it again changes nothing
+            assert_eq!(Vec::<Win>::new(), bingo.draw(3));
+            // on the board twice, which fills the bottom row and the first column at once
+            assert_eq!(
+                vec![Win {
+                    board: 1,
+                    draw: 1,
+                    score: 2
+                }],
+                bingo.draw(1)
+            );
+            // it already won
+            assert_eq!(Vec::<Win>::new(), bingo.draw(2));
+        }
 
-            let (unmarked_sum, drawn) = calculate_winnings(&drawings, &play_fields);
+        #[test]
+        fn other_sizes() {
+            let game = parse_lines(&["4,1,2,5,3", "", "1 2 3", "4 5 6", "7 8 9"]).unwrap();
 
-            assert_eq!(unmarked_sum, 188);
-            assert_eq!(drawn, 24);
+            assert_eq!(3, game.boards()[0].width());
+            assert_eq!(
+                vec![Win {
+                    board: 0,
+                    draw: 3,
+                    score: 3 * (6 + 7 + 8 + 9)
+                }],
+                play(&game, Rules::default())
+            );
+        }
+
+        #[test]
+        fn boards_of_different_sizes() {
+            assert_eq!(
+                "line 7, column 1: expected 2 numbers, got 3",
+                parse_lines(&["1", "", "1 2", "3 4", "", "1 2", "3 4 5"])
+                    .unwrap_err()
+                    .to_string()
+            );
+            assert_eq!(
+                "line 7, column 1: board has 1 rows, expected 2",
+                parse_lines(&["1", "", "1 2", "3 4", "", "1 2"])
+                    .unwrap_err()
+                    .to_string()
+            );
         }
     }
 
@@ -383,24 +475,41 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_04::test::get_example;
-        use crate::day_04::{Solution, calculate_last_winning_board_2, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_04::{INPUT, Rules, Solution, Win, parse_lines, play};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(6804));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U64(6804)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let (drawings, play_fields) = parse_lines(&lines);
+            let game = parse_lines(&lines).unwrap();
 
-            let (unmarked_sum, drawn) = calculate_last_winning_board_2(&drawings, &play_fields);
+            assert_eq!(
+                Some(&Win {
+                    board: 1,
+                    draw: 13,
+                    score: 1924
+                }),
+                play(&game, Rules::default()).last()
+            );
+        }
 
-            assert_eq!(unmarked_sum, 148);
-            assert_eq!(drawn, 13);
+        #[test]
+        fn no_winner() {
+            assert_eq!(
+                "No solution: no board ever wins",
+                solve(&Solution {}, 2, "1\n\n1 2\n3 4")
+                    .unwrap_err()
+                    .to_string()
+            );
         }
     }
 }
@@ -1,157 +1,209 @@
-use std::cmp;
+use std::cmp::Ordering;
+use std::iter;
 
-use regex::Regex;
+use shared::counter::Counter;
+use shared::parse::{ParseError, RecordParser};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-#[derive(Debug)]
-struct VentLine {
-    x1: u32,
-    y1: u32,
-    x2: u32,
-    y2: u32,
+pub const INPUT: &str = include_str!("day_05/input.txt");
+
+/// `(x, y)`.
+pub type Point = (i64, i64);
+
+/// The most points apart the ends of a line can be. Every point gets counted, so a line much
+/// longer than the ocean floor is a mistake, not something to walk.
+pub const MAX_SPAN: u64 = 1 << 20;
+
+/// A line of vents, both ends included.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VentLine {
+    from: Point,
+    to: Point,
 }
 
 impl VentLine {
-    fn new(x1: u32, y1: u32, x2: u32, y2: u32) -> VentLine {
-        VentLine { x1, y1, x2, y2 }
-    }
-    fn is_horizontal_or_vertical(&self) -> bool {
-        self.x1 == self.x2 || self.y1 == self.y2
+    /// `None` unless the line is horizontal, vertical or diagonal at 45 degrees, the only lines
+    /// the vents make.
+    #[must_use]
+    pub fn new(from: Point, to: Point) -> Option<Self> {
+        let horizontal_or_vertical = from.0 == to.0 || from.1 == to.1;
+        let diagonal = from.0.abs_diff(to.0) == from.1.abs_diff(to.1);
+
+        (horizontal_or_vertical || diagonal).then_some(Self { from, to })
     }
-}
 
-fn parse_lines(lines: &[&str], include_diagonal: bool) -> Vec<VentLine> {
-    let regex = Regex::new(r"(\d+),(\d+) -> (\d+),(\d+)").unwrap();
+    #[must_use]
+    pub fn from(&self) -> Point {
+        self.from
+    }
 
-    let mut vent_lines = Vec::new();
+    #[must_use]
+    pub fn to(&self) -> Point {
+        self.to
+    }
 
-    for line in lines {
-        let captures = regex.captures(line.trim()).unwrap();
+    #[must_use]
+    pub fn is_horizontal_or_vertical(&self) -> bool {
+        self.from.0 == self.to.0 || self.from.1 == self.to.1
+    }
 
-        let x1 = captures[1].parse::<u32>().unwrap();
-        let y1 = captures[2].parse::<u32>().unwrap();
-        let x2 = captures[3].parse::<u32>().unwrap();
-        let y2 = captures[4].parse::<u32>().unwrap();
+    /// Every point on the line, from `from` to `to`.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<> {
+        let to = self.to;
 
-        let vent_line = VentLine::new(x1, y1, x2, y2);
+        // by comparing, as subtracting the ends can overflow
+        let step = (direction(self.from.0, to.0), direction(self.from.1, to.1));
 
-        if vent_line.is_horizontal_or_vertical() || include_diagonal {
-            vent_lines.push(vent_line);
-        }
+        iter::successors(Some(self.from), move |&point| {
+            (point != to).then_some((point.0 + step.0, point.1 + step.1))
+        })
     }
+}
 
-    vent_lines
+/// -1, 0 or 1, whichever way gets us from `from` to `to`.
+fn direction(from: i64, to: i64) -> i64 {
+    match to.cmp(&from) {
+        Ordering::Less => -1,
+        Ordering::Equal => 0,
+        Ordering::Greater => 1,
+    }
 }
 
-fn calculate_overlap_of_2(vent_lines: &[VentLine]) -> u32 {
-    let x_max = vent_lines
-        .iter()
-        .map(|vl| cmp::max(vl.x1, vl.x2))
-        .max()
-        .unwrap();
-    let y_max = vent_lines
-        .iter()
-        .map(|vl| cmp::max(vl.y1, vl.y2))
-        .max()
-        .unwrap();
+/// How many vent lines go through every point, only storing the points that have any. That
+/// keeps it as small as the lines, however far apart they are.
+#[derive(Clone, Debug, Default)]
+pub struct VentMap {
+    vents: Counter<Point>,
+}
 
-    // println!("x max: {}, y max: {}", x_max, y_max);
+impl VentMap {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    let mut field: Vec<Vec<u32>> = Vec::new();
+    /// A map of all of `lines`.
+    ///
+    /// # Errors
+    ///
+    /// Once `cancel` runs out.
+    pub fn from_lines<'l, I: IntoIterator<Item = &'l VentLine>>(
+        lines: I,
+        cancel: &CancellationToken,
+    ) -> Result<Self, DayError> {
+        let mut map = Self::new();
+
+        for line in lines {
+            map.add(line, cancel)?;
+        }
 
-    for _ in 0..=y_max {
-        field.push(std::iter::repeat_n(0, usize::try_from(x_max).unwrap() + 1).collect::<Vec<_>>());
+        Ok(map)
     }
 
-    for vent_line in vent_lines {
-        for &(x, y) in &generate_coordinates_in_between(vent_line) {
-            let val = field[y as usize][x as usize];
+    /// # Errors
+    ///
+    /// Once `cancel` runs out, with only part of `line` added.
+    pub fn add(&mut self, line: &VentLine, cancel: &CancellationToken) -> Result<(), DayError> {
+        for point in line.points() {
+            cancel.check()?;
 
-            field[y as usize][x as usize] = val + 1;
+            self.vents.add(point);
         }
-    }
-
-    // for row in &field {
-    //     for c in row {
-    //         match c {
-    //             0 => print!("."),
-    //             x => print!("{}", x),
-    //         }
-    //     }
 
-    //     println!();
-    // }
+        Ok(())
+    }
 
-    let count_of_over_2: usize = field
-        .iter()
-        .map(|r| r.iter().filter(|c| **c >= 2).count())
-        .sum();
+    /// How many lines go through `point`.
+    #[must_use]
+    pub fn vents_at(&self, point: Point) -> u64 {
+        self.vents.get(&point)
+    }
 
-    count_of_over_2 as u32
+    /// How many points at least `threshold` lines go through.
+    #[must_use]
+    pub fn count_overlaps(&self, threshold: u64) -> usize {
+        self.vents.len_where(|count| count >= threshold)
+    }
 }
 
-fn generate_coordinates_in_between(vent_line: &VentLine) -> Vec<(u32, u32)> {
-    let mut in_between: Vec<(u32, u32)> = Vec::new();
-
-    // let x_diff = cmp::max(vent_line.x1, vent_line.x2) - cmp::min(vent_line.x1, vent_line.x2);
-    // let y_diff = cmp::max(vent_line.y1, vent_line.y2) - cmp::min(vent_line.y1, vent_line.y2);
-
-    // println!("Vec: {:?}", vent_line);
-
-    let x_range: Vec<u32> = if vent_line.x1 <= vent_line.x2 {
-        (vent_line.x1..=vent_line.x2).collect()
-    } else {
-        (vent_line.x2..=vent_line.x1).rev().collect()
-    };
+fn parse_lines(lines: &[&str]) -> Result<Vec<VentLine>, ParseError> {
+    let parser = RecordParser::new(r"^(-?\d+),(-?\d+) -> (-?\d+),(-?\d+)$", "x1,y1 -> x2,y2");
+
+    parser.parse_all(lines, |record| {
+        let from: Point = (record.get(1)?, record.get(2)?);
+        let to: Point = (record.get(3)?, record.get(4)?);
+
+        if from.0.abs_diff(to.0).max(from.1.abs_diff(to.1)) > MAX_SPAN {
+            return Err(record.error(
+                0,
+                format!(
+                    "{},{} -> {},{} is longer than {} points",
+                    from.0, from.1, to.0, to.1, MAX_SPAN
+                ),
+            ));
+        }
 
-    // println!("x_range: {:?}", x_range);
+        VentLine::new(from, to).ok_or_else(|| {
+            record.error(
+                0,
+                format!(
+                    "{},{} -> {},{} is not horizontal, vertical or diagonal at 45 degrees",
+                    from.0, from.1, to.0, to.1
+                ),
+            )
+        })
+    })
+}
 
-    let y_range: Vec<u32> = if vent_line.y1 <= vent_line.y2 {
-        (vent_line.y1..=vent_line.y2).collect()
-    } else {
-        (vent_line.y2..=vent_line.y1).rev().collect()
-    };
+fn count_overlaps<'l, I: IntoIterator<Item = &'l VentLine>>(
+    vent_lines: I,
+    cancel: &CancellationToken,
+) -> Result<usize, DayError> {
+    let map = VentMap::from_lines(vent_lines, cancel)?;
 
-    // println!("y_range: {:?}", y_range);
+    ::tracing::event!(
+        ::tracing::Level::DEBUG,
+        "Vents at {} points",
+        map.vents.len()
+    );
 
-    // diagonal
-    if x_range.len() == y_range.len() {
-        for i in 0..x_range.len() {
-            in_between.push((x_range[i], y_range[i]));
-        }
-    } else {
-        for x in &x_range {
-            for y in &y_range {
-                in_between.push((*x, *y));
-            }
-        }
-    }
-
-    in_between
+    Ok(map.count_overlaps(2))
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_05/input.txt").lines().collect();
-
-        let vent_lines: Vec<VentLine> = parse_lines(&lines, false);
+    type Parsed = Vec<VentLine>;
 
-        let overlap_of_2 = calculate_overlap_of_2(&vent_lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::U32(overlap_of_2)
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_05/input.txt").lines().collect();
-
-        let vent_lines: Vec<VentLine> = parse_lines(&lines, true);
-
-        let overlap_of_2 = calculate_overlap_of_2(&vent_lines);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        // part 1 only considers horizontal and vertical lines
+        let overlaps = count_overlaps(
+            parsed
+                .iter()
+                .filter(|vent_line| vent_line.is_horizontal_or_vertical()),
+            cancel,
+        )?;
+
+        Ok(PartSolution::USize(overlaps))
+    }
 
-        PartSolution::U32(overlap_of_2)
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::USize(count_overlaps(parsed, cancel)?))
     }
 }
 
@@ -162,26 +214,128 @@ mod test {
     }
 
     mod part_1 {
+        use std::time::Duration;
+
         use pretty_assertions::assert_eq;
 
         use crate::day_05::test::get_example;
-        use crate::day_05::{Solution, VentLine, calculate_overlap_of_2, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_05::{INPUT, Solution, VentLine, VentMap, parse_lines};
+        use crate::shared::{CancellationToken, Day as _, DayError, PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(4993));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::USize(4993)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let vent_lines: Vec<VentLine> = parse_lines(&lines, false);
+            let vent_lines = parse_lines(&lines).unwrap();
+
+            let map = VentMap::from_lines(
+                vent_lines
+                    .iter()
+                    .filter(|vent_line| vent_line.is_horizontal_or_vertical()),
+                &CancellationToken::never(),
+            )
+            .unwrap();
+
+            assert_eq!(map.count_overlaps(2), 5);
+        }
+
+        #[test]
+        fn points() {
+            assert_eq!(
+                vec![(3, 1), (2, 1), (1, 1)],
+                VentLine::new((3, 1), (1, 1))
+                    .unwrap()
+                    .points()
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![(9, 7), (8, 8), (7, 9)],
+                VentLine::new((9, 7), (7, 9))
+                    .unwrap()
+                    .points()
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![(4, 4)],
+                VentLine::new((4, 4), (4, 4))
+                    .unwrap()
+                    .points()
+                    .collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn extreme_coordinates() {
+            // the ends are further apart than an i64 goes
+            assert_eq!(
+                vec![(-2, 0), (-1, 0), (0, 0)],
+                VentLine::new((-2, 0), (i64::MAX, 0))
+                    .unwrap()
+                    .points()
+                    .take(3)
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                vec![(i64::MIN, i64::MAX), (i64::MIN + 1, i64::MAX - 1)],
+                VentLine::new((i64::MIN, i64::MAX), (i64::MIN + 1, i64::MAX - 1))
+                    .unwrap()
+                    .points()
+                    .collect::<Vec<_>>()
+            );
+
+            assert_eq!(
+                "line 1, column 1: -9223372036854775808,0 -> 9223372036854775807,0 is longer than \
+                 1048576 points",
+                parse_lines(&["-9223372036854775808,0 -> 9223372036854775807,0"])
+                    .unwrap_err()
+                    .to_string()
+            );
+            assert_eq!(
+                "line 1, column 1: -2,0 -> 9223372036854775807,0 is longer than 1048576 points",
+                parse_lines(&["-2,0 -> 9223372036854775807,0"])
+                    .unwrap_err()
+                    .to_string()
+            );
+            assert_eq!(
+                "line 1, column 1: 0,0 -> 1048577,1048577 is longer than 1048576 points",
+                parse_lines(&["0,0 -> 1048577,1048577"])
+                    .unwrap_err()
+                    .to_string()
+            );
+            assert_eq!(1, parse_lines(&["0,0 -> 1048576,1048576"]).unwrap().len());
+        }
+
+        #[test]
+        fn times_out() {
+            let timeout = Duration::from_millis(10);
 
-            let overlap_of_2 = calculate_overlap_of_2(&vent_lines);
+            // a million points each, which takes a while to count
+            let vent_lines = parse_lines(&["0,0 -> 1048576,0"; 100]).unwrap();
 
-            assert_eq!(overlap_of_2, 5);
+            assert_eq!(
+                Err(DayError::TimedOut(timeout)),
+                (Solution {}).part_1(&vent_lines, &CancellationToken::with_timeout(timeout))
+            );
+        }
+
+        #[test]
+        fn unsupported_slope() {
+            assert_eq!(None, VentLine::new((0, 0), (2, 1)));
+            assert_eq!(
+                "line 2, column 1: 0,0 -> 8,3 is not horizontal, vertical or diagonal at 45 \
+                 degrees",
+                parse_lines(&["0,9 -> 5,9", "0,0 -> 8,3"])
+                    .unwrap_err()
+                    .to_string()
+            );
         }
     }
 
@@ -189,23 +343,59 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_05::test::get_example;
-        use crate::day_05::{Solution, VentLine, calculate_overlap_of_2, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_05::{INPUT, Solution, VentMap, parse_lines};
+        use crate::shared::{CancellationToken, PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(21101));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::USize(21101)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let vent_lines: Vec<VentLine> = parse_lines(&lines, true);
+            let vent_lines = parse_lines(&lines).unwrap();
 
-            let overlap_of_2 = calculate_overlap_of_2(&vent_lines);
+            let map = VentMap::from_lines(&vent_lines, &CancellationToken::never()).unwrap();
 
-            assert_eq!(overlap_of_2, 12);
+            assert_eq!(map.count_overlaps(2), 12);
+        }
+
+        #[test]
+        fn thresholds() {
+            let vent_lines = parse_lines(&get_example()).unwrap();
+
+            let map = VentMap::from_lines(&vent_lines, &CancellationToken::never()).unwrap();
+
+            assert_eq!(
+                vec![39, 12, 2, 0],
+                [1, 2, 3, 4].map(|threshold| map.count_overlaps(threshold))
+            );
+            assert_eq!(3, map.vents_at((4, 4)));
+            assert_eq!(2, map.vents_at((7, 4)));
+            assert_eq!(0, map.vents_at((-1, 0)));
+        }
+
+        #[test]
+        fn huge_coordinates() {
+            let vent_lines = parse_lines(&[
+                "-5000000000,7000000000 -> -4999999990,7000000000",
+                "-4999999995,6999999995 -> -4999999995,7000000005",
+                "-5000000000,6999999995 -> -4999999990,7000000005",
+                "9000000000000000000,0 -> 9000000000000000000,3",
+            ])
+            .unwrap();
+
+            let map = VentMap::from_lines(&vent_lines, &CancellationToken::never()).unwrap();
+
+            // all three cross at one point
+            assert_eq!(3, map.vents_at((-4_999_999_995, 7_000_000_000)));
+            assert_eq!(1, map.count_overlaps(2));
+            assert_eq!(11 + 11 + 11 + 4 - 2, map.count_overlaps(1));
         }
     }
 }
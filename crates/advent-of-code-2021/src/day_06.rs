@@ -1,14 +1,14 @@
-use hashbrown::HashMap;
+use shared::counter::Counter;
+use shared::parse::{ParseError, get_line, parse_separated};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-fn parse_lines(lines: &[&str]) -> Vec<u8> {
-    let fishes_nearby = lines[0]
-        .split(',')
-        .map(|f| f.parse::<u8>().unwrap())
-        .collect();
+pub const INPUT: &str = include_str!("day_06/input.txt");
 
-    fishes_nearby
+fn parse_lines(lines: &[&str]) -> Result<Vec<u8>, ParseError> {
+    let line = get_line(lines, 0)?;
+
+    parse_separated(0, line, ',')
 }
 
 fn age_fishes(fishes: &mut Vec<u8>) {
@@ -26,61 +26,63 @@ fn age_fishes(fishes: &mut Vec<u8>) {
     fishes.resize(fishes.len() + new_fishes_to_append, 8);
 }
 
-fn speed_up_fishes(fishes_nearby: &[u8]) -> HashMap<u8, u64> {
-    let mut fish_counts: HashMap<u8, u64> = HashMap::new();
-
-    for fish in fishes_nearby {
-        let fish_count = *fish_counts.get(fish).unwrap_or(&0);
-
-        fish_counts.insert(*fish, fish_count + 1);
-    }
-
-    fish_counts
+fn speed_up_fishes(fishes_nearby: &[u8]) -> Counter<u8> {
+    fishes_nearby.iter().copied().collect()
 }
 
-fn age_fishes_fast(fishes: &mut HashMap<u8, u64>) {
-    // fishes age 0 will be reset to age 6, and will spawn new fishes aged 8
-    let fishes_which_will_spawn_new_fishes = *fishes.get(&0).unwrap_or(&0);
-
-    for i in 1..=8 {
-        let current = *fishes.get(&i).unwrap_or(&0);
+fn age_fishes_fast(fishes: &mut Counter<u8>) {
+    let mut aged = Counter::new();
 
-        fishes.insert(i - 1, current);
+    for (&age, count) in fishes.iter() {
+        if age == 0 {
+            // fishes age 0 reset to age 6, and spawn new fishes aged 8
+            aged.add_n(6, count);
+            aged.add_n(8, count);
+        } else {
+            aged.add_n(age - 1, count);
+        }
     }
 
-    // all fishes reset create 1 offspring
-    fishes.insert(8, fishes_which_will_spawn_new_fishes);
-
-    // and add the ones at age 0 back to the pool, but they start back at 6
-    let entry_6 = fishes.entry(6).or_default();
-    *entry_6 += fishes_which_will_spawn_new_fishes;
+    *fishes = aged;
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_06/input.txt").lines().collect();
+    type Parsed = Vec<u8>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
+
+        Ok(parse_lines(&lines)?)
+    }
+
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let mut fishes = parsed.clone();
 
-        let mut fishes = parse_lines(&lines);
         for _ in 0..80 {
             age_fishes(&mut fishes);
         }
 
-        PartSolution::USize(fishes.len())
+        Ok(PartSolution::USize(fishes.len()))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_06/input.txt").lines().collect();
-
-        let fishes = parse_lines(&lines);
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let mut fast_fishes = speed_up_fishes(parsed);
 
-        let mut fast_fishes = speed_up_fishes(&fishes);
         for _ in 1..=256 {
             age_fishes_fast(&mut fast_fishes);
         }
 
-        PartSolution::U64(fast_fishes.values().sum::<u64>())
+        Ok(PartSolution::U64(fast_fishes.total()))
     }
 }
 
@@ -94,19 +96,22 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_06::test::get_example;
-        use crate::day_06::{Solution, age_fishes, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_06::{INPUT, Solution, age_fishes, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::USize(395_627));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::USize(395_627)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let mut fishes: Vec<u8> = parse_lines(&lines);
+            let mut fishes: Vec<u8> = parse_lines(&lines).unwrap();
 
             for _ in 0..18 {
                 age_fishes(&mut fishes);
@@ -120,7 +125,7 @@ mod test {
         fn example_2() {
             let lines = get_example();
 
-            let mut fishes: Vec<u8> = parse_lines(&lines);
+            let mut fishes: Vec<u8> = parse_lines(&lines).unwrap();
 
             for _ in 0..80 {
                 age_fishes(&mut fishes);
@@ -134,19 +139,22 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_06::test::get_example;
-        use crate::day_06::{Solution, age_fishes_fast, parse_lines, speed_up_fishes};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_06::{INPUT, Solution, age_fishes_fast, parse_lines, speed_up_fishes};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U64(1_767_323_539_209));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U64(1_767_323_539_209)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let fishes = parse_lines(&lines);
+            let fishes = parse_lines(&lines).unwrap();
 
             let mut fast_fishes = speed_up_fishes(&fishes);
 
@@ -154,14 +162,14 @@ mod test {
                 age_fishes_fast(&mut fast_fishes);
             }
 
-            assert_eq!(26, fast_fishes.values().sum::<u64>());
+            assert_eq!(26, fast_fishes.total());
         }
 
         #[test]
         fn example_2() {
             let lines = get_example();
 
-            let fishes = parse_lines(&lines);
+            let fishes = parse_lines(&lines).unwrap();
 
             let mut fast_fishes = speed_up_fishes(&fishes);
 
@@ -169,14 +177,14 @@ mod test {
                 age_fishes_fast(&mut fast_fishes);
             }
 
-            assert_eq!(5934, fast_fishes.values().sum::<u64>());
+            assert_eq!(5934, fast_fishes.total());
         }
 
         #[test]
         fn example_3() {
             let lines = get_example();
 
-            let fishes = parse_lines(&lines);
+            let fishes = parse_lines(&lines).unwrap();
 
             let mut fast_fishes = speed_up_fishes(&fishes);
 
@@ -184,7 +192,7 @@ mod test {
                 age_fishes_fast(&mut fast_fishes);
             }
 
-            assert_eq!(26_984_457_539, fast_fishes.values().sum::<u64>());
+            assert_eq!(26_984_457_539, fast_fishes.total());
         }
     }
 }
@@ -1,14 +1,15 @@
 use std::cmp;
 
-use hashbrown::HashMap;
+use shared::parse::{ParseError, get_line, parse_separated};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-fn parse_lines(lines: &[&str]) -> Vec<u32> {
-    lines[0]
-        .split(',')
-        .map(|f| f.parse::<u32>().unwrap())
-        .collect()
+pub const INPUT: &str = include_str!("day_07/input.txt");
+
+fn parse_lines(lines: &[&str]) -> Result<Vec<u32>, ParseError> {
+    let line = get_line(lines, 0)?;
+
+    parse_separated(0, line, ',')
 }
 
 fn find_minimum(crabs: &[u32]) -> u32 {
@@ -39,14 +40,9 @@ fn find_minimum(crabs: &[u32]) -> u32 {
     min
 }
 
-fn calculate_fuel_needed_2(distance: u32, cache: &mut HashMap<u32, u32>) -> u32 {
-    *cache.entry(distance).or_insert_with(|| {
-        let mut fuel_needed: u32 = 0;
-        for i in 1..=distance {
-            fuel_needed += i;
-        }
-        fuel_needed
-    })
+/// Every step costs one more than the last, so 1 + 2 + ... + `distance`.
+fn calculate_fuel_needed_2(distance: u32) -> u32 {
+    distance * (distance + 1) / 2
 }
 
 fn find_minimum_2(crabs: &[u32]) -> u32 {
@@ -55,16 +51,13 @@ fn find_minimum_2(crabs: &[u32]) -> u32 {
 
     let mut min = u32::MAX;
 
-    let mut fuel_needed_cache = HashMap::<u32, u32>::new();
-
     for depth in minimum_depth..=max_depth {
         let mut fuel_needed_for_depth: u32 = 0;
 
         for crab in crabs {
             let crab_distance_from_depth = cmp::max(*crab, depth) - cmp::min(*crab, depth);
 
-            fuel_needed_for_depth +=
-                calculate_fuel_needed_2(crab_distance_from_depth, &mut fuel_needed_cache);
+            fuel_needed_for_depth += calculate_fuel_needed_2(crab_distance_from_depth);
 
             // no need to continue if we are already over the last minimum
             if fuel_needed_for_depth > min {
@@ -83,20 +76,28 @@ fn find_minimum_2(crabs: &[u32]) -> u32 {
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_07/input.txt").lines().collect();
+    type Parsed = Vec<u32>;
 
-        let crabs = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::U32(find_minimum(&crabs))
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_07/input.txt").lines().collect();
-
-        let crabs = parse_lines(&lines);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U32(find_minimum(parsed)))
+    }
 
-        PartSolution::U32(find_minimum_2(&crabs))
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U32(find_minimum_2(parsed)))
     }
 }
 
@@ -110,51 +111,58 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_07::test::get_example;
-        use crate::day_07::{Solution, find_minimum, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_07::{INPUT, Solution, find_minimum, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(339_321));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(339_321)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let crabs: Vec<u32> = parse_lines(&lines);
+            let crabs: Vec<u32> = parse_lines(&lines).unwrap();
 
             assert_eq!(37, find_minimum(&crabs));
         }
     }
 
     mod part_2 {
-        use hashbrown::HashMap;
         use pretty_assertions::assert_eq;
 
         use crate::day_07::test::get_example;
-        use crate::day_07::{Solution, calculate_fuel_needed_2, find_minimum_2, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_07::{
+            INPUT, Solution, calculate_fuel_needed_2, find_minimum_2, parse_lines,
+        };
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(95_476_244));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U32(95_476_244)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let crabs: Vec<u32> = parse_lines(&lines);
+            let crabs: Vec<u32> = parse_lines(&lines).unwrap();
 
             assert_eq!(168, find_minimum_2(&crabs));
         }
 
         #[test]
         fn fuel_needed() {
-            assert_eq!(1, calculate_fuel_needed_2(1, &mut HashMap::new()));
-            assert_eq!(3, calculate_fuel_needed_2(2, &mut HashMap::new()));
-            assert_eq!(15, calculate_fuel_needed_2(5, &mut HashMap::new()));
+            assert_eq!(1, calculate_fuel_needed_2(1));
+            assert_eq!(3, calculate_fuel_needed_2(2));
+            assert_eq!(15, calculate_fuel_needed_2(5));
         }
     }
 }
@@ -1,39 +1,62 @@
-#![expect(clippy::disallowed_macros, reason = "No pretty needed in actual code")]
 use std::ops::Sub as _;
 
 use hashbrown::HashSet;
+use shared::parse::ParseError;
+
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_08/input.txt");
+
+fn parse_into_letter_combinations(
+    line_index: usize,
+    line: &str,
+    half: &str,
+) -> Result<Vec<LetterCombination>, ParseError> {
+    half.split(' ')
+        .filter(|x| !x.is_empty())
+        .map(|f| {
+            if let Some(bad) = f.chars().find(|c| !('a'..='g').contains(c)) {
+                return Err(ParseError::at_token(
+                    line_index,
+                    line,
+                    f,
+                    format!("`{}` is not a segment between `a` and `g`", bad),
+                ));
+            }
 
-use crate::shared::{Day, PartSolution};
-
-fn parse_into_letter_combinations(line: &str) -> Vec<LetterCombination> {
-    line.split(' ')
-        .filter(|x| *x != "|")
-        .map(|f| HashSet::from_iter(f.trim().chars().collect::<LetterCombination>()))
+            Ok(f.chars().collect::<LetterCombination>())
+        })
         .collect()
 }
 
-#[expect(clippy::disallowed_macros, reason = "No pretty needed in actual code")]
-fn parse_lines(input: &[&str]) -> Vec<(Vec<LetterCombination>, Vec<LetterCombination>)> {
+fn parse_lines(input: &[&str]) -> Result<Vec<Entry>, ParseError> {
     let mut into_pieces = Vec::new();
 
-    for line in input {
-        let split: Vec<&str> = line.split('|').collect();
-
-        assert_eq!(split.len(), 2, "Bad input");
+    for (line_index, &line) in input.iter().enumerate() {
+        let (left, right) = line.split_once('|').ok_or_else(|| {
+            ParseError::new(
+                line_index,
+                0,
+                "expected patterns and output separated by `|`".to_owned(),
+            )
+        })?;
 
-        let left = parse_into_letter_combinations(split.first().unwrap().trim());
+        let left = parse_into_letter_combinations(line_index, line, left)?;
 
-        let right = parse_into_letter_combinations(split.get(1).unwrap().trim());
+        let right = parse_into_letter_combinations(line_index, line, right)?;
 
         into_pieces.push((left, right));
     }
 
-    into_pieces
+    Ok(into_pieces)
 }
 
 type LetterCombination = HashSet<char>;
 
-fn count_digits_1_4_7_8(lines: &[(Vec<LetterCombination>, Vec<LetterCombination>)]) -> usize {
+/// The unique signal patterns, and the four digit output value.
+type Entry = (Vec<LetterCombination>, Vec<LetterCombination>);
+
+fn count_digits_1_4_7_8(lines: &[Entry]) -> usize {
     let mut digits = Vec::new();
 
     for &(_, ref right) in lines {
@@ -55,32 +78,63 @@ fn count_digits_1_4_7_8(lines: &[(Vec<LetterCombination>, Vec<LetterCombination>
     digits.len()
 }
 
-#[expect(clippy::disallowed_macros, reason = "No pretty needed in actual code")]
-fn single_piece(input: &HashSet<char>) -> char {
-    assert_eq!(input.len(), 1, "Bad input");
+fn wiring_error(reason: &str) -> DayError {
+    DayError::NoSolution(format!("the patterns don't match the display: {}", reason))
+}
 
-    *input.iter().next().unwrap()
+/// Makes sure we found as many `what` as the display has.
+fn check_count(found: usize, expected: usize, what: &str) -> Result<(), DayError> {
+    if found == expected {
+        Ok(())
+    } else {
+        Err(wiring_error(&format!(
+            "expected {} {}, found {}",
+            expected, what, found
+        )))
+    }
 }
 
-fn calculate_signal_patterns(encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<char>>)) -> u32 {
+/// The segment that is `what`, when `input` narrowed it down to one.
+fn single_piece(input: &HashSet<char>, what: &str) -> Result<char, DayError> {
+    check_count(input.len(), 1, what)?;
+
+    input
+        .iter()
+        .next()
+        .copied()
+        .ok_or_else(|| wiring_error(&format!("no {}", what)))
+}
+
+/// The pattern of `digit`, the only digit lit with `length` segments.
+fn find_by_length(
+    patterns: &[LetterCombination],
+    length: usize,
+    digit: u32,
+) -> Result<&LetterCombination, DayError> {
+    patterns.iter().find(|x| x.len() == length).ok_or_else(|| {
+        wiring_error(&format!(
+            "no pattern has {} segments, like a {}",
+            length, digit
+        ))
+    })
+}
+
+fn calculate_signal_patterns(
+    encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<char>>),
+) -> Result<usize, DayError> {
     let &(ref encoded_key, ref encoded_solution) = encoded_line;
 
     // these are fixed by length
-    let one: &LetterCombination = encoded_key.iter().find(|x| x.len() == 2).unwrap();
-    let seven: &LetterCombination = encoded_key.iter().find(|x| x.len() == 3).unwrap();
-    let four: &LetterCombination = encoded_key.iter().find(|x| x.len() == 4).unwrap();
-    let eight: &LetterCombination = encoded_key.iter().find(|x| x.len() == 7).unwrap();
-
-    assert_eq!(one.len(), 2);
-    assert_eq!(seven.len(), 3);
-    assert_eq!(four.len(), 4);
-    assert_eq!(eight.len(), 7);
+    let one: &LetterCombination = find_by_length(encoded_key, 2, 1)?;
+    let seven: &LetterCombination = find_by_length(encoded_key, 3, 7)?;
+    let four: &LetterCombination = find_by_length(encoded_key, 4, 4)?;
+    let eight: &LetterCombination = find_by_length(encoded_key, 7, 8)?;
 
     // these are all a length of six
     let zero_six_nine: Vec<&LetterCombination> =
         encoded_key.iter().filter(|x| x.len() == 6).collect();
 
-    assert_eq!(zero_six_nine.len(), 3);
+    check_count(zero_six_nine.len(), 3, "patterns with 6 segments")?;
 
     // 7 - 1 reveals the top part
     //  _          _
@@ -88,15 +142,13 @@ fn calculate_signal_patterns(encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<cha
     //   |     |
     let top = seven.sub(one);
 
-    assert_eq!(top.len(), 1);
-
     // 4 - 7 reveals the top and middle part
     //        _
     // |_| -   | = |_
     //   |     |
     let middle_and_left_top: LetterCombination = four.sub(seven);
 
-    assert_eq!(middle_and_left_top.len(), 2);
+    check_count(middle_and_left_top.len(), 2, "middle and left top segments")?;
 
     // 6 and 9 both contain the top and middle part, unlike zero, which is missing the middle part
     let six_nine: Vec<&LetterCombination> = zero_six_nine
@@ -105,15 +157,13 @@ fn calculate_signal_patterns(encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<cha
         .copied()
         .collect();
 
-    assert_eq!(six_nine.len(), 2);
+    check_count(six_nine.len(), 2, "patterns for 6 and 9")?;
 
     // zero is what is in zero_six_nine after we remove six and nine
     let zero: &LetterCombination = zero_six_nine
         .iter()
         .find(|x| !six_nine.contains(x))
-        .unwrap();
-
-    assert_eq!(zero.len(), 6);
+        .ok_or_else(|| wiring_error("no pattern for 0"))?;
 
     // 8 - 0 reveals middle part
     //  _     _
@@ -121,25 +171,23 @@ fn calculate_signal_patterns(encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<cha
     // |_|   |_|
     let middle = eight.sub(zero);
 
-    assert_eq!(middle.len(), 1);
-
     // (left top and middle) - middle reveals the left top
     //
     // |_  -  _  =  |
     //
     let left_top = middle_and_left_top.sub(&middle);
 
-    assert_eq!(left_top.len(), 1);
-
     // 9 is the one from 6 and 9 that completely encompasses 1
-    let nine: &LetterCombination = six_nine.iter().find(|x| x.is_superset(one)).unwrap();
-
-    assert_eq!(nine.len(), 6);
+    let nine: &LetterCombination = six_nine
+        .iter()
+        .find(|x| x.is_superset(one))
+        .ok_or_else(|| wiring_error("no pattern for 9"))?;
 
     // 6 is the one that isn't nine
-    let six: &LetterCombination = six_nine.iter().find(|x| **x != nine).unwrap();
-
-    assert_eq!(six.len(), 6);
+    let six: &LetterCombination = six_nine
+        .iter()
+        .find(|x| **x != nine)
+        .ok_or_else(|| wiring_error("no pattern for 6"))?;
 
     // 1 - 6 reveals the right top part
     //        _
@@ -147,8 +195,6 @@ fn calculate_signal_patterns(encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<cha
     //   |   |_|
     let right_top = one.sub(six);
 
-    assert_eq!(right_top.len(), 1);
-
     // 8 - 9 reveals the left bottom part
     //  _     _
     // |_| - |_| =
@@ -168,17 +214,16 @@ fn calculate_signal_patterns(encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<cha
         .sub(&right_top)
         .sub(&right_bottom);
 
-    assert_eq!(bottom.len(), 1);
-
-    let top_part = single_piece(&top);
-    let left_top_part = single_piece(&left_top);
-    let right_top_part = single_piece(&right_top);
-    let middle_part = single_piece(&middle);
-    let left_bottom_part = single_piece(&left_bottom);
-    let right_bottom_part = single_piece(&right_bottom);
-    let bottom_part = single_piece(&bottom);
+    let top_part = single_piece(&top, "top segment")?;
+    let left_top_part = single_piece(&left_top, "left top segment")?;
+    let right_top_part = single_piece(&right_top, "right top segment")?;
+    let middle_part = single_piece(&middle, "middle segment")?;
+    let left_bottom_part = single_piece(&left_bottom, "left bottom segment")?;
+    let right_bottom_part = single_piece(&right_bottom, "right bottom segment")?;
+    let bottom_part = single_piece(&bottom, "bottom segment")?;
 
-    print!(
+    ::tracing::event!(
+        ::tracing::Level::TRACE,
         " {}{}{}{}\n{}    {}\n{}    {}\n {}{}{}{}\n{}    {}\n{}    {}\n {}{}{}{}",
         top_part,
         top_part,
@@ -202,7 +247,12 @@ fn calculate_signal_patterns(encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<cha
         bottom_part
     );
 
-    println!("   {:?} | {:?}", encoded_key, encoded_solution);
+    ::tracing::event!(
+        ::tracing::Level::TRACE,
+        "   {:?} | {:?}",
+        encoded_key,
+        encoded_solution
+    );
 
     let two: HashSet<char> = HashSet::from_iter(vec![
         top_part,
@@ -237,50 +287,61 @@ fn calculate_signal_patterns(encoded_line: &(Vec<HashSet<char>>, Vec<HashSet<cha
     decode_solution(&decoded_key, &encoded)
 }
 
-fn decode_solution(key: &[&LetterCombination], encoded_solution: &[&LetterCombination]) -> u32 {
+fn decode_solution(
+    key: &[&LetterCombination],
+    encoded_solution: &[&LetterCombination],
+) -> Result<usize, DayError> {
     let mut result = 0;
 
-    // to calculate the power
-    let length = encoded_solution.len() - 1;
-
-    for (index, n) in encoded_solution.iter().enumerate() {
+    for n in encoded_solution {
         let Some(decoded) = key.iter().position(|x| x == n) else {
-            panic!()
+            let mut segments = n.iter().collect::<Vec<_>>();
+
+            segments.sort_unstable();
+
+            return Err(wiring_error(&format!(
+                "`{}` in the output is not a digit",
+                segments.into_iter().collect::<String>()
+            )));
         };
 
-        // now based on the index we need to add it to result
-        // letter at index zero is actually the highest
-        result += 10_usize.pow(length as u32 - index as u32) * decoded;
+        // the letter at index zero is the highest digit
+        result = result * 10 + decoded;
     }
 
-    result as u32
+    Ok(result)
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_08/input.txt").lines().collect();
+    type Parsed = Vec<Entry>;
 
-        let split = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::USize(count_digits_1_4_7_8(&split))
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_08/input.txt").lines().collect();
-
-        let mut last_4_sum = Vec::new();
-
-        let split = parse_lines(&lines);
-
-        for s in split {
-            let decoded = calculate_signal_patterns(&s);
-
-            last_4_sum.push(decoded);
-        }
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::USize(count_digits_1_4_7_8(parsed)))
+    }
 
-        PartSolution::U32(last_4_sum.iter().sum())
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::USize(
+            parsed
+                .iter()
+                .map(calculate_signal_patterns)
+                .sum::<Result<_, _>>()?,
+        ))
     }
 }
 
@@ -294,19 +355,22 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_08::test::get_example;
-        use crate::day_08::{Solution, count_digits_1_4_7_8, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_08::{INPUT, Solution, count_digits_1_4_7_8, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::USize(392));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::USize(392)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let split = parse_lines(&lines);
+            let split = parse_lines(&lines).unwrap();
 
             assert_eq!(26, count_digits_1_4_7_8(&split));
         }
@@ -316,29 +380,52 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_08::test::get_example;
-        use crate::day_08::{Solution, calculate_signal_patterns, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_08::{INPUT, Solution, calculate_signal_patterns, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(1_004_688));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::USize(1_004_688)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let split = parse_lines(&lines);
+            let split = parse_lines(&lines).unwrap();
 
             let mut sum = 0;
 
             for s in split {
-                let decoded = calculate_signal_patterns(&s);
+                let decoded = calculate_signal_patterns(&s).unwrap();
 
                 sum += decoded;
             }
 
             assert_eq!(61229, sum);
         }
+
+        #[test]
+        fn wrong_patterns() {
+            assert_eq!(
+                "No solution: the patterns don't match the display: no pattern has 2 segments, \
+                 like a 1",
+                solve(&Solution {}, 2, "a | b").unwrap_err().to_string()
+            );
+
+            // the example's patterns, with an output digit they don't have
+            let (patterns, _) = get_example()[0].split_once('|').unwrap();
+
+            assert_eq!(
+                "No solution: the patterns don't match the display: `ab` in the output is not a \
+                 digit",
+                solve(&Solution {}, 2, &format!("{}| ba", patterns))
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
     }
 }
@@ -1,134 +1,91 @@
 use std::cell::Cell;
 
 use hashbrown::HashSet;
+use shared::grid::{Coordinates, Grid};
+use shared::parse::{ParseError, parse_digit};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-fn parse_lines(lines: &[&str]) -> Vec<Vec<u32>> {
-    let mut field: Vec<Vec<u32>> = Vec::new();
+pub const INPUT: &str = include_str!("day_09/input.txt");
 
-    for line in lines {
-        field.push(line.chars().map(|x| x.to_digit(10).unwrap()).collect());
-    }
-
-    field
-}
-
-fn calculate_risk_level(low_points: &[(usize, usize)], heatmap: &[Vec<u32>]) -> u32 {
-    let mut low_point_values: Vec<u32> = Vec::new();
-
-    for &(x, y) in low_points {
-        let low_point_value: u32 = heatmap[y][x];
-
-        low_point_values.push(low_point_value);
-    }
-
-    low_point_values.iter().map(|x| x + 1).sum()
+fn parse_heatmap(input: &str) -> Result<Grid<u32>, ParseError> {
+    Ok(Grid::parse(input, |(row, column), c| {
+        parse_digit(row, column, c)
+    })?)
 }
 
-fn value_smaller_than_all_neighbors(
-    value: u32,
-    heatmap: &[Vec<u32>],
-    neighbors: &HashSet<Coordinates>,
-) -> bool {
-    neighbors.iter().all(|&(x, y)| heatmap[y][x] > value)
+fn calculate_risk_level(low_points: &[Coordinates], heatmap: &Grid<u32>) -> u32 {
+    low_points
+        .iter()
+        .map(|&low_point| heatmap[low_point] + 1)
+        .sum()
 }
 
-fn get_low_points(heatmap: &[Vec<u32>]) -> Vec<(usize, usize)> {
+fn get_low_points(heatmap: &Grid<u32>) -> Vec<Coordinates> {
     let mut low_points = Vec::new();
 
-    for (row_index, row) in heatmap.iter().enumerate() {
-        for (column_index, value) in row.iter().enumerate() {
-            let neighbors = get_neighbors(heatmap, row_index, column_index);
-
-            if value_smaller_than_all_neighbors(*value, heatmap, &neighbors) {
-                let neighbor_values: Vec<u32> =
-                    neighbors.iter().map(|&(x, y)| heatmap[y][x]).collect();
-                println!(
-                    "{} is smaller than all values in neighbors ({:?})",
-                    *value, neighbor_values
-                );
-                low_points.push((column_index, row_index));
-            }
+    for (coordinates, &value) in heatmap.iter() {
+        if heatmap
+            .neighbors_4(coordinates)
+            .all(|neighbor| heatmap[neighbor] > value)
+        {
+            ::tracing::event!(
+                ::tracing::Level::TRACE,
+                "{} is smaller than all values in neighbors ({:?})",
+                value,
+                heatmap
+                    .neighbors_4(coordinates)
+                    .map(|neighbor| heatmap[neighbor])
+                    .collect::<Vec<_>>()
+            );
+            low_points.push(coordinates);
         }
     }
 
     low_points
 }
 
-type Coordinates = (usize, usize);
-
-fn get_neighbors<T>(
-    heatmap: &[Vec<T>],
-    row_index: usize,
-    column_index: usize,
-) -> HashSet<Coordinates> {
-    let mut coordinates = HashSet::new();
-
-    if column_index != 0 {
-        coordinates.insert((column_index - 1, row_index));
-    }
-
-    if column_index + 1 < heatmap[row_index].len() {
-        coordinates.insert((column_index + 1, row_index));
-    }
-
-    if row_index != 0 {
-        coordinates.insert((column_index, row_index - 1));
-    }
-
-    if row_index + 1 < heatmap.len() {
-        coordinates.insert((column_index, row_index + 1));
-    }
-
-    coordinates
-}
-
 fn visit_neighbors_that_are_not_nine(
-    heatmap: &[Vec<u32>],
-    row_index: usize,
-    column_index: usize,
+    heatmap: &Grid<u32>,
+    coordinates: Coordinates,
     mut visited_neighbors: HashSet<Coordinates>,
 ) -> HashSet<Coordinates> {
-    let neighbors = get_neighbors(heatmap, row_index, column_index);
+    for neighbor in heatmap.neighbors_4(coordinates) {
+        if heatmap[neighbor] != 9 && !visited_neighbors.contains(&neighbor) {
+            visited_neighbors.insert(neighbor);
 
-    for (x, y) in neighbors {
-        if heatmap[y][x] != 9 && !visited_neighbors.contains(&(x, y)) {
-            // let mut clone = visited_neighbors.clone();
-            visited_neighbors.insert((x, y));
-
-            visited_neighbors = visit_neighbors_that_are_not_nine(heatmap, y, x, visited_neighbors);
+            visited_neighbors =
+                visit_neighbors_that_are_not_nine(heatmap, neighbor, visited_neighbors);
         }
     }
 
     visited_neighbors
 }
 
-fn get_basins(heatmap: &[Vec<u32>], low_points: &[(usize, usize)]) -> Vec<Vec<u32>> {
+fn get_basins(heatmap: &Grid<u32>, low_points: &[Coordinates]) -> Vec<Vec<u32>> {
     let mut basins: Vec<Vec<u32>> = Vec::new();
 
-    for &(column_index, row_index) in low_points {
-        let basin_coordinates = visit_neighbors_that_are_not_nine(
-            heatmap,
-            row_index,
-            column_index,
-            HashSet::<Coordinates>::new(),
-        );
+    for &low_point in low_points {
+        let basin_coordinates =
+            visit_neighbors_that_are_not_nine(heatmap, low_point, HashSet::<Coordinates>::new());
 
         let basin_values = basin_coordinates
             .iter()
-            .map(|&(x, y)| heatmap[y][x])
+            .map(|&coordinates| heatmap[coordinates])
             .collect();
 
-        println!(
-            "We started with low point {} at x: {}, y: {} and got a set of neighbors with values {:?}",
-            heatmap[row_index][column_index], column_index, row_index, basin_values
+        ::tracing::event!(
+            ::tracing::Level::TRACE,
+            "We started with low point {} at {:?} and got a set of neighbors with values {:?}",
+            heatmap[low_point],
+            low_point,
+            basin_values
         );
 
         basins.push(basin_values);
     }
 
-    println!("Basins 1: {:?}", basins);
+    ::tracing::event!(::tracing::Level::DEBUG, "Basins 1: {:?}", basins);
 
     basins
 }
@@ -139,73 +96,55 @@ struct HeatMapCell {
     visited: Cell<bool>,
 }
 
-fn visit_neighbors_that_are_not_nine_2(
-    heatmap: &[Vec<HeatMapCell>],
-    row_index: usize,
-    column_index: usize,
-) {
-    let neighbors = get_neighbors(heatmap, row_index, column_index);
+fn visit_neighbors_that_are_not_nine_2(heatmap: &Grid<HeatMapCell>, coordinates: Coordinates) {
+    for neighbor in heatmap.neighbors_4(coordinates) {
+        let cell = &heatmap[neighbor];
 
-    for (x, y) in neighbors {
-        if heatmap[y][x].value != 9 && !heatmap[y][x].visited.get() {
-            // let mut clone = visited_neighbors.clone();
-            heatmap[y][x].visited.set(true);
+        if cell.value != 9 && !cell.visited.get() {
+            cell.visited.set(true);
 
-            visit_neighbors_that_are_not_nine_2(heatmap, y, x);
+            visit_neighbors_that_are_not_nine_2(heatmap, neighbor);
         }
     }
 }
 
-fn heatmap_u32_heatmap_cell(heatmap: &[Vec<u32>]) -> Vec<Vec<HeatMapCell>> {
-    let mut new_heatmap = Vec::new();
-
-    for line in heatmap {
-        new_heatmap.push(
-            line.iter()
-                .map(|value| HeatMapCell {
-                    value: *value,
-                    ..HeatMapCell::default()
-                })
-                .collect(),
-        );
-    }
-
-    new_heatmap
+fn heatmap_u32_heatmap_cell(heatmap: &Grid<u32>) -> Grid<HeatMapCell> {
+    heatmap.map(|&value| HeatMapCell {
+        value,
+        ..HeatMapCell::default()
+    })
 }
 
-fn get_visited_values(visited_heatmap: &[Vec<HeatMapCell>]) -> Vec<u32> {
-    let mut visited_values = Vec::new();
-
-    for line in visited_heatmap {
-        for cell in line {
-            if cell.visited.get() {
-                visited_values.push(cell.value);
-            }
-        }
-    }
-
-    visited_values
+fn get_visited_values(visited_heatmap: &Grid<HeatMapCell>) -> Vec<u32> {
+    visited_heatmap
+        .iter()
+        .filter(|&(_, cell)| cell.visited.get())
+        .map(|(_, cell)| cell.value)
+        .collect()
 }
 
-fn get_basins_2(heatmap: &[Vec<u32>], low_points: &[(usize, usize)]) -> Vec<Vec<u32>> {
+fn get_basins_2(heatmap: &Grid<u32>, low_points: &[Coordinates]) -> Vec<Vec<u32>> {
     let mut basins: Vec<Vec<u32>> = Vec::new();
 
-    for &(column_index, row_index) in low_points {
+    for &low_point in low_points {
         let visitable_heatmap = heatmap_u32_heatmap_cell(heatmap);
 
-        visit_neighbors_that_are_not_nine_2(&visitable_heatmap, row_index, column_index);
+        visit_neighbors_that_are_not_nine_2(&visitable_heatmap, low_point);
 
         let basin_values = get_visited_values(&visitable_heatmap);
 
-        println!(
-            "We started with low point {} at x: {}, y: {} and got a set of neighbors with values {:?}",
-            heatmap[row_index][column_index], column_index, row_index, basin_values
+        ::tracing::event!(
+            ::tracing::Level::TRACE,
+            "We started with low point {} at {:?} and got a set of neighbors with values {:?}",
+            heatmap[low_point],
+            low_point,
+            basin_values
         );
 
         basins.push(basin_values);
     }
 
-    println!("Basins 2: {:?}", basins);
+    ::tracing::event!(::tracing::Level::DEBUG, "Basins 2: {:?}", basins);
 
     basins
 }
@@ -221,59 +160,77 @@ fn calculate_basin_scores(basins: &[Vec<u32>]) -> Vec<usize> {
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_09/input.txt").lines().collect();
+    type Parsed = (Grid<u32>, Vec<Coordinates>);
 
-        let heatmap = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let heatmap = parse_heatmap(input)?;
 
+        // both parts start from the low points
         let low_points = get_low_points(&heatmap);
 
-        PartSolution::U32(calculate_risk_level(&low_points, &heatmap))
+        Ok((heatmap, low_points))
     }
 
-    #[expect(clippy::disallowed_macros, reason = "No pretty needed in actual code")]
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_09/input.txt").lines().collect();
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let &(ref heatmap, ref low_points) = parsed;
 
-        let heatmap = parse_lines(&lines);
-
-        let low_points = get_low_points(&heatmap);
+        Ok(PartSolution::U32(calculate_risk_level(low_points, heatmap)))
+    }
 
-        let basins = get_basins(&heatmap, &low_points);
+    #[expect(clippy::disallowed_macros, reason = "No pretty needed in actual code")]
+    #[expect(
+        clippy::panic_in_result_fn,
+        reason = "Both basin algorithms have to agree"
+    )]
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let &(ref heatmap, ref low_points) = parsed;
+
+        let basins = get_basins(heatmap, low_points);
         let basin_scores = calculate_basin_scores(&basins);
 
-        let basins_2 = get_basins_2(&heatmap, &low_points);
+        let basins_2 = get_basins_2(heatmap, low_points);
         let basin_scores_2 = calculate_basin_scores(&basins_2);
 
         assert_eq!(basin_scores, basin_scores_2);
 
-        PartSolution::USize(basin_scores.iter().take(3).product::<usize>())
+        Ok(PartSolution::USize(
+            basin_scores.iter().take(3).product::<usize>(),
+        ))
     }
 }
 
 #[cfg(test)]
 mod test {
-    fn get_example() -> Vec<&'static str> {
-        include_str!("day_09/example.txt").lines().collect()
+    fn get_example() -> &'static str {
+        include_str!("day_09/example.txt")
     }
 
     mod part_1 {
         use pretty_assertions::assert_eq;
 
         use super::get_example;
-        use crate::day_09::{Solution, calculate_risk_level, get_low_points, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_09::{INPUT, Solution, calculate_risk_level, get_low_points, parse_heatmap};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(585));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(585)
+            );
         }
 
         #[test]
         fn example() {
-            let lines = get_example();
-
-            let heatmap = parse_lines(&lines);
+            let heatmap = parse_heatmap(get_example()).unwrap();
 
             let low_points = get_low_points(&heatmap);
 
@@ -286,20 +243,22 @@ mod test {
 
         use super::get_example;
         use crate::day_09::{
-            Solution, calculate_basin_scores, get_basins, get_basins_2, get_low_points, parse_lines,
+            INPUT, Solution, calculate_basin_scores, get_basins, get_basins_2, get_low_points,
+            parse_heatmap,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::USize(827_904));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::USize(827_904)
+            );
         }
 
         #[test]
         fn example() {
-            let lines = get_example();
-
-            let heatmap = parse_lines(&lines);
+            let heatmap = parse_heatmap(get_example()).unwrap();
 
             let low_points = get_low_points(&heatmap);
 
@@ -1,7 +1,11 @@
-use crate::shared::{Day, PartSolution};
+use shared::parse::ParseError;
+
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_10/input.txt");
 
 #[derive(PartialEq, Copy, Clone, Debug)]
-enum Symbol {
+pub enum Symbol {
     OpenParentheses,
     CloseParentheses,
     OpenBracket,
@@ -123,65 +127,93 @@ fn calculate_score(completion: &[Symbol]) -> u64 {
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_10/input.txt").lines().collect();
+    type Parsed = Vec<Vec<Symbol>>;
 
-        let into_symbols: Vec<Vec<Symbol>> = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
+
+        Ok(parse_lines(&lines)?)
+    }
 
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
         let mut first_illegal_characters: Vec<Symbol> = Vec::new();
 
-        for line_into_symbols in into_symbols {
-            match find_first_illegal_character(&line_into_symbols) {
-                Some(symbol) => first_illegal_characters.push(symbol),
-                None => println!("All good"),
+        for line_into_symbols in parsed {
+            if let Some(symbol) = find_first_illegal_character(line_into_symbols) {
+                first_illegal_characters.push(symbol);
+            } else {
+                ::tracing::event!(::tracing::Level::TRACE, "All good");
             }
         }
 
-        PartSolution::U32(calculate_winnings(&first_illegal_characters))
+        Ok(PartSolution::U32(calculate_winnings(
+            &first_illegal_characters,
+        )))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_10/input.txt").lines().collect();
-
-        let into_symbols: Vec<Vec<Symbol>> = parse_lines(&lines);
-
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
         let mut scores: Vec<u64> = Vec::new();
 
-        for valid_line in into_symbols
+        for valid_line in parsed
             .iter()
             .filter(|line_into_symbols| find_first_illegal_character(line_into_symbols).is_none())
         {
             let completion: Vec<Symbol> = calculate_completion(valid_line);
 
-            scores.push(calculate_score(&completion));
+            // complete lines need no completion, so they don't score
+            if !completion.is_empty() {
+                scores.push(calculate_score(&completion));
+            }
         }
 
         scores.sort_unstable();
 
-        PartSolution::U64(scores[scores.len() / 2])
+        let middle = scores
+            .get(scores.len() / 2)
+            .copied()
+            .ok_or_else(|| DayError::NoSolution("no line is incomplete".to_owned()))?;
+
+        Ok(PartSolution::U64(middle))
     }
 }
 
-fn parse_lines(lines: &[&str]) -> Vec<Vec<Symbol>> {
+fn parse_lines(lines: &[&str]) -> Result<Vec<Vec<Symbol>>, ParseError> {
     let mut parsed = Vec::new();
-    for line in lines {
+    for (line_index, line) in lines.iter().enumerate() {
         parsed.push(
             line.chars()
-                .map(|x| match x {
-                    '(' => Symbol::OpenParentheses,
-                    ')' => Symbol::CloseParentheses,
-                    '[' => Symbol::OpenBracket,
-                    ']' => Symbol::CloseBracket,
-                    '{' => Symbol::OpenBrace,
-                    '}' => Symbol::CloseBrace,
-                    '<' => Symbol::OpenChevron,
-                    '>' => Symbol::CloseChevron,
-                    _ => panic!("OMG WTF BBQ"),
+                .enumerate()
+                .map(|(column_index, x)| {
+                    Ok(match x {
+                        '(' => Symbol::OpenParentheses,
+                        ')' => Symbol::CloseParentheses,
+                        '[' => Symbol::OpenBracket,
+                        ']' => Symbol::CloseBracket,
+                        '{' => Symbol::OpenBrace,
+                        '}' => Symbol::CloseBrace,
+                        '<' => Symbol::OpenChevron,
+                        '>' => Symbol::CloseChevron,
+                        _ => {
+                            return Err(ParseError::new(
+                                line_index,
+                                column_index,
+                                format!("`{}` is not a bracket", x),
+                            ));
+                        },
+                    })
                 })
-                .collect(),
+                .collect::<Result<_, _>>()?,
         );
     }
-    parsed
+    Ok(parsed)
 }
 
 #[cfg(test)]
@@ -195,20 +227,23 @@ mod test {
 
         use super::get_example;
         use crate::day_10::{
-            Solution, Symbol, calculate_winnings, find_first_illegal_character, parse_lines,
+            INPUT, Solution, Symbol, calculate_winnings, find_first_illegal_character, parse_lines,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(392_139));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(392_139)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let into_symbols: Vec<Vec<Symbol>> = parse_lines(&lines);
+            let into_symbols: Vec<Vec<Symbol>> = parse_lines(&lines).unwrap();
 
             let mut first_illegal_characters: Vec<Symbol> = Vec::new();
 
@@ -228,21 +263,24 @@ mod test {
 
         use super::get_example;
         use crate::day_10::{
-            Solution, Symbol, calculate_completion, calculate_score, find_first_illegal_character,
-            parse_lines,
+            INPUT, Solution, Symbol, calculate_completion, calculate_score,
+            find_first_illegal_character, parse_lines,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U64(4_001_832_844));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U64(4_001_832_844)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let into_symbols: Vec<Vec<Symbol>> = parse_lines(&lines);
+            let into_symbols: Vec<Vec<Symbol>> = parse_lines(&lines).unwrap();
 
             let mut scores: Vec<u64> = Vec::new();
 
@@ -258,5 +296,18 @@ mod test {
 
             assert_eq!(288_957, scores[scores.len() / 2]);
         }
+
+        #[test]
+        fn nothing_incomplete() {
+            for input in ["", "()\n[<>]", "(]"] {
+                assert_eq!(
+                    "No solution: no line is incomplete",
+                    solve(&Solution {}, 2, input).unwrap_err().to_string()
+                );
+            }
+
+            // the complete line doesn't count as a score of 0
+            assert_eq!(Ok(PartSolution::U64(1)), solve(&Solution {}, 2, "()\n("));
+        }
     }
 }
@@ -1,140 +1,86 @@
-use std::cell::Cell;
+use std::convert::Infallible;
 
-use hashbrown::HashSet;
+use shared::automaton::{Automaton, Edges};
+use shared::grid::{Grid, OFFSETS_8};
+use shared::parse::{ParseError, parse_digit};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-type Octopus = Cell<u8>;
-type Coordinates = (usize, usize);
+pub const INPUT: &str = include_str!("day_11/input.txt");
 
-fn parse_lines(lines: &[&str]) -> Vec<Vec<Octopus>> {
-    let mut field = Vec::new();
+/// The energy level of each octopus.
+type OctopusField = Grid<u32>;
 
-    for line in lines {
-        field.push(
-            line.chars()
-                .map(|x| x.to_digit(10).unwrap() as u8)
-                .map(Cell::new)
-                .collect(),
-        );
-    }
-
-    field
+fn parse_octopus_field(input: &str) -> Result<OctopusField, ParseError> {
+    Ok(Grid::parse(input, |(row, column), c| {
+        parse_digit(row, column, c)
+    })?)
 }
 
-fn get_neighbors<T>(
-    octopus_field: &[Vec<T>],
-    row_index: usize,
-    column_index: usize,
-) -> HashSet<Coordinates> {
-    let mut neighbors = HashSet::new();
-
-    let rows = octopus_field.len();
-    let columns = octopus_field.first().map(Vec::len).unwrap_or_default();
-
-    // clockwise:
-    // left up
-    // up
-    // right up
-    // right
-    // right down
-    // bottom
-    // left down
-    // left
-
-    let can_go_left = column_index > 0;
-    let can_go_up = row_index > 0;
-
-    let can_go_right = column_index + 1 < columns;
-    let can_go_down = row_index + 1 < rows;
-
-    // left up
-    if can_go_left && can_go_up {
-        neighbors.insert((row_index - 1, column_index - 1));
-    }
-
-    // up
-    if can_go_up {
-        neighbors.insert((row_index - 1, column_index));
-    }
-
-    // right up
-    if can_go_up && can_go_right {
-        neighbors.insert((row_index - 1, column_index + 1));
-    }
-
-    // right
-    if can_go_right {
-        neighbors.insert((row_index, column_index + 1));
-    }
-
-    // right down
-    if can_go_down && can_go_right {
-        neighbors.insert((row_index + 1, column_index + 1));
-    }
-
-    // down
-    if can_go_down {
-        neighbors.insert((row_index + 1, column_index));
-    }
+/// An octopus while its flashes spread through the field.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Octopus {
+    Charging(u32),
+    /// Over 9, so it lights up its neighbors next.
+    Flashing,
+    /// Done for this step.
+    Flashed,
+}
 
-    // left down
-    if can_go_down && can_go_left {
-        neighbors.insert((row_index + 1, column_index - 1));
+impl Octopus {
+    fn charged(energy: u32) -> Self {
+        if energy > 9 {
+            Octopus::Flashing
+        } else {
+            Octopus::Charging(energy)
+        }
     }
+}
 
-    // left
-    if can_go_left {
-        neighbors.insert((row_index, column_index - 1));
+/// Every octopus that flashed last round adds one to its neighbors, which might make them flash
+/// in turn.
+#[expect(
+    clippy::trivially_copy_pass_by_ref,
+    reason = "Rules get the cell by reference"
+)]
+fn spread_flashes(octopus: &Octopus, neighbors: [&Octopus; 8]) -> Octopus {
+    match *octopus {
+        Octopus::Charging(energy) => {
+            let flashing_neighbors = neighbors
+                .into_iter()
+                .filter(|&&neighbor| neighbor == Octopus::Flashing)
+                .count();
+
+            Octopus::charged(energy + u32::try_from(flashing_neighbors).unwrap_or(u32::MAX))
+        },
+        Octopus::Flashing | Octopus::Flashed => Octopus::Flashed,
     }
-
-    neighbors
 }
 
-fn process_flash(octopus_field: &[Vec<Octopus>], row_index: usize, column_index: usize) -> u32 {
-    let mut flashed = 0;
-    let octopus = &octopus_field[row_index][column_index];
-
-    if octopus.get() > 9 {
-        octopus.set(0);
-
-        let neighbors = get_neighbors(octopus_field, row_index, column_index);
-
-        for (neighbor_row_index, neighbor_column_index) in neighbors {
-            let neighbor_octopus = &octopus_field[neighbor_row_index][neighbor_column_index];
-
-            let new_neighbor_energy = match neighbor_octopus.get() {
-                0 => 0, // once flashed, we don't flash until next round
-                x => x + 1,
-            };
-
-            neighbor_octopus.set(new_neighbor_energy);
-
-            flashed += process_flash(octopus_field, neighbor_row_index, neighbor_column_index);
-        }
+/// Returns how many octopuses flashed.
+fn step(octopus_field: &mut OctopusField) -> usize {
+    let charged = octopus_field.map(|&energy| Octopus::charged(energy + 1));
 
-        flashed += 1;
-    }
+    // nothing flashes beyond the edges
+    let mut automaton = Automaton::new(charged, OFFSETS_8, Edges::Fixed(Octopus::Flashed));
 
-    flashed
-}
+    // every octopus flashes at most once, so it settles within one round per octopus
+    let max_rounds = u64::try_from(octopus_field.len())
+        .unwrap_or(u64::MAX)
+        .saturating_add(1);
 
-fn step(octopus_field: &[Vec<Octopus>]) -> u32 {
-    for row in octopus_field {
-        for octopus in row {
-            let val = octopus.get();
+    let Ok(_) = automaton.run_until_stable(max_rounds, spread_flashes, |_| Ok::<_, Infallible>(()));
 
-            octopus.set(val + 1);
-        }
-    }
+    let mut flashed: usize = 0;
 
-    let mut flashed: u32 = 0;
+    *octopus_field = automaton.cells().map(|&octopus| match octopus {
+        Octopus::Charging(energy) => energy,
+        Octopus::Flashing | Octopus::Flashed => {
+            flashed += 1;
 
-    for row_index in 0..octopus_field.len() {
-        for column_index in 0..octopus_field[row_index].len() {
-            flashed += process_flash(octopus_field, row_index, column_index);
-        }
-    }
+            0
+        },
+    });
 
     flashed
 }
@@ -142,34 +88,44 @@ fn step(octopus_field: &[Vec<Octopus>]) -> u32 {
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_11/input.txt").lines().collect();
+    type Parsed = OctopusField;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        Ok(parse_octopus_field(input)?)
+    }
 
-        let octopus_field = parse_lines(&lines);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let mut octopus_field = parsed.clone();
 
         let mut flashes = 0;
 
         for _ in 0..100 {
-            flashes += step(&octopus_field);
+            flashes += step(&mut octopus_field);
         }
 
-        PartSolution::U32(flashes)
+        Ok(PartSolution::USize(flashes))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_11/input.txt").lines().collect();
-
-        let octopus_field = parse_lines(&lines);
-        let field_size =
-            octopus_field.len() * octopus_field.first().map(Vec::len).unwrap_or_default();
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let mut octopus_field = parsed.clone();
 
         let mut steps: u32 = 0;
         loop {
-            let flashes = step(&octopus_field);
+            cancel.check()?;
+
+            let flashes = step(&mut octopus_field);
 
             steps += 1;
-            if flashes == field_size as u32 {
-                return PartSolution::U32(steps);
+            if flashes == octopus_field.len() {
+                return Ok(PartSolution::U32(steps));
             }
         }
     }
@@ -177,42 +133,33 @@ impl Day for Solution {
 
 #[cfg(test)]
 mod test {
-    fn get_example() -> Vec<&'static str> {
-        include_str!("day_11/example.txt").lines().collect()
+    fn get_example() -> &'static str {
+        include_str!("day_11/example.txt")
     }
 
     mod part_1 {
         use pretty_assertions::assert_eq;
 
         use super::get_example;
-        use crate::day_11::{Octopus, Solution, parse_lines, step};
-        use crate::shared::{Day as _, PartSolution};
-
-        fn back_to_vec_string(octopus_field: &[Vec<Octopus>]) -> Vec<String> {
-            let mut lines = Vec::new();
-
-            for octopus_line in octopus_field {
-                lines.push(octopus_line.iter().map(|x| x.get().to_string()).collect());
-            }
-
-            lines
-        }
+        use crate::day_11::{INPUT, Solution, parse_octopus_field, step};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(1_755));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::USize(1_755)
+            );
         }
 
         #[test]
         fn example() {
-            let lines = get_example();
-
-            let octopus_field = parse_lines(&lines);
+            let mut octopus_field = parse_octopus_field(get_example()).unwrap();
 
             let mut flashes = 0;
 
             for _ in 0..100 {
-                flashes += step(&octopus_field);
+                flashes += step(&mut octopus_field);
             }
 
             assert_eq!(1656, flashes);
@@ -220,22 +167,21 @@ mod test {
 
         #[test]
         fn example_step_by_step() {
-            let lines = &["11111", "19991", "19191", "19991", "11111"];
-
-            let octopus_field = parse_lines(lines);
+            let mut octopus_field =
+                parse_octopus_field("11111\n19991\n19191\n19991\n11111").unwrap();
 
-            let _: u32 = step(&octopus_field);
+            let _: usize = step(&mut octopus_field);
 
             assert_eq!(
                 vec!["34543", "40004", "50005", "40004", "34543"],
-                back_to_vec_string(&octopus_field)
+                octopus_field.lines()
             );
 
-            let _: u32 = step(&octopus_field);
+            let _: usize = step(&mut octopus_field);
 
             assert_eq!(
                 vec!["45654", "51115", "61116", "51115", "45654",],
-                back_to_vec_string(&octopus_field)
+                octopus_field.lines()
             );
         }
     }
@@ -244,29 +190,27 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use super::get_example;
-        use crate::day_11::{Solution, parse_lines, step};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_11::{INPUT, Solution, parse_octopus_field, step};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(212));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U32(212)
+            );
         }
 
         #[test]
         fn example() {
-            let lines = get_example();
-
-            let octopus_field = parse_lines(&lines);
-
-            let field_size =
-                octopus_field.len() * octopus_field.first().map(Vec::len).unwrap_or_default();
+            let mut octopus_field = parse_octopus_field(get_example()).unwrap();
 
             let mut steps: u32 = 0;
             loop {
-                let flashes = step(&octopus_field);
+                let flashes = step(&mut octopus_field);
 
                 steps += 1;
-                if flashes == field_size as u32 {
+                if flashes == octopus_field.len() {
                     assert_eq!(steps, 195);
                     break;
                 }
@@ -1,198 +1,214 @@
-use std::cell::RefCell;
-use std::hash::{Hash, Hasher};
-use std::rc::Rc;
+use hashbrown::HashMap;
+use shared::memo::Memo;
+use shared::parse::ParseError;
+use shared::search::Graph;
 
-use hashbrown::{HashMap, HashSet};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-use crate::shared::{Day, PartSolution};
+pub const INPUT: &str = include_str!("day_12/input.txt");
 
-#[derive(Eq, Default, Debug)]
-struct Cave {
-    name: String,
-    targets: RefCell<Caves>,
+fn is_small(cave: &str) -> bool {
+    cave.to_lowercase() == cave
 }
 
-impl Cave {
-    fn is_end(&self) -> bool {
-        self.name == "end"
+/// A path between two caves, by name.
+type Connection = (String, String);
+
+fn parse_lines(lines: &[&str]) -> Result<Vec<Connection>, ParseError> {
+    let mut connections = Vec::new();
+
+    for (line_index, line) in lines.iter().enumerate() {
+        let (left, right) = line
+            .split_once('-')
+            .ok_or_else(|| ParseError::new(line_index, 0, "expected `from-to`".to_owned()))?;
+
+        // we could go back and forth between them forever
+        if !is_small(left) && !is_small(right) {
+            return Err(ParseError::new(
+                line_index,
+                0,
+                format!(
+                    "`{}` and `{}` are both big caves, so there's no end to the paths",
+                    left, right
+                ),
+            ));
+        }
+
+        connections.push((left.to_owned(), right.to_owned()));
     }
 
-    fn is_small(&self) -> bool {
-        self.name.to_lowercase() == self.name
+    for required in ["start", "end"] {
+        if !connections
+            .iter()
+            .any(|&(ref left, ref right)| left == required || right == required)
+        {
+            return Err(ParseError::end_of_input(
+                lines,
+                format!("no path mentions the `{}` cave", required),
+            ));
+        }
     }
+
+    Ok(connections)
 }
 
-impl PartialEq for Cave {
-    fn eq(&self, other: &Self) -> bool {
-        self.name == other.name
-    }
+/// Every cave, with the caves it has a path to.
+struct CaveSystem<'connections> {
+    caves: HashMap<&'connections str, Vec<&'connections str>>,
 }
 
-impl Hash for Cave {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.name.hash(state);
+impl<'connections> Graph for CaveSystem<'connections> {
+    type Node = &'connections str;
+    type Cost = u32;
+
+    fn neighbors(
+        &self,
+        node: &&'connections str,
+    ) -> impl Iterator<Item = (&'connections str, u32)> {
+        self.caves
+            .get(node)
+            .into_iter()
+            .flatten()
+            .map(|&target| (target, 1))
     }
 }
 
-type Caves = HashSet<Rc<Cave>>;
+fn build_cave_system(connections: &[Connection]) -> CaveSystem<'_> {
+    let mut caves: HashMap<&str, Vec<&str>> = HashMap::new();
 
-fn get_or_insert_cave(caves: &mut Caves, cave_name: &str) -> Rc<Cave> {
-    let cave = Rc::new(Cave {
-        name: cave_name.to_owned(),
-        ..Cave::default()
-    });
-
-    if let Some(found_cave) = caves.get(&cave) {
-        Rc::clone(found_cave)
-    } else {
-        caves.insert(Rc::clone(&cave));
-
-        cave
+    for &(ref left, ref right) in connections {
+        caves.entry(left).or_default().push(right);
+        caves.entry(right).or_default().push(left);
     }
-}
-
-fn add_path(caves: &mut Caves, from: &str, to: &str) {
-    let from_cave = get_or_insert_cave(caves, from);
-    let to_cave = get_or_insert_cave(caves, to);
 
-    from_cave.targets.borrow_mut().insert(Rc::clone(&to_cave));
-    to_cave.targets.borrow_mut().insert(from_cave);
+    CaveSystem { caves }
 }
 
-fn build_cave_system(lines: &[&str]) -> Caves {
-    let mut caves: Caves = HashSet::default();
-
-    for line in lines {
-        let pieces: Vec<&str> = line.split('-').collect();
-
-        let left = pieces.first().unwrap();
-        let right = pieces.get(1).unwrap();
+/// Where we are, and what we can still visit from there.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Route<'connections> {
+    cave: &'connections str,
+    /// A bit per small cave we went through, see `small_cave_bits`.
+    visited: u64,
+    /// Whether we still have our one visit to a small cave we went through before.
+    may_revisit: bool,
+}
 
-        add_path(&mut caves, left, right);
+/// Gives every small cave its own bit, so the caves a route went through fit in a `u64`.
+fn small_cave_bits<'connections>(
+    cave_system: &CaveSystem<'connections>,
+) -> Result<HashMap<&'connections str, u64>, DayError> {
+    let mut small_caves: Vec<&str> = cave_system
+        .caves
+        .keys()
+        .copied()
+        .filter(|cave| is_small(cave))
+        .collect();
+
+    small_caves.sort_unstable();
+
+    if small_caves.len() > 64 {
+        return Err(DayError::NoSolution(format!(
+            "{} small caves don't fit in a u64",
+            small_caves.len()
+        )));
     }
 
-    caves
+    Ok(small_caves
+        .into_iter()
+        .enumerate()
+        .map(|(index, cave)| (cave, 1 << index))
+        .collect())
 }
 
-fn navigate_caves<F>(
-    cave: &Rc<Cave>,
-    can_revisit: &F,
-    mut visited: Vec<Rc<Cave>>,
-) -> Vec<Vec<Rc<Cave>>>
-where
-    F: Fn(&[Rc<Cave>], &Rc<Cave>) -> bool,
-{
-    let mut solutions = Vec::new();
+/// The number of paths from `start` to `end` that go through small caves at most once, except
+/// for one small cave that can be visited twice when `may_revisit` is set.
+///
+/// Paths that share a cave and the small caves they went through so far continue in the same
+/// number of ways, so we only count those once.
+fn count_paths(cave_system: &CaveSystem<'_>, may_revisit: bool) -> Result<usize, DayError> {
+    let bits = small_cave_bits(cave_system)?;
 
-    let vc = Rc::clone(cave);
-    visited.push(vc);
+    let start = Route {
+        cave: "start",
+        visited: bits.get("start").copied().unwrap_or_default(),
+        may_revisit,
+    };
 
-    if cave.is_end() {
-        solutions.push(visited);
-    } else {
-        for target_cave in &*cave.targets.borrow() {
-            if can_revisit(&visited, target_cave) {
-                let visited_new = visited.clone();
+    let mut memo = Memo::new();
 
-                println!("Visiting {} -> {}", cave.name, target_cave.name);
+    let paths = memo.solve(&start, |route, lookup| {
+        let mut paths = 0;
 
-                let mut new_solutions = navigate_caves(target_cave, can_revisit, visited_new);
+        for (cave, _) in cave_system.neighbors(&route.cave) {
+            if cave == "end" {
+                paths += 1;
 
-                solutions.append(&mut new_solutions);
+                continue;
             }
-        }
-    }
 
-    solutions
-}
-
-fn calculate_all_paths<F>(cave_system: &Caves, can_revisit: F) -> usize
-where
-    F: Fn(&[Rc<Cave>], &Rc<Cave>) -> bool,
-{
-    let start = cave_system.iter().find(|c| c.name == "start").unwrap();
-
-    let solutions = navigate_caves(start, &can_revisit, Vec::new());
-
-    println!("The end, we visited the following paths to get here.");
-
-    let mut debug_lines = solutions
-        .iter()
-        .map(|solution| {
-            solution
-                .iter()
-                .map(|x| x.name.clone())
-                .collect::<Vec<_>>()
-                .join(",")
-        })
-        .collect::<Vec<_>>();
-
-    debug_lines.sort();
-    for line in debug_lines {
-        println!("{}", line);
-    }
+            if cave == "start" {
+                continue;
+            }
 
-    solutions.len()
-}
+            let bit = bits.get(cave).copied().unwrap_or_default();
+
+            let next = if route.visited & bit == 0 {
+                Route {
+                    cave,
+                    visited: route.visited | bit,
+                    may_revisit: route.may_revisit,
+                }
+            } else if route.may_revisit {
+                Route {
+                    cave,
+                    visited: route.visited,
+                    may_revisit: false,
+                }
+            } else {
+                continue;
+            };
+
+            paths += lookup.get(&next).unwrap_or_default();
+        }
 
-fn can_visit_part_1(visited_caves: &[Rc<Cave>], cave: &Rc<Cave>) -> bool {
-    if cave.is_small() {
-        !visited_caves.contains(cave)
-    } else {
-        true
-    }
-}
+        paths
+    });
 
-fn can_visit_part_2(visited_caves: &[Rc<Cave>], cave: &Rc<Cave>) -> bool {
-    let cave_name = cave.name.clone();
-
-    if cave_name == "start" || cave_name == "end" {
-        !visited_caves.contains(cave) // only if we haven't visited them yet
-    } else if cave.is_small() && visited_caves.contains(cave) {
-        // if the cave is small and we haven't visited it, fall through
-        // BUT we can visit ONE small cave twice.
-        // so let's count the small caves, and see if we visited ANY twice
-        // if we didn't, we can visit this one again
-        let mut visit_counts: HashMap<Rc<Cave>, u32> = HashMap::new();
-
-        for visited_cave in visited_caves.iter().filter(|x| x.is_small()) {
-            let visit_count = visit_counts
-                .entry(Rc::clone(visited_cave))
-                .and_modify(|c| *c += 1)
-                .or_insert(1);
-
-            if *visit_count >= 2 {
-                return false;
-            }
-        }
+    ::tracing::event!(::tracing::Level::DEBUG, "Routes: {}", memo.stats());
 
-        true
-    } else {
-        true // unlimited
-    }
+    Ok(paths)
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_12/input.txt").lines().collect();
+    type Parsed = Vec<Connection>;
 
-        let cave_system = build_cave_system(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let paths: usize = calculate_all_paths(&cave_system, can_visit_part_1);
-
-        PartSolution::USize(paths)
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_12/input.txt").lines().collect();
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let cave_system = build_cave_system(parsed);
 
-        let cave_system = build_cave_system(&lines);
+        Ok(PartSolution::USize(count_paths(&cave_system, false)?))
+    }
 
-        let paths: usize = calculate_all_paths(&cave_system, can_visit_part_2);
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let cave_system = build_cave_system(parsed);
 
-        PartSolution::USize(paths)
+        Ok(PartSolution::USize(count_paths(&cave_system, true)?))
     }
 }
 
@@ -218,21 +234,26 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use super::{get_example, get_example_even_larger, get_example_slightly_larger};
-        use crate::day_12::{Solution, build_cave_system, calculate_all_paths, can_visit_part_1};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_12::{INPUT, Solution, build_cave_system, count_paths, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::USize(4495));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::USize(4495)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let cave_system = build_cave_system(&lines);
+            let connections = parse_lines(&lines).unwrap();
 
-            let paths: usize = calculate_all_paths(&cave_system, can_visit_part_1);
+            let cave_system = build_cave_system(&connections);
+
+            let paths = count_paths(&cave_system, false).unwrap();
 
             assert_eq!(paths, 10);
         }
@@ -241,9 +262,11 @@ mod test {
         fn example_slightly_larger() {
             let lines = get_example_slightly_larger();
 
-            let cave_system = build_cave_system(&lines);
+            let connections = parse_lines(&lines).unwrap();
+
+            let cave_system = build_cave_system(&connections);
 
-            let paths: usize = calculate_all_paths(&cave_system, can_visit_part_1);
+            let paths = count_paths(&cave_system, false).unwrap();
 
             assert_eq!(paths, 19);
         }
@@ -252,12 +275,24 @@ mod test {
         fn example_even_larger() {
             let lines = get_example_even_larger();
 
-            let cave_system = build_cave_system(&lines);
+            let connections = parse_lines(&lines).unwrap();
+
+            let cave_system = build_cave_system(&connections);
 
-            let paths: usize = calculate_all_paths(&cave_system, can_visit_part_1);
+            let paths = count_paths(&cave_system, false).unwrap();
 
             assert_eq!(paths, 226);
         }
+
+        #[test]
+        fn big_caves_next_to_each_other() {
+            assert_eq!(
+                "line 3, column 1: `A` and `B` are both big caves, so there's no end to the paths",
+                parse_lines(&["start-A", "A-end", "A-B"])
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
     }
 
     mod part_2 {
@@ -265,21 +300,26 @@ mod test {
 
         use super::get_example;
         use crate::day_12::test::{get_example_even_larger, get_example_slightly_larger};
-        use crate::day_12::{Solution, build_cave_system, calculate_all_paths, can_visit_part_2};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_12::{INPUT, Solution, build_cave_system, count_paths, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::USize(131_254));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::USize(131_254)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let cave_system = build_cave_system(&lines);
+            let connections = parse_lines(&lines).unwrap();
+
+            let cave_system = build_cave_system(&connections);
 
-            let paths: usize = calculate_all_paths(&cave_system, can_visit_part_2);
+            let paths = count_paths(&cave_system, true).unwrap();
 
             assert_eq!(paths, 36);
         }
@@ -288,9 +328,11 @@ mod test {
         fn example_slightly_larger() {
             let lines = get_example_slightly_larger();
 
-            let cave_system = build_cave_system(&lines);
+            let connections = parse_lines(&lines).unwrap();
 
-            let paths: usize = calculate_all_paths(&cave_system, can_visit_part_2);
+            let cave_system = build_cave_system(&connections);
+
+            let paths = count_paths(&cave_system, true).unwrap();
 
             assert_eq!(paths, 103);
         }
@@ -299,9 +341,11 @@ mod test {
         fn example_even_larger() {
             let lines = get_example_even_larger();
 
-            let cave_system = build_cave_system(&lines);
+            let connections = parse_lines(&lines).unwrap();
+
+            let cave_system = build_cave_system(&connections);
 
-            let paths: usize = calculate_all_paths(&cave_system, can_visit_part_2);
+            let paths = count_paths(&cave_system, true).unwrap();
 
             assert_eq!(paths, 3509);
         }
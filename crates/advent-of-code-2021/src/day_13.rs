@@ -1,42 +1,59 @@
-use std::cell::Cell;
+use shared::grid::{Coordinates, Grid};
+use shared::parse::{ParseError, blocks, parse_separated, parse_token};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-enum Instruction {
+pub const INPUT: &str = include_str!("day_13/input.txt");
+
+/// `true` where there is a dot.
+type Paper = Grid<bool>;
+
+pub enum Instruction {
     X(usize),
     Y(usize),
 }
 
-fn parse_lines(lines: &[&str]) -> (Vec<Vec<Cell<bool>>>, Vec<Instruction>) {
-    let mut field = Vec::new();
+fn parse_lines(lines: &[&str]) -> Result<(Paper, Vec<Instruction>), ParseError> {
+    let &[dots, folds] = blocks(lines).as_slice() else {
+        return Err(ParseError::end_of_input(
+            lines,
+            "expected the dots, an empty line, and the folds".to_owned(),
+        ));
+    };
 
     let mut coordinates = Vec::new();
 
-    for line in lines
-        .iter()
-        .filter(|l| !l.starts_with("fold") && !l.is_empty())
-    {
-        let split: Vec<_> = line.split(',').collect();
-
-        let x = split.first().unwrap().parse::<usize>().unwrap();
-        let y = split.get(1).unwrap().parse::<usize>().unwrap();
+    for (line_index, line) in dots.enumerate() {
+        let &[x, y] = parse_separated::<usize>(line_index, line, ',')?.as_slice() else {
+            return Err(ParseError::new(line_index, 0, "expected `x,y`".to_owned()));
+        };
 
-        coordinates.push((x, y));
+        coordinates.push((line_index, x, y));
     }
 
     let mut instructions: Vec<Instruction> = Vec::new();
 
-    for line in lines.iter().filter(|l| l.starts_with("fold")) {
-        let line_trimmed = line.replace("fold along ", "");
-        let split: Vec<_> = line_trimmed.split('=').collect();
+    for (line_index, line) in folds.enumerate() {
+        let (plane, x_y) = line
+            .strip_prefix("fold along ")
+            .and_then(|l| l.split_once('='))
+            .ok_or_else(|| {
+                ParseError::new(line_index, 0, "expected `fold along x=..`".to_owned())
+            })?;
 
-        let plane = split.first().unwrap().parse::<char>().unwrap();
-        let x_y = split.get(1).unwrap().parse::<usize>().unwrap();
+        let x_y = parse_token::<usize>(line_index, line, x_y)?;
 
         let instruction = match plane {
-            'x' => Instruction::X(x_y),
-            'y' => Instruction::Y(x_y),
-            _ => panic!("Unknown fold instruction"),
+            "x" => Instruction::X(x_y),
+            "y" => Instruction::Y(x_y),
+            _ => {
+                return Err(ParseError::at_token(
+                    line_index,
+                    line,
+                    plane,
+                    format!("cannot fold along `{}`", plane),
+                ));
+            },
         };
 
         instructions.push(instruction);
@@ -58,7 +75,7 @@ fn parse_lines(lines: &[&str]) -> (Vec<Vec<Cell<bool>>>, Vec<Instruction>) {
         .max()
         .map(|i| i * 2)
         .map(|i| i + 1)
-        .unwrap();
+        .ok_or_else(|| ParseError::end_of_input(lines, "no fold along x".to_owned()))?;
 
     let rows = instructions
         .iter()
@@ -72,102 +89,106 @@ fn parse_lines(lines: &[&str]) -> (Vec<Vec<Cell<bool>>>, Vec<Instruction>) {
         .max()
         .map(|i| i * 2)
         .map(|i| i + 1)
-        .unwrap();
-
-    for _ in 0..rows {
-        let mut v = Vec::new();
-        v.resize(columns, Cell::new(false));
-        field.push(v);
-    }
-
-    for (x, y) in coordinates {
-        field[y][x].set(true);
+        .ok_or_else(|| ParseError::end_of_input(lines, "no fold along y".to_owned()))?;
+
+    let mut field = Grid::new(columns, rows, false);
+
+    for (line_index, x, y) in coordinates {
+        *field.get_mut((y, x)).ok_or_else(|| {
+            ParseError::new(
+                line_index,
+                0,
+                format!("dot {},{} lies outside of the folds", x, y),
+            )
+        })? = true;
     }
 
-    (field, instructions)
+    Ok((field, instructions))
 }
 
-fn pretty_print(field: &[Vec<Cell<bool>>]) -> Vec<String> {
-    field
-        .iter()
-        .map(|row| {
-            row.iter()
-                .map(|c| if c.get() { "#" } else { "." })
-                .collect::<Vec<_>>()
-                .join("")
-        })
-        .collect()
+fn to_grid(field: &Paper) -> Grid<char> {
+    field.map(|&dot| if dot { '#' } else { '.' })
 }
 
-fn fold(field: &mut Vec<Vec<Cell<bool>>>, instruction: &Instruction) {
+/// Where `coordinates` ends up after folding along `instruction`. Dots on the fold line itself
+/// are outside of the folded paper.
+fn folded_coordinates(
+    (row, column): Coordinates,
+    instruction: &Instruction,
+) -> Option<Coordinates> {
     match *instruction {
-        Instruction::X(fold_on_column) => {
-            for row in field {
-                for c in 0..fold_on_column {
-                    let mirrored_column = (fold_on_column * 2) - c;
-
-                    let cell_value = row[c].get();
-
-                    let mirrored_value = row[mirrored_column].get();
-
-                    row[c].set(cell_value | mirrored_value);
-                }
-
-                row.truncate(fold_on_column);
-            }
+        Instruction::X(fold_on_column) if column > fold_on_column => {
+            Some((row, (fold_on_column * 2).checked_sub(column)?))
         },
-        Instruction::Y(fold_on_row) => {
-            let columns = field.first().map_or(0, Vec::len);
+        Instruction::Y(fold_on_row) if row > fold_on_row => {
+            Some(((fold_on_row * 2).checked_sub(row)?, column))
+        },
+        Instruction::X(_) | Instruction::Y(_) => Some((row, column)),
+    }
+}
 
-            for r in 0..fold_on_row {
-                let mirrored_row = (fold_on_row * 2) - r;
+fn fold(field: &mut Paper, instruction: &Instruction) {
+    let mut folded = match *instruction {
+        Instruction::X(fold_on_column) => Grid::new(fold_on_column, field.height(), false),
+        Instruction::Y(fold_on_row) => Grid::new(field.width(), fold_on_row, false),
+    };
+
+    for (coordinates, &dot) in field.iter() {
+        if let Some(cell) = folded_coordinates(coordinates, instruction)
+            .and_then(|folded_coordinates| folded.get_mut(folded_coordinates))
+        {
+            *cell |= dot;
+        }
+    }
 
-                #[expect(
-                    clippy::needless_range_loop,
-                    reason = "`c` is being used across multiple rows"
-                )]
-                for c in 0..columns {
-                    let cell_value = field[r][c].get();
+    *field = folded;
+}
 
-                    let mirrored_value = field[mirrored_row][c].get();
+pub struct Solution {}
 
-                    field[r][c].set(cell_value | mirrored_value);
-                }
-            }
+impl Day for Solution {
+    type Parsed = (Paper, Vec<Instruction>);
 
-            field.truncate(fold_on_row);
-        },
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
+
+        Ok(parse_lines(&lines)?)
     }
-}
 
-pub struct Solution {}
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let &(ref field, ref fold_instructions) = parsed;
 
-impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_13/input.txt").lines().collect();
+        let first_fold = fold_instructions
+            .first()
+            .ok_or_else(|| DayError::NoSolution("there are no folds".to_owned()))?;
 
-        let (mut field, fold_instructions) = parse_lines(&lines);
+        let mut field = field.clone();
 
-        fold(&mut field, fold_instructions.first().unwrap());
+        fold(&mut field, first_fold);
 
-        PartSolution::USize(
-            field
-                .iter()
-                .map(|r| r.iter().filter(|x| x.get()).count())
-                .sum::<usize>(),
-        )
+        Ok(PartSolution::USize(
+            field.iter().filter(|&(_, &dot)| dot).count(),
+        ))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_13/input.txt").lines().collect();
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let &(ref field, ref fold_instructions) = parsed;
 
-        let (mut field, fold_instructions) = parse_lines(&lines);
+        let mut field = field.clone();
 
         for fold_instruction in fold_instructions {
-            fold(&mut field, &fold_instruction);
+            fold(&mut field, fold_instruction);
         }
 
-        PartSolution::Vec(pretty_print(&field))
+        Ok(PartSolution::Grid(to_grid(&field)))
     }
 }
 
@@ -181,22 +202,25 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use super::get_example;
-        use crate::day_13::{Solution, fold, parse_lines, pretty_print};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_13::{INPUT, Solution, fold, parse_lines, to_grid};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::USize(638));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::USize(638)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let (mut field, fold_instructions) = parse_lines(&lines);
+            let (mut field, fold_instructions) = parse_lines(&lines).unwrap();
 
             println!("Field:");
-            for field_line in pretty_print(&field) {
+            for field_line in to_grid(&field).lines() {
                 println!("{}", field_line);
             }
 
@@ -215,23 +239,17 @@ mod test {
                 "...........",
             ]
             .iter()
-            .map(|l| (*l).to_owned())
+            .map(|l| l.chars().collect())
             .collect();
 
-            assert_eq!(lines_after_fold_1, pretty_print(&field));
+            assert_eq!(lines_after_fold_1, to_grid(&field).lines());
 
             println!("Field:");
-            for field_line in pretty_print(&field) {
+            for field_line in to_grid(&field).lines() {
                 println!("{}", field_line);
             }
 
-            assert_eq!(
-                17,
-                field
-                    .iter()
-                    .map(|r| r.iter().filter(|x| x.get()).count())
-                    .sum::<usize>()
-            );
+            assert_eq!(17, field.iter().filter(|&(_, &dot)| dot).count());
 
             fold(&mut field, fold_instructions.get(1).unwrap());
 
@@ -246,13 +264,13 @@ mod test {
                 ".....",
             ]
             .iter()
-            .map(|l| (*l).to_owned())
+            .map(|l| l.chars().collect())
             .collect();
 
-            assert_eq!(lines_after_fold_2, pretty_print(&field));
+            assert_eq!(lines_after_fold_2, to_grid(&field).lines());
 
             println!("Field:");
-            for field_line in pretty_print(&field) {
+            for field_line in to_grid(&field).lines() {
                 println!("{}", field_line);
             }
         }
@@ -260,13 +278,14 @@ mod test {
 
     mod part_2 {
         use pretty_assertions::assert_eq;
+        use shared::grid::Grid;
 
-        use crate::day_13::Solution;
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_13::{INPUT, Solution};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            let solution: Vec<String> = [
+            let solution = [
                 ".##....##..##..#..#.###...##..###..###..",
                 "#..#....#.#..#.#.#..#..#.#..#.#..#.#..#.",
                 "#.......#.#....##...###..#..#.#..#.###..",
@@ -274,11 +293,12 @@ mod test {
                 "#..#.#..#.#..#.#.#..#..#.#..#.#....#..#.",
                 ".##...##...##..#..#.###..#..#.#....###..",
             ]
-            .iter()
-            .map(|l| (*l).to_owned())
-            .collect();
+            .join("\n");
 
-            assert_eq!((Solution {}).part_2(), PartSolution::Vec(solution));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::Grid(Grid::parse(&solution, |_, c| Ok::<_, ()>(c)).unwrap())
+            );
         }
     }
 }
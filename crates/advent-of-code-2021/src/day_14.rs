@@ -1,55 +1,72 @@
-use std::hash::Hash;
-use std::ops::AddAssign;
-
 use hashbrown::HashMap;
+use shared::counter::Counter;
+use shared::parse::{ParseError, get_line, parse_token};
 
-use crate::shared::{Day, PartSolution};
-
-fn parse_lines(lines: &[&str]) -> (Vec<char>, HashMap<Key, char>) {
-    let mut dictionary = HashMap::new();
-
-    for line in lines.iter().skip(2) {
-        let split = line.split(" -> ").collect::<Vec<_>>();
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-        let from = (*split.first().unwrap()).to_owned();
+pub const INPUT: &str = include_str!("day_14/input.txt");
 
-        let cc = from.chars().collect::<Vec<char>>();
+fn parse_lines(lines: &[&str]) -> Result<(Vec<char>, HashMap<Key, char>), ParseError> {
+    let mut dictionary = HashMap::new();
 
-        let c0 = cc[0];
-        let c1 = cc[1];
+    for (line_index, &line) in lines.iter().enumerate().skip(2) {
+        let (from, to) = line
+            .split_once(" -> ")
+            .ok_or_else(|| ParseError::new(line_index, 0, "expected `AB -> C`".to_owned()))?;
+
+        let &[c0, c1] = from.chars().collect::<Vec<char>>().as_slice() else {
+            return Err(ParseError::at_token(
+                line_index,
+                line,
+                from,
+                format!("`{}` is not a pair of elements", from),
+            ));
+        };
 
-        let to = split.get(1).unwrap().parse::<char>().unwrap();
+        let to = parse_token::<char>(line_index, line, to)?;
 
         dictionary.insert(Key { c0, c1 }, to);
     }
 
-    let template = (lines[0]).chars().collect::<Vec<_>>();
+    let template = get_line(lines, 0)?.chars().collect::<Vec<_>>();
 
-    (template, dictionary)
+    if template.is_empty() {
+        return Err(ParseError::new(0, 0, "the template is empty".to_owned()));
+    }
+
+    Ok((template, dictionary))
 }
 
-#[derive(PartialEq, Eq, Hash)]
-struct Key {
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
+pub struct Key {
     c0: char,
     c1: char,
 }
 
-fn parse_lines_part_2(input: &[char]) -> HashMap<Key, u64> {
-    let mut map = HashMap::new();
-
-    for cc in input.array_windows::<2>() {
-        let key = Key {
+fn count_pairs(input: &[char]) -> Counter<Key> {
+    input
+        .array_windows::<2>()
+        .map(|cc| Key {
             c0: cc[0],
             c1: cc[1],
-        };
-
-        map.entry(key).and_modify(|c| *c += 1).or_insert(1);
-    }
+        })
+        .collect()
+}
 
-    map
+/// What goes between the elements of `key`, when there's a rule for it.
+fn insertion(pair_insertion_rules: &HashMap<Key, char>, key: &Key) -> Result<char, DayError> {
+    pair_insertion_rules.get(key).copied().ok_or_else(|| {
+        DayError::NoSolution(format!(
+            "there's no rule for the pair `{}{}`",
+            key.c0, key.c1
+        ))
+    })
 }
 
-fn parse_polymer(input: &[char], pair_insertion_rules: &HashMap<Key, char>) -> Vec<char> {
+fn parse_polymer(
+    input: &[char],
+    pair_insertion_rules: &HashMap<Key, char>,
+) -> Result<Vec<char>, DayError> {
     let mut new_string: Vec<char> = vec![input[0]];
 
     for cc in input.array_windows::<2>() {
@@ -58,124 +75,124 @@ fn parse_polymer(input: &[char], pair_insertion_rules: &HashMap<Key, char>) -> V
             c1: cc[1],
         };
 
-        let translated = pair_insertion_rules.get(&lookup).unwrap();
-
-        new_string.push(*translated);
+        new_string.push(insertion(pair_insertion_rules, &lookup)?);
         new_string.push(cc[1]);
     }
 
-    new_string
+    Ok(new_string)
 }
 
 fn parse_polymer_part_2(
-    input: &HashMap<Key, u64>,
+    input: &Counter<Key>,
     pair_insertion_rules: &HashMap<Key, char>,
-) -> HashMap<Key, u64> {
-    let mut new_counts = HashMap::new();
-
-    for (key, value) in input.iter().filter(|&(_, v)| *v > 0) {
-        let c_new = pair_insertion_rules.get(key).unwrap();
-
-        let chars_vec = key;
-
-        let c0 = chars_vec.c0;
-        let c1 = chars_vec.c1;
-
-        insert_or_add(&mut new_counts, Key { c0, c1: *c_new }, *value);
-        insert_or_add(&mut new_counts, Key { c0: *c_new, c1 }, *value);
-    }
-
-    new_counts
-}
-
-fn polymer_to_hashmap(input: &[char]) -> HashMap<char, u64> {
-    let mut counts: HashMap<char, u64> = HashMap::new();
-    for i in input {
-        insert_or_add(&mut counts, *i, 1);
+) -> Result<Counter<Key>, DayError> {
+    let mut new_counts = Counter::new();
+
+    for (key, count) in input.iter() {
+        let c_new = insertion(pair_insertion_rules, key)?;
+
+        new_counts.add_n(
+            Key {
+                c0: key.c0,
+                c1: c_new,
+            },
+            count,
+        );
+        new_counts.add_n(
+            Key {
+                c0: c_new,
+                c1: key.c1,
+            },
+            count,
+        );
     }
 
-    counts
+    Ok(new_counts)
 }
 
-fn get_min_and_max_hashmap(hashmap: &HashMap<char, u64>) -> (u64, u64) {
+/// The counts of the least and the most common element.
+fn least_and_most_common(elements: &Counter<char>) -> (u64, u64) {
     (
-        hashmap.values().copied().min().unwrap(),
-        hashmap.values().copied().max().unwrap(),
+        elements.min().map(|(_, count)| count).unwrap_or_default(),
+        elements.max().map(|(_, count)| count).unwrap_or_default(),
     )
 }
 
-fn insert_or_add<T: Eq + Hash, U: AddAssign + Default>(
-    hashmap: &mut HashMap<T, U>,
-    key: T,
-    value: U,
-) {
-    let entry = hashmap.entry(key).or_default();
-
-    *entry += value;
-}
-
-fn polymer_to_hashmap_part2(
-    polymer: &[char],
-    polymer_groups_set: &HashMap<Key, u64>,
-) -> HashMap<char, u64> {
-    let first_char = polymer[0];
-    let last_char = polymer[polymer.len() - 1];
+/// Every element is part of 2 pairs, except for the first and last element of the polymer.
+fn count_elements_in_pairs(polymer: &[char], pairs: &Counter<Key>) -> Counter<char> {
+    let mut doubled = Counter::new();
 
-    let mut counts = HashMap::new();
-
-    for (key, value) in polymer_groups_set {
-        insert_or_add(&mut counts, key.c0, *value);
-        insert_or_add(&mut counts, key.c1, *value);
+    for (key, count) in pairs.iter() {
+        doubled.add_n(key.c0, count);
+        doubled.add_n(key.c1, count);
     }
 
-    insert_or_add(&mut counts, first_char, 1);
-    insert_or_add(&mut counts, last_char, 1);
+    doubled.extend(polymer.first().copied());
+    doubled.extend(polymer.last().copied());
 
-    for amount in counts.values_mut() {
-        *amount /= 2;
-    }
-
-    counts
+    doubled
+        .iter()
+        .map(|(&element, count)| (element, count / 2))
+        .collect()
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_14/input.txt").lines().collect();
+    type Parsed = (Vec<char>, HashMap<Key, char>);
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let (mut polymer, pair_insertion_rules) = parse_lines(&lines);
+        Ok(parse_lines(&lines)?)
+    }
+
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let &(ref polymer, ref pair_insertion_rules) = parsed;
+
+        let mut polymer = polymer.clone();
 
         for i in 1..=10 {
-            polymer = parse_polymer(&polymer, &pair_insertion_rules);
+            polymer = parse_polymer(&polymer, pair_insertion_rules)?;
 
-            println!("After step {}: {}", i, polymer.iter().collect::<String>());
+            ::tracing::event!(
+                ::tracing::Level::TRACE,
+                "After step {}: {}",
+                i,
+                polymer.iter().collect::<String>()
+            );
         }
 
-        let chars_with_count = polymer_to_hashmap(&polymer);
+        let elements: Counter<char> = polymer.iter().copied().collect();
 
-        let (min, max) = get_min_and_max_hashmap(&chars_with_count);
+        let (min, max) = least_and_most_common(&elements);
 
-        PartSolution::U64(max - min)
+        Ok(PartSolution::U64(max - min))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_14/input.txt").lines().collect();
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let &(ref polymer, ref pair_insertion_rules) = parsed;
 
-        let (polymer, pair_insertion_rules) = parse_lines(&lines);
-
-        let mut polymer_groups_set = parse_lines_part_2(&polymer);
+        let mut polymer_groups_set = count_pairs(polymer);
 
         for i in 1..=40 {
-            println!("Step {}", i);
-            polymer_groups_set = parse_polymer_part_2(&polymer_groups_set, &pair_insertion_rules);
+            ::tracing::event!(::tracing::Level::TRACE, "Step {}", i);
+            polymer_groups_set = parse_polymer_part_2(&polymer_groups_set, pair_insertion_rules)?;
         }
 
-        let chars_with_count = polymer_to_hashmap_part2(&polymer, &polymer_groups_set);
+        let elements = count_elements_in_pairs(polymer, &polymer_groups_set);
 
-        let (min, max) = get_min_and_max_hashmap(&chars_with_count);
+        let (min, max) = least_and_most_common(&elements);
 
-        PartSolution::U64(max - min)
+        Ok(PartSolution::U64(max - min))
     }
 }
 
@@ -187,37 +204,50 @@ mod test {
 
     mod part_1 {
         use pretty_assertions::assert_eq;
+        use shared::counter::Counter;
 
         use super::get_example;
-        use crate::day_14::{
-            Solution, get_min_and_max_hashmap, parse_lines, parse_polymer, polymer_to_hashmap,
-        };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_14::{INPUT, Solution, least_and_most_common, parse_lines, parse_polymer};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U64(2851));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U64(2851)
+            );
         }
 
         #[test]
         fn example() {
             let lines = get_example();
 
-            let (mut polymer, pair_insertion_rules) = parse_lines(&lines);
+            let (mut polymer, pair_insertion_rules) = parse_lines(&lines).unwrap();
 
             for i in 1..=10 {
-                polymer = parse_polymer(&polymer, &pair_insertion_rules);
+                polymer = parse_polymer(&polymer, &pair_insertion_rules).unwrap();
 
                 println!("After step {}: {}", i, polymer.iter().collect::<String>());
             }
 
-            let chars_with_count = polymer_to_hashmap(&polymer);
+            let elements: Counter<char> = polymer.iter().copied().collect();
 
-            let (min, max) = get_min_and_max_hashmap(&chars_with_count);
+            let (min, max) = least_and_most_common(&elements);
 
             assert_eq!(min, 161);
             assert_eq!(max, 1749);
         }
+
+        #[test]
+        fn missing_rule() {
+            // `AB` becomes `ACB`, and nothing says what goes in `AC`
+            assert_eq!(
+                "No solution: there's no rule for the pair `AC`",
+                solve(&Solution {}, 1, "AB\n\nAB -> C")
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
     }
 
     mod part_2 {
@@ -225,15 +255,15 @@ mod test {
 
         use crate::day_14::test::get_example;
         use crate::day_14::{
-            Solution, get_min_and_max_hashmap, parse_lines, parse_lines_part_2,
-            parse_polymer_part_2, polymer_to_hashmap_part2,
+            INPUT, Solution, count_elements_in_pairs, count_pairs, least_and_most_common,
+            parse_lines, parse_polymer_part_2,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
             assert_eq!(
-                (Solution {}).part_2(),
+                solve(&Solution {}, 2, INPUT).unwrap(),
                 PartSolution::U64(10_002_813_279_337)
             );
         }
@@ -242,21 +272,31 @@ mod test {
         fn example() {
             let lines = get_example();
 
-            let (polymer, pair_insertion_rules) = parse_lines(&lines);
+            let (polymer, pair_insertion_rules) = parse_lines(&lines).unwrap();
 
-            let mut polymer_groups_set = parse_lines_part_2(&polymer);
+            let mut polymer_groups_set = count_pairs(&polymer);
 
             for _ in 1..=10 {
                 polymer_groups_set =
-                    parse_polymer_part_2(&polymer_groups_set, &pair_insertion_rules);
+                    parse_polymer_part_2(&polymer_groups_set, &pair_insertion_rules).unwrap();
             }
 
-            let chars_with_count = polymer_to_hashmap_part2(&polymer, &polymer_groups_set);
+            let elements = count_elements_in_pairs(&polymer, &polymer_groups_set);
 
-            let (min, max) = get_min_and_max_hashmap(&chars_with_count);
+            let (min, max) = least_and_most_common(&elements);
 
             assert_eq!(min, 161);
             assert_eq!(max, 1749);
         }
+
+        #[test]
+        fn missing_rule() {
+            assert_eq!(
+                "No solution: there's no rule for the pair `AC`",
+                solve(&Solution {}, 2, "AB\n\nAB -> C")
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
     }
 }
@@ -1,293 +1,189 @@
-use std::cell::Cell;
-use std::collections::BinaryHeap;
+use shared::grid::{Coordinates, Grid};
+use shared::parse::{ParseError, parse_digit};
+use shared::search::{Graph, Path, a_star};
 
-use hashbrown::HashMap;
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-use crate::shared::{Day, PartSolution};
+pub const INPUT: &str = include_str!("day_15/input.txt");
 
-type Chiton = (u32, Cell<bool>);
-type Coordinates = (usize, usize);
-
-fn parse_lines(lines: &[&str]) -> Vec<Vec<Chiton>> {
-    let mut field = Vec::new();
-
-    for line in lines {
-        field.push(
-            line.chars()
-                .map(|x| x.to_digit(10).unwrap())
-                .map(|x| (x, Cell::new(false)))
-                .collect(),
-        );
-    }
-
-    field
-}
-
-fn get_neighbors<T>(
-    chiton_field: &[Vec<T>],
-    (row_index, column_index): Coordinates,
-) -> Vec<Coordinates> {
-    let mut neighbors = Vec::new();
-
-    let rows = chiton_field.len();
-    let columns = chiton_field
-        .get(row_index)
-        .map(Vec::len)
-        .unwrap_or_default();
-
-    let left = column_index.checked_sub(1);
-    let up = row_index.checked_sub(1);
-
-    let right = (column_index + 1 < columns).then_some(column_index + 1);
-    let down = (row_index + 1 < rows).then_some(row_index + 1);
-
-    // up
-    if let Some(u) = up {
-        neighbors.push((u, column_index));
-    }
-
-    // right
-    if let Some(r) = right {
-        neighbors.push((row_index, r));
-    }
-
-    // down
-    if let Some(d) = down {
-        neighbors.push((d, column_index));
-    }
-
-    // left
-    if let Some(l) = left {
-        neighbors.push((row_index, l));
-    }
-
-    neighbors
-}
-
-fn reconstruct_path(
-    came_from: &HashMap<Coordinates, Coordinates>,
-    mut current: Coordinates,
-) -> Vec<Coordinates> {
-    let mut total_path = vec![current];
-
-    while let Some(c) = came_from.get(&current) {
-        total_path.push(*c);
-
-        current = *c;
-    }
-
-    total_path.reverse();
-    total_path
+fn parse_risk_levels(input: &str) -> Result<Grid<u32>, ParseError> {
+    Ok(Grid::parse(input, |(row, column), c| {
+        parse_digit(row, column, c)
+    })?)
 }
 
-fn distance(field: &[Vec<Chiton>], current: Coordinates, neighbor: Coordinates) -> u32 {
-    // initially I only had the neighbor's value here, but adding the current value increases
-    // variability and speeds up the algorithm
-    field[current.0][current.1].0 + field[neighbor.0][neighbor.1].0
+/// Entering a position costs its risk level.
+struct Cave<'risk_levels> {
+    risk_levels: &'risk_levels Grid<u32>,
+    goal: Coordinates,
 }
 
-fn heuristic(field: &[Vec<Chiton>], current: Coordinates) -> u32 {
-    // initially I only had the neighbor's value here, but adding the current value increases
-    // variability and speeds up the algorithm
-    // field[current.0][current.1] + field[neighbor.0][neighbor.1]
+impl Graph for Cave<'_> {
+    type Node = Coordinates;
+    type Cost = u32;
 
-    ((field.len() - current.0) + (field[0].len() - current.1)) as u32
-}
-
-#[derive(PartialEq, Eq)]
-struct Node(Coordinates, u32);
-
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.1.cmp(&self.1)
+    fn neighbors(&self, node: &Coordinates) -> impl Iterator<Item = (Coordinates, u32)> {
+        self.risk_levels
+            .neighbors_4(*node)
+            .map(|neighbor| (neighbor, self.risk_levels[neighbor]))
     }
-}
 
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+    fn heuristic(&self, node: &Coordinates) -> u32 {
+        // every step costs at least 1
+        u32::try_from(self.goal.0.abs_diff(node.0) + self.goal.1.abs_diff(node.1))
+            .unwrap_or(u32::MAX)
     }
 }
 
-fn a_star(field: &mut [Vec<Chiton>], start: Coordinates, goal: Coordinates) -> Vec<Coordinates> {
-    let mut open_set = BinaryHeap::from([Node(start, heuristic(field, start))]);
-
-    let mut came_from = HashMap::<Coordinates, Coordinates>::new();
+/// The path from the top left to the bottom right corner of the cave with the lowest total risk.
+fn lowest_risk_path(risk_levels: &Grid<u32>) -> Result<Path<Coordinates, u32>, DayError> {
+    let goal = risk_levels
+        .height()
+        .checked_sub(1)
+        .zip(risk_levels.width().checked_sub(1))
+        .ok_or_else(|| DayError::NoSolution("the cave is empty".to_owned()))?;
 
-    let mut g_score: HashMap<_, _> = HashMap::from([(start, 0)]);
+    let cave = Cave { risk_levels, goal };
 
-    while let Some(node) = open_set.pop() {
-        let coordinates = node.0;
+    let path = a_star(&cave, (0, 0), |&node| node == goal)
+        .ok_or_else(|| DayError::NoSolution("no path through the cave".to_owned()))?;
 
-        field[coordinates.0][coordinates.1].1.set(true);
+    ::tracing::event!(
+        ::tracing::Level::DEBUG,
+        "Expanded {} of {} positions",
+        path.expanded,
+        risk_levels.len()
+    );
 
-        if coordinates == goal {
-            return reconstruct_path(&came_from, coordinates);
-        }
-
-        let neighbors = get_neighbors(field, coordinates);
-
-        for neighbor in neighbors {
-            let tentative_g_score =
-                g_score.get(&coordinates).unwrap() + distance(field, coordinates, neighbor);
-
-            if tentative_g_score < *g_score.get(&neighbor).unwrap_or(&u32::MAX) {
-                came_from.insert(neighbor, coordinates);
-
-                g_score.insert(neighbor, tentative_g_score);
-
-                let g_score_with_heuristic = tentative_g_score + heuristic(field, neighbor);
-
-                open_set.push(Node(neighbor, g_score_with_heuristic));
-            }
-        }
-    }
+    dump_field(risk_levels, &path.nodes);
 
-    panic!("No solution found")
+    Ok(path)
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_15/input.txt").lines().collect();
-
-        let mut parsed = parse_lines(&lines);
-
-        let max_row = parsed.len() - 1;
-        let max_col = parsed[0].len() - 1;
-
-        let cheapest = a_star(&mut parsed, (0, 0), (max_row, max_col));
-
-        dump_field(&parsed);
+    type Parsed = Grid<u32>;
 
-        PartSolution::U32(
-            cheapest
-                .iter()
-                .skip(1)
-                .map(|&(r, c)| (parsed[r][c]).0)
-                .sum::<u32>(),
-        )
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        Ok(parse_risk_levels(input)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_15/input.txt").lines().collect();
-
-        let mut parsed = parse_lines(&lines);
-
-        duplicate_x_times(&mut parsed, 4);
-
-        let max_row = parsed.len() - 1;
-        let max_col = parsed[0].len() - 1;
-
-        let cheapest = a_star(&mut parsed, (0, 0), (max_row, max_col));
-
-        PartSolution::U32(
-            cheapest
-                .iter()
-                .skip(1)
-                .map(|&(r, c)| (parsed[r][c]).0)
-                .sum::<u32>(),
-        )
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U32(lowest_risk_path(parsed)?.cost))
     }
-}
 
-fn roll_over_after_9(val: &mut u32) {
-    *val += 1;
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let risk_levels = duplicate_x_times(parsed, 4);
 
-    if *val > 9 {
-        *val = 1;
+        Ok(PartSolution::U32(lowest_risk_path(&risk_levels)?.cost))
     }
 }
 
-fn dump_field(field: &[Vec<Chiton>]) {
-    for r in field {
-        for c in r {
-            let color: u32 = if c.1.get() { 31 } else { 0 };
-            print!("\x1b[{}m{}\x1b[0m", color, c.0);
-        }
+fn roll_over_after_9(risk_level: u32, increase: u32) -> u32 {
+    (risk_level + increase - 1) % 9 + 1
+}
 
-        println!();
+fn dump_field(risk_levels: &Grid<u32>, path: &[Coordinates]) {
+    if !::tracing::enabled!(::tracing::Level::TRACE) {
+        return;
     }
-}
 
-fn duplicate_x_times(original: &mut Vec<Vec<Chiton>>, times: u32) {
-    for r in &mut *original {
-        let mut to_roll_over_and_re_insert = r.clone();
+    let mut dump = String::new();
 
-        for _ in 0..times {
-            for f in &mut to_roll_over_and_re_insert {
-                roll_over_after_9(&mut f.0);
-            }
+    for (row_index, row) in risk_levels.rows().enumerate() {
+        dump.push('\n');
 
-            let mut clone = to_roll_over_and_re_insert.clone();
+        dump.extend(row.iter().enumerate().map(|(column_index, risk_level)| {
+            let color: u32 = if path.contains(&(row_index, column_index)) {
+                31
+            } else {
+                0
+            };
 
-            r.append(&mut clone);
-        }
+            format!("\x1b[{}m{}\x1b[0m", color, risk_level)
+        }));
     }
 
-    let mut to_roll_over_and_re_insert = original
-        .iter()
-        .map(|v| Vec::from_iter(v.clone()))
-        .collect::<Vec<_>>();
+    ::tracing::event!(::tracing::Level::TRACE, "{}", dump);
+}
 
-    for _ in 0..times {
-        // bump all numbers
-        for inner in &mut to_roll_over_and_re_insert {
-            for f in inner {
-                roll_over_after_9(&mut f.0);
+/// Tiles `original` `times + 1` times in both directions, every tile to the right or below adding
+/// one to the risk levels.
+fn duplicate_x_times(original: &Grid<u32>, times: u32) -> Grid<u32> {
+    let height = original.height();
+    let width = original.width();
+
+    let tile_increases: Vec<(usize, u32)> = (0..=times).enumerate().collect();
+
+    let mut duplicated = Grid::new(
+        width * tile_increases.len(),
+        height * tile_increases.len(),
+        0,
+    );
+
+    for &(tile_row, row_increase) in &tile_increases {
+        for &(tile_column, column_increase) in &tile_increases {
+            for ((row, column), &risk_level) in original.iter() {
+                duplicated[(tile_row * height + row, tile_column * width + column)] =
+                    roll_over_after_9(risk_level, row_increase + column_increase);
             }
         }
-
-        let mut clone = to_roll_over_and_re_insert.clone();
-
-        original.append(&mut clone);
     }
+
+    duplicated
 }
 
 #[cfg(test)]
 mod test {
-    fn get_example() -> Vec<&'static str> {
-        include_str!("day_15/example.txt").lines().collect()
+    fn get_example() -> &'static str {
+        include_str!("day_15/example.txt")
     }
 
-    fn get_example_5x() -> Vec<&'static str> {
-        include_str!("day_15/example 5x.txt").lines().collect()
+    fn get_example_5x() -> &'static str {
+        include_str!("day_15/example 5x.txt")
     }
 
     mod part_1 {
         use pretty_assertions::assert_eq;
 
         use super::get_example;
-        use crate::day_15::{Solution, a_star, dump_field, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_15::{INPUT, Solution, lowest_risk_path, parse_risk_levels};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(604));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(604)
+            );
         }
 
         #[test]
         fn example() {
-            let lines = get_example();
-
-            let mut parsed = parse_lines(&lines);
-
-            let max_row = parsed.len() - 1;
-            let max_col = parsed[0].len() - 1;
+            let risk_levels = parse_risk_levels(get_example()).unwrap();
 
-            let cheapest = a_star(&mut parsed, (0, 0), (max_row, max_col));
-
-            dump_field(&parsed);
+            let path = lowest_risk_path(&risk_levels).unwrap();
 
+            assert_eq!(40, path.cost);
             assert_eq!(
-                40,
-                cheapest
+                path.cost,
+                path.nodes
                     .iter()
                     .skip(1)
-                    .map(|&(r, c)| (parsed[r][c]).0)
+                    .map(|&coordinates| risk_levels[coordinates])
                     .sum::<u32>()
             );
+            assert_eq!(Some(&(0, 0)), path.nodes.first());
+            assert_eq!(&(9, 9), path.goal());
         }
     }
 
@@ -295,46 +191,33 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use super::{get_example, get_example_5x};
-        use crate::day_15::{Solution, a_star, duplicate_x_times, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_15::{
+            INPUT, Solution, duplicate_x_times, lowest_risk_path, parse_risk_levels,
+        };
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(2907));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U32(2907)
+            );
         }
 
         #[test]
         fn example() {
-            let lines = get_example_5x();
-
-            let mut parsed = parse_lines(&lines);
-
-            let max_row = parsed.len() - 1;
-            let max_col = parsed[0].len() - 1;
+            let risk_levels = parse_risk_levels(get_example_5x()).unwrap();
 
-            let cheapest = a_star(&mut parsed, (0, 0), (max_row, max_col));
-
-            assert_eq!(
-                315,
-                cheapest
-                    .iter()
-                    .skip(1)
-                    .map(|&(r, c)| (parsed[r][c]).0)
-                    .sum::<u32>()
-            );
+            assert_eq!(315, lowest_risk_path(&risk_levels).unwrap().cost);
         }
+
         #[test]
         fn duplication() {
-            let lines = get_example();
-            let lines_5x = get_example_5x();
-
-            let mut parsed = parse_lines(&lines);
-
-            let parsed_5x = parse_lines(&lines_5x);
+            let risk_levels = parse_risk_levels(get_example()).unwrap();
 
-            duplicate_x_times(&mut parsed, 4);
+            let risk_levels_5x = parse_risk_levels(get_example_5x()).unwrap();
 
-            assert_eq!(parsed, parsed_5x);
+            assert_eq!(duplicate_x_times(&risk_levels, 4), risk_levels_5x);
         }
     }
 }
@@ -1,17 +1,50 @@
-use crate::shared::{Day, PartSolution};
+use shared::bits::BitReader;
+use shared::parse::{ParseError, get_line};
 
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_16/input.txt");
+
+/// A BITS packet, with the packets inside it when it's an operator.
 #[derive(Debug, PartialEq, Eq)]
-struct Packet {
+pub struct Packet {
     version: u16,
     r#type: u16,
     inside: PacketInside,
 }
 
-struct RemainingStream<T> {
-    parsed_contents: T,
-    buffer: u16,
-    relevant_bits: u8,
-    remaining_nibbles: Vec<u8>,
+impl Packet {
+    #[must_use]
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// The packets inside an operator, none for a literal.
+    #[must_use]
+    pub fn sub_packets(&self) -> &[Packet] {
+        match self.inside {
+            PacketInside::Literal(_) => &[],
+            PacketInside::Sum(ref v)
+            | PacketInside::Product(ref v)
+            | PacketInside::Minimum(ref v)
+            | PacketInside::Maximum(ref v)
+            | PacketInside::GreaterThan(ref v)
+            | PacketInside::LessThanThan(ref v)
+            | PacketInside::Equal(ref v) => v,
+        }
+    }
+
+    /// The versions of this packet and all packets inside it, added up.
+    #[must_use]
+    pub fn version_sum(&self) -> u32 {
+        calculate_version_sum(self)
+    }
+
+    /// What the expression this packet is evaluates to.
+    #[must_use]
+    pub fn value(&self) -> u64 {
+        calculate_deep_packet_value(self)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -26,309 +59,207 @@ enum PacketInside {
     Equal(Vec<Packet>),
 }
 
-const VERSION_BITS: u8 = 3;
-const PACKET_TYPE_BITS: u8 = 3;
-const LENGTH_TYPE_ID_BIT: u8 = 1;
-const OPERATOR_PACKET_TYPE_0_SUBPACKET_LENGTH_BITS: u8 = 15;
-const OPERATOR_PACKET_TYPE_1_SUBPACKET_COUNT_BITS: u8 = 11;
-
-fn fetch_until_bits_in_buffer(
-    buffer: &mut u16,
-    nibbles: &mut Vec<u8>,
-    bits_loaded_in_buffer: &mut u8,
-    bits_needed_in_buffer: u8,
-) -> u16 {
-    while *bits_loaded_in_buffer < bits_needed_in_buffer {
-        match nibbles.pop() {
-            Some(nibble) => {
-                *buffer <<= 4;
-                *buffer |= u16::from(nibble);
-            },
-            None => {
-                println!("You're asking more than we have left!");
-            },
-        }
+const VERSION_BITS: usize = 3;
+const PACKET_TYPE_BITS: usize = 3;
+const LITERAL_PACKET_TYPE: u16 = 4;
+const LITERAL_GROUP_BITS: usize = 4;
+const OPERATOR_PACKET_TYPE_0_SUBPACKET_LENGTH_BITS: usize = 15;
+const OPERATOR_PACKET_TYPE_1_SUBPACKET_COUNT_BITS: usize = 11;
 
-        *bits_loaded_in_buffer += 4;
-    }
-
-    // the value is the buffer shifted (bits_loaded_in_buffer - bits_needed_in_buffer)
-    // AND the bits needed turned into selector
-    let value = (*buffer >> (*bits_loaded_in_buffer - bits_needed_in_buffer))
-        & (u16::pow(2, u32::from(bits_needed_in_buffer)) - 1);
-
-    // clean out the remainder of the buffer, not needed
-    // *buffer &= u16::pow(2, u32::from(*bits_loaded_in_buffer - bits_needed_in_buffer)) - 1;
-
-    // set the new amount of bits loaded in buffer
-    *bits_loaded_in_buffer -= bits_needed_in_buffer;
-
-    value
-}
-
-fn parse_literal_packet(
-    mut buffer: u16,
-    mut additional_offset: u8,
-    mut nibbles: Vec<u8>,
-) -> RemainingStream<u64> {
-    // this one bit me, I used a u32
-    // and the <<= operation doesn't complain
-    // when overwriting data
+fn parse_literal_packet(reader: &mut BitReader<'_>) -> Result<u64, ParseError> {
     let mut number: u64 = 0;
 
-    let mut have_more = true;
-
-    while have_more {
-        have_more =
-            fetch_until_bits_in_buffer(&mut buffer, &mut nibbles, &mut additional_offset, 1) == 1;
+    // groups of 4 bits, each behind a bit telling whether another group follows
+    loop {
+        let have_more = reader.read_bit()?;
 
-        let number_nibble =
-            fetch_until_bits_in_buffer(&mut buffer, &mut nibbles, &mut additional_offset, 4);
+        if number.leading_zeros() < 4 {
+            return Err(ParseError::new(
+                0,
+                0,
+                "literal doesn't fit in 64 bits".to_owned(),
+            ));
+        }
 
-        number <<= 4;
-        number |= u64::from(number_nibble);
-    }
+        number = (number << LITERAL_GROUP_BITS) | reader.read(LITERAL_GROUP_BITS)?;
 
-    RemainingStream {
-        parsed_contents: number,
-        relevant_bits: additional_offset,
-        buffer,
-        remaining_nibbles: nibbles,
+        if !have_more {
+            return Ok(number);
+        }
     }
 }
 
-fn parse_operator_packet_0(
-    mut buffer: u16,
-    mut bits_in_buffer: u8,
-    mut nibbles: Vec<u8>,
-) -> RemainingStream<Vec<Packet>> {
-    let mut packets = Vec::new();
+fn parse_operator_packet_0(reader: &mut BitReader<'_>) -> Result<Vec<Packet>, ParseError> {
+    let length = reader.read_as(OPERATOR_PACKET_TYPE_0_SUBPACKET_LENGTH_BITS)?;
 
-    // fetch 15 more bits
-    let bits_to_take = fetch_until_bits_in_buffer(
-        &mut buffer,
-        &mut nibbles,
-        &mut bits_in_buffer,
-        OPERATOR_PACKET_TYPE_0_SUBPACKET_LENGTH_BITS,
-    );
+    let mut sub_reader = reader.sub_reader(length)?;
 
-    let mut bits_taken = u16::from(bits_in_buffer);
-
-    let mut new_nibbles = Vec::new();
+    let mut packets = Vec::new();
 
-    while bits_taken < bits_to_take {
-        new_nibbles.push(nibbles.pop().unwrap());
-        bits_taken += 4;
+    while !sub_reader.is_empty() {
+        packets.push(parse_packet(&mut sub_reader)?);
     }
 
-    new_nibbles.reverse();
+    Ok(packets)
+}
 
-    while !new_nibbles.is_empty() {
-        let result = parse_packet(buffer, bits_in_buffer, new_nibbles);
+fn parse_operator_packet_1(reader: &mut BitReader<'_>) -> Result<Vec<Packet>, ParseError> {
+    let count = reader.read_as(OPERATOR_PACKET_TYPE_1_SUBPACKET_COUNT_BITS)?;
 
-        packets.push(result.parsed_contents);
-        buffer = result.buffer;
-        bits_in_buffer = result.relevant_bits;
-        new_nibbles = result.remaining_nibbles;
-    }
+    let mut packets = Vec::with_capacity(count);
 
-    RemainingStream {
-        parsed_contents: packets,
-        relevant_bits: bits_in_buffer,
-        buffer,
-        remaining_nibbles: nibbles,
+    for _ in 0..count {
+        packets.push(parse_packet(reader)?);
     }
+
+    Ok(packets)
 }
 
-fn parse_operator_packet_1(
-    mut buffer: u16,
-    mut relevant_bits: u8,
-    mut nibbles: Vec<u8>,
-) -> RemainingStream<Vec<Packet>> {
-    let mut packets = Vec::new();
+/// Comparisons compare 2 packets, the other operators need at least one.
+fn check_sub_packet_count(
+    packet_type: u16,
+    sub_packets: &[Packet],
+    column_index: usize,
+) -> Result<(), ParseError> {
+    let count = sub_packets.len();
+
+    let expected = match packet_type {
+        5..=7 if count != 2 => "exactly 2 packets",
+        0..=3 if count == 0 => "at least 1 packet",
+        _ => return Ok(()),
+    };
 
-    let sub_packets = fetch_until_bits_in_buffer(
-        &mut buffer,
-        &mut nibbles,
-        &mut relevant_bits,
-        OPERATOR_PACKET_TYPE_1_SUBPACKET_COUNT_BITS,
-    );
+    Err(ParseError::new(
+        0,
+        column_index,
+        format!(
+            "packet type {} needs {} inside, got {}",
+            packet_type, expected, count
+        ),
+    ))
+}
 
-    for _ in 0..sub_packets {
-        let x = parse_packet(buffer, relevant_bits, nibbles);
+fn parse_packet(reader: &mut BitReader<'_>) -> Result<Packet, ParseError> {
+    // in hex digits, for errors about the packet as a whole
+    let column_index = reader.stream_position() / 4;
 
-        packets.push(x.parsed_contents);
-        buffer = x.buffer;
-        relevant_bits = x.relevant_bits;
-        nibbles = x.remaining_nibbles;
-    }
+    let version = reader.read_as(VERSION_BITS)?;
+    let packet_type = reader.read_as(PACKET_TYPE_BITS)?;
 
-    RemainingStream {
-        parsed_contents: packets,
-        relevant_bits,
-        buffer,
-        remaining_nibbles: nibbles,
+    if packet_type == LITERAL_PACKET_TYPE {
+        return Ok(Packet {
+            version,
+            r#type: packet_type,
+            inside: PacketInside::Literal(parse_literal_packet(reader)?),
+        });
     }
-}
 
-fn parse_packet(
-    mut buffer: u16,
-    mut relevant_bits: u8,
-    mut nibbles: Vec<u8>,
-) -> RemainingStream<Packet> {
-    // let's get the version, first 3 bits
-    let version =
-        fetch_until_bits_in_buffer(&mut buffer, &mut nibbles, &mut relevant_bits, VERSION_BITS);
-
-    let packet_type = fetch_until_bits_in_buffer(
-        &mut buffer,
-        &mut nibbles,
-        &mut relevant_bits,
-        PACKET_TYPE_BITS,
-    );
-
-    let inside = if packet_type == 4 {
-        // literal packet
-        let result = parse_literal_packet(buffer, relevant_bits, nibbles);
-
-        relevant_bits = result.relevant_bits;
-        buffer = result.buffer;
-        nibbles = result.remaining_nibbles;
-
-        PacketInside::Literal(result.parsed_contents)
+    let sub_packets = if reader.read_bit()? {
+        parse_operator_packet_1(reader)?
     } else {
-        let length_type_id = fetch_until_bits_in_buffer(
-            &mut buffer,
-            &mut nibbles,
-            &mut relevant_bits,
-            LENGTH_TYPE_ID_BIT,
-        );
-
-        let result = if length_type_id == 0 {
-            parse_operator_packet_0(buffer, relevant_bits, nibbles)
-        } else {
-            parse_operator_packet_1(buffer, relevant_bits, nibbles)
-        };
-
-        relevant_bits = result.relevant_bits;
-        buffer = result.buffer;
-        nibbles = result.remaining_nibbles;
-
-        match packet_type {
-            0 => PacketInside::Sum(result.parsed_contents),
-            1 => PacketInside::Product(result.parsed_contents),
-            2 => PacketInside::Minimum(result.parsed_contents),
-            3 => PacketInside::Maximum(result.parsed_contents),
-            5 => PacketInside::GreaterThan(result.parsed_contents),
-            6 => PacketInside::LessThanThan(result.parsed_contents),
-            7 => PacketInside::Equal(result.parsed_contents),
-            _ => {
-                panic!(
-                    "Packet type ({}) / length type id ({}) not supported",
-                    packet_type, length_type_id,
-                );
-            },
-        }
+        parse_operator_packet_0(reader)?
     };
 
-    RemainingStream {
-        parsed_contents: Packet {
-            version,
-            r#type: packet_type,
-            inside,
+    check_sub_packet_count(packet_type, &sub_packets, column_index)?;
+
+    let inside = match packet_type {
+        0 => PacketInside::Sum(sub_packets),
+        1 => PacketInside::Product(sub_packets),
+        2 => PacketInside::Minimum(sub_packets),
+        3 => PacketInside::Maximum(sub_packets),
+        5 => PacketInside::GreaterThan(sub_packets),
+        6 => PacketInside::LessThanThan(sub_packets),
+        7 => PacketInside::Equal(sub_packets),
+        _ => {
+            return Err(ParseError::new(
+                0,
+                0,
+                format!("packet type {} is not supported", packet_type),
+            ));
         },
-        relevant_bits,
-        buffer,
-        remaining_nibbles: nibbles,
-    }
-}
-
-fn parse_packet_string(packet_string: &str) -> Packet {
-    let mut hex = packet_string
-        .chars()
-        .map(|c| c.to_digit(16).unwrap() as u8)
-        .collect::<Vec<_>>();
+    };
 
-    hex.reverse();
+    Ok(Packet {
+        version,
+        r#type: packet_type,
+        inside,
+    })
+}
 
-    let result = parse_packet(0, 0, hex);
+fn parse_packet_string(packet_string: &str) -> Result<Packet, ParseError> {
+    let mut reader = BitReader::from_hex(packet_string)?;
 
-    result.parsed_contents
+    // anything after the outermost packet is padding
+    parse_packet(&mut reader)
 }
 
 fn calculate_version_sum(packet: &Packet) -> u32 {
     u32::from(packet.version)
-        + match packet.inside {
-            PacketInside::Literal(_) => 0,
-            PacketInside::Sum(ref v)
-            | PacketInside::Product(ref v)
-            | PacketInside::Minimum(ref v)
-            | PacketInside::Maximum(ref v)
-            | PacketInside::GreaterThan(ref v)
-            | PacketInside::LessThanThan(ref v)
-            | PacketInside::Equal(ref v) => v.iter().map(calculate_version_sum).sum::<u32>(),
-        }
+        + packet
+            .sub_packets()
+            .iter()
+            .map(calculate_version_sum)
+            .sum::<u32>()
+}
+
+/// `compare` applied to the 2 packets inside, as 1 or 0.
+fn compare_packet_values(packets: &[Packet], compare: fn(u64, u64) -> bool) -> u64 {
+    match *packets {
+        [ref l, ref r] => u64::from(compare(
+            calculate_deep_packet_value(l),
+            calculate_deep_packet_value(r),
+        )),
+        // `parse_packet` makes sure comparisons have 2 packets
+        _ => 0,
+    }
 }
 
-#[expect(clippy::disallowed_macros, reason = "No pretty needed in actual code")]
 fn calculate_deep_packet_value(packet: &Packet) -> u64 {
     match packet.inside {
         PacketInside::Literal(l) => l,
         PacketInside::Sum(ref v) => v.iter().map(calculate_deep_packet_value).sum(),
         PacketInside::Product(ref v) => v.iter().map(calculate_deep_packet_value).product(),
-        PacketInside::Minimum(ref v) => v.iter().map(calculate_deep_packet_value).min().unwrap(),
-        PacketInside::Maximum(ref v) => v.iter().map(calculate_deep_packet_value).max().unwrap(),
-        PacketInside::GreaterThan(ref v) => {
-            assert_eq!(2, v.len());
-
-            let l = calculate_deep_packet_value(&v[0]);
-            let r = calculate_deep_packet_value(&v[1]);
-
-            u64::from(l > r)
-        },
-        PacketInside::LessThanThan(ref v) => {
-            assert_eq!(2, v.len());
-
-            let l = calculate_deep_packet_value(&v[0]);
-            let r = calculate_deep_packet_value(&v[1]);
-
-            u64::from(l < r)
-        },
-        PacketInside::Equal(ref v) => {
-            assert_eq!(2, v.len());
-            let l = calculate_deep_packet_value(&v[0]);
-            let r = calculate_deep_packet_value(&v[1]);
-
-            u64::from(l == r)
-        },
+        PacketInside::Minimum(ref v) => v
+            .iter()
+            .map(calculate_deep_packet_value)
+            .min()
+            .unwrap_or_default(),
+        PacketInside::Maximum(ref v) => v
+            .iter()
+            .map(calculate_deep_packet_value)
+            .max()
+            .unwrap_or_default(),
+        PacketInside::GreaterThan(ref v) => compare_packet_values(v, |l, r| l > r),
+        PacketInside::LessThanThan(ref v) => compare_packet_values(v, |l, r| l < r),
+        PacketInside::Equal(ref v) => compare_packet_values(v, |l, r| l == r),
     }
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_16/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
+    type Parsed = Packet;
 
-        let translated = parse_packet_string(&lines[0]);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::U32(calculate_version_sum(&translated))
+        Ok(parse_packet_string(get_line(&lines, 0)?)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_16/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
-
-        let translated = parse_packet_string(&lines[0]);
-
-        let total = calculate_deep_packet_value(&translated);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U32(calculate_version_sum(parsed)))
+    }
 
-        PartSolution::U64(total)
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U64(calculate_deep_packet_value(parsed)))
     }
 }
 
@@ -339,20 +270,23 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_16::{
-            Packet, PacketInside, Solution, calculate_version_sum, parse_packet_string,
+            INPUT, Packet, PacketInside, Solution, calculate_version_sum, parse_packet_string,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(971));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(971)
+            );
         }
 
         #[test]
         fn example_literal_1() {
             let example_packet = "D2FE28".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             assert_eq!(
                 Packet {
@@ -366,11 +300,36 @@ mod test {
             // assert_eq!(6, calculate_history_sum(&translated));
         }
 
+        #[test]
+        fn truncated() {
+            // the literal's last group is cut off
+            let error = parse_packet_string("D2FE").unwrap_err();
+
+            assert_eq!(
+                "line 1, column 5: asked for 1 bits at bit 16, but only 0 are left",
+                error.to_string()
+            );
+        }
+
+        #[test]
+        fn wrong_sub_packet_count() {
+            // greater than, with only one packet inside
+            assert_eq!(
+                "line 1, column 1: packet type 5 needs exactly 2 packets inside, got 1",
+                parse_packet_string("16004408").unwrap_err().to_string()
+            );
+            // a sum inside a sum, with nothing inside the inner one
+            assert_eq!(
+                "line 1, column 5: packet type 0 needs at least 1 packet inside, got 0",
+                parse_packet_string("0200408000").unwrap_err().to_string()
+            );
+        }
+
         #[test]
         fn example_operator_length_type_id_1() {
             let example_packet = "38006F45291200".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             assert_eq!(
                 Packet {
@@ -399,7 +358,7 @@ mod test {
         fn example_operator_length_type_id_2() {
             let example_packet = "EE00D40C823060".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             assert_eq!(
                 Packet {
@@ -433,7 +392,7 @@ mod test {
         fn example_3() {
             let example_packet = "8A004A801A8002F478".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             assert_eq!(
                 Packet {
@@ -463,7 +422,7 @@ mod test {
         fn example_4() {
             let example_packet = "620080001611562C8802118E34".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             assert_eq!(
                 Packet {
@@ -514,7 +473,7 @@ mod test {
         fn example_5() {
             let example_packet = "C0015000016115A2E0802F182340".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             assert_eq!(
                 Packet {
@@ -565,7 +524,7 @@ mod test {
         fn example_6() {
             let example_packet = "A0016C880162017C3686B18A3D4780".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             assert_eq!(
                 Packet {
@@ -617,19 +576,22 @@ mod test {
     mod part_2 {
         use pretty_assertions::assert_eq;
 
-        use crate::day_16::{Solution, calculate_deep_packet_value, parse_packet_string};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_16::{INPUT, Solution, calculate_deep_packet_value, parse_packet_string};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U64(831_996_589_851));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U64(831_996_589_851)
+            );
         }
 
         #[test]
         fn example_1() {
             let example_packet = "C200B40A82".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             let total = calculate_deep_packet_value(&translated);
 
@@ -640,7 +602,7 @@ mod test {
         fn example_2() {
             let example_packet = "04005AC33890".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             let total = calculate_deep_packet_value(&translated);
 
@@ -651,7 +613,7 @@ mod test {
         fn example_3() {
             let example_packet = "880086C3E88112".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             let total = calculate_deep_packet_value(&translated);
 
@@ -662,7 +624,7 @@ mod test {
         fn example_4() {
             let example_packet = "CE00C43D881120".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             let total = calculate_deep_packet_value(&translated);
 
@@ -673,7 +635,7 @@ mod test {
         fn example_5() {
             let example_packet = "D8005AC2A8F0".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             let total = calculate_deep_packet_value(&translated);
 
@@ -684,7 +646,7 @@ mod test {
         fn example_6() {
             let example_packet = "F600BC2D8F".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             let total = calculate_deep_packet_value(&translated);
 
@@ -695,7 +657,7 @@ mod test {
         fn example_7() {
             let example_packet = "9C005AC2F8F0".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             let total = calculate_deep_packet_value(&translated);
 
@@ -706,7 +668,7 @@ mod test {
         fn example_8() {
             let example_packet = "9C0141080250320F1802104A08".to_owned();
 
-            let translated = parse_packet_string(&example_packet);
+            let translated = parse_packet_string(&example_packet).unwrap();
 
             let total = calculate_deep_packet_value(&translated);
 
@@ -1,6 +1,10 @@
 use std::cell::Cell;
 
-use crate::shared::{Day, PartSolution};
+use shared::parse::{ParseError, RecordParser, get_line};
+
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_17/input.txt");
 
 struct Probe {
     velocity_x: Cell<i32>,
@@ -34,7 +38,8 @@ impl Probe {
     }
 }
 
-struct Target {
+#[derive(PartialEq, Eq, Debug)]
+pub struct Target {
     x1: i32,
     x2: i32,
     y1: i32,
@@ -107,7 +112,7 @@ fn find_max_y(target: &Target) -> i32 {
 
     let x_range = min_x..=max_x;
 
-    println!("Launching X between {:?}", x_range);
+    ::tracing::event!(::tracing::Level::DEBUG, "Launching X between {:?}", x_range);
 
     for launch_x in x_range {
         let mut max_y_for_x = i32::MIN;
@@ -120,7 +125,12 @@ fn find_max_y(target: &Target) -> i32 {
                 Hit::Hit(y) => {
                     if y > max_y_for_x {
                         max_y_for_x = y;
-                        println!("MAX HIT AT {},{}", launch_x, launch_y);
+                        ::tracing::event!(
+                            ::tracing::Level::TRACE,
+                            "MAX HIT AT {},{}",
+                            launch_x,
+                            launch_y
+                        );
                     }
                 },
                 Hit::Missed => {},
@@ -151,7 +161,7 @@ fn count_hits(target: &Target) -> u32 {
             match result {
                 Hit::Hit(_) => {
                     hits += 1;
-                    println!("HIT AT {},{}", launch_x, launch_y);
+                    ::tracing::event!(::tracing::Level::TRACE, "HIT AT {},{}", launch_x, launch_y);
                 },
                 Hit::Missed => {},
             }
@@ -161,43 +171,47 @@ fn count_hits(target: &Target) -> u32 {
     hits
 }
 
-fn parse_lines(_lines: &[String]) -> Target {
-    // let line = lines[0].replace("target area: ", "");
+fn parse_lines(lines: &[&str]) -> Result<Target, ParseError> {
+    let parser = RecordParser::new(
+        r"^target area: x=(-?\d+)\.\.(-?\d+), y=(-?\d+)\.\.(-?\d+)$",
+        "target area: x=x1..x2, y=y1..y2",
+    );
 
-    Target {
-        x1: 88,
-        x2: 125,
-        y1: -157,
-        y2: -103,
-    }
+    let record = parser.parse(0, get_line(lines, 0)?)?;
+
+    Ok(Target {
+        x1: record.get(1)?,
+        x2: record.get(2)?,
+        y1: record.get(3)?,
+        y2: record.get(4)?,
+    })
 }
+
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_17/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
-
-        let target = parse_lines(&lines);
+    type Parsed = Target;
 
-        let max = find_max_y(&target);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::I32(max)
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<String> = include_str!("day_17/input.txt")
-            .lines()
-            .map(Into::into)
-            .collect();
-
-        let target = parse_lines(&lines);
-
-        let hits = count_hits(&target);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::I32(find_max_y(parsed)))
+    }
 
-        PartSolution::U32(hits)
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U32(count_hits(parsed)))
     }
 }
 
@@ -209,12 +223,28 @@ mod test {
 
         use pretty_assertions::assert_eq;
 
-        use crate::day_17::{Probe, Solution, Target, find_max_y};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_17::{INPUT, Probe, Solution, Target, find_max_y, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::I32(12246));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::I32(12246)
+            );
+        }
+
+        #[test]
+        fn parses_target() {
+            assert_eq!(
+                Target {
+                    x1: 20,
+                    x2: 30,
+                    y1: -10,
+                    y2: -5,
+                },
+                parse_lines(&["target area: x=20..30, y=-10..-5"]).unwrap()
+            );
         }
 
         #[test]
@@ -441,12 +471,15 @@ mod test {
     mod part_2 {
         use pretty_assertions::assert_eq;
 
-        use crate::day_17::{Solution, Target, count_hits};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_17::{INPUT, Solution, Target, count_hits};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(3528));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U32(3528)
+            );
         }
 
         #[test]
@@ -1,17 +1,28 @@
 use std::fmt::Debug;
 
-use crate::shared::{Day, PartSolution};
+use shared::parse::ParseError;
+
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_18/input.txt");
 
 static COMMA_WIDTH: usize = 1;
 static BRACE_WIDTH: usize = 1;
 
+/// A snailfish number, a regular number or a pair of snailfish numbers.
 #[derive(PartialEq, Eq, Clone)]
-enum Snailfish {
+pub enum Snailfish {
     Value(u32),
     Pair((Box<Snailfish>, Box<Snailfish>)),
 }
 
 impl Snailfish {
+    /// 3 times the magnitude of the left side plus 2 times that of the right, all the way down.
+    #[must_use]
+    pub fn magnitude(&self) -> u32 {
+        calculate_magnitude(self)
+    }
+
     fn extract_value(self) -> Option<u32> {
         match self {
             Snailfish::Value(x) => Some(x),
@@ -79,32 +90,88 @@ impl SplitSnailfish {
     }
 }
 
-fn parse_snailfish_pair(chars: &[char], offset: usize) -> (Snailfish, usize) {
-    let as_digit = chars[offset].to_digit(10);
+fn expect_char(
+    line_index: usize,
+    chars: &[char],
+    offset: usize,
+    expected: char,
+) -> Result<(), ParseError> {
+    match chars.get(offset) {
+        Some(&c) if c == expected => Ok(()),
+        Some(&c) => Err(ParseError::new(
+            line_index,
+            offset,
+            format!("expected `{}`, found `{}`", expected, c),
+        )),
+        None => Err(ParseError::new(
+            line_index,
+            offset,
+            format!("expected `{}`, found the end of the line", expected),
+        )),
+    }
+}
+
+fn parse_snailfish_pair(
+    line_index: usize,
+    chars: &[char],
+    offset: usize,
+) -> Result<(Snailfish, usize), ParseError> {
+    let as_digit = chars.get(offset).and_then(|c| c.to_digit(10));
     if let Some(d) = as_digit {
-        return (Snailfish::Value(d), 1);
+        return Ok((Snailfish::Value(d), 1));
     }
 
-    let (left, left_width) = parse_snailfish_pair(chars, offset + BRACE_WIDTH);
-    let (right, consumed2) =
-        parse_snailfish_pair(chars, offset + BRACE_WIDTH + left_width + COMMA_WIDTH);
+    expect_char(line_index, chars, offset, '[')?;
+
+    let (left, left_width) = parse_snailfish_pair(line_index, chars, offset + BRACE_WIDTH)?;
+
+    expect_char(line_index, chars, offset + BRACE_WIDTH + left_width, ',')?;
 
-    (
+    let (right, consumed2) = parse_snailfish_pair(
+        line_index,
+        chars,
+        offset + BRACE_WIDTH + left_width + COMMA_WIDTH,
+    )?;
+
+    expect_char(
+        line_index,
+        chars,
+        offset + BRACE_WIDTH + left_width + COMMA_WIDTH + consumed2,
+        ']',
+    )?;
+
+    Ok((
         Snailfish::Pair((Box::new(left), Box::new(right))),
         BRACE_WIDTH + left_width + COMMA_WIDTH + consumed2 + BRACE_WIDTH,
-    )
+    ))
 }
 
-fn parse_lines(lines: &[&str]) -> Vec<Snailfish> {
+fn parse_lines(lines: &[&str]) -> Result<Vec<Snailfish>, ParseError> {
     let mut snailfish_pairs = Vec::new();
-    for line in lines {
-        snailfish_pairs.push(parse_snailfish_pair(&line.chars().collect::<Vec<_>>(), 0));
+    for (line_index, line) in lines.iter().enumerate() {
+        let chars = line.chars().collect::<Vec<_>>();
+
+        let (snailfish, width) = parse_snailfish_pair(line_index, &chars, 0)?;
+
+        if width < chars.len() {
+            return Err(ParseError::new(
+                line_index,
+                width,
+                "unexpected characters after the snailfish number".to_owned(),
+            ));
+        }
+
+        snailfish_pairs.push(snailfish);
+    }
+
+    if snailfish_pairs.is_empty() {
+        return Err(ParseError::end_of_input(
+            lines,
+            "expected at least one snailfish number".to_owned(),
+        ));
     }
 
-    snailfish_pairs
-        .into_iter()
-        .map(|(s, _)| s)
-        .collect::<Vec<_>>()
+    Ok(snailfish_pairs)
 }
 
 fn add_number_to_snailfish(snailfish: Snailfish, number: Option<u32>, side: Side) -> Snailfish {
@@ -195,9 +262,13 @@ fn split(snailfish: Snailfish, did1split: bool) -> SplitSnailfish {
 
 fn sum_explode_split(input: Vec<Snailfish>) -> Snailfish {
     let reduced = input.into_iter().reduce(|acc, item| {
-        println!("Adding {:?} and {:?}", acc, item);
+        ::tracing::event!(::tracing::Level::TRACE, "Adding {:?} and {:?}", acc, item);
         let mut snailfish_sum = Snailfish::Pair((acc.into(), item.into()));
-        println!("after addition: {:?}", snailfish_sum);
+        ::tracing::event!(
+            ::tracing::Level::TRACE,
+            "after addition: {:?}",
+            snailfish_sum
+        );
 
         let mut stable = false;
 
@@ -215,7 +286,11 @@ fn sum_explode_split(input: Vec<Snailfish>) -> Snailfish {
                 snailfish_sum = esf.snailfish;
 
                 if esf.exploded {
-                    println!("after explode:  {:?}", snailfish_sum);
+                    ::tracing::event!(
+                        ::tracing::Level::TRACE,
+                        "after explode:  {:?}",
+                        snailfish_sum
+                    );
 
                     // if we had an explosion we have to run again
                     explosion_stable = false;
@@ -233,7 +308,11 @@ fn sum_explode_split(input: Vec<Snailfish>) -> Snailfish {
             snailfish_sum = ssf.snailfish;
 
             if ssf.split {
-                println!("after split:    {:?}", snailfish_sum);
+                ::tracing::event!(
+                    ::tracing::Level::TRACE,
+                    "after split:    {:?}",
+                    snailfish_sum
+                );
 
                 stable = false;
             }
@@ -286,24 +365,34 @@ fn find_combination_with_highest_magnitude(
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_18/input.txt").lines().collect();
+    type Parsed = Vec<Snailfish>;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let snailfish = parse_lines(&lines);
+        Ok(parse_lines(&lines)?)
+    }
 
-        let result = sum_explode_split(snailfish);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let result = sum_explode_split(parsed.clone());
 
         let magnitude = calculate_magnitude(&result);
 
-        PartSolution::U32(magnitude)
+        Ok(PartSolution::U32(magnitude))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_18/input.txt").lines().collect();
-
-        let snailfish = parse_lines(&lines);
-
-        PartSolution::U32(find_combination_with_highest_magnitude(&snailfish).0)
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U32(
+            find_combination_with_highest_magnitude(parsed).0,
+        ))
     }
 }
 
@@ -314,14 +403,17 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_18::{
-            Snailfish, Solution, calculate_magnitude, explode, parse_lines, parse_snailfish_pair,
-            split, sum_explode_split,
+            INPUT, Snailfish, Solution, calculate_magnitude, explode, parse_lines,
+            parse_snailfish_pair, split, sum_explode_split,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(4243));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(4243)
+            );
         }
 
         #[test]
@@ -331,7 +423,7 @@ mod test {
             let expected =
                 Snailfish::Pair((Box::new(Snailfish::Value(1)), Box::new(Snailfish::Value(2))));
 
-            let (result, _) = parse_snailfish_pair(&str.chars().collect::<Vec<_>>(), 0);
+            let (result, _) = parse_snailfish_pair(0, &str.chars().collect::<Vec<_>>(), 0).unwrap();
 
             assert_eq!(expected, result);
         }
@@ -348,7 +440,7 @@ mod test {
                 Box::new(Snailfish::Value(3)),
             ));
 
-            let (result, _) = parse_snailfish_pair(&str.chars().collect::<Vec<_>>(), 0);
+            let (result, _) = parse_snailfish_pair(0, &str.chars().collect::<Vec<_>>(), 0).unwrap();
 
             assert_eq!(expected, result);
         }
@@ -365,7 +457,7 @@ mod test {
                 ))),
             ));
 
-            let (result, _) = parse_snailfish_pair(&str.chars().collect::<Vec<_>>(), 0);
+            let (result, _) = parse_snailfish_pair(0, &str.chars().collect::<Vec<_>>(), 0).unwrap();
 
             assert_eq!(expected, result);
         }
@@ -385,7 +477,7 @@ mod test {
                 ))),
             ));
 
-            let (result, _) = parse_snailfish_pair(&str.chars().collect::<Vec<_>>(), 0);
+            let (result, _) = parse_snailfish_pair(0, &str.chars().collect::<Vec<_>>(), 0).unwrap();
 
             assert_eq!(expected, result);
         }
@@ -420,7 +512,7 @@ mod test {
                 Box::new(Snailfish::Value(9)),
             ));
 
-            let (result, _) = parse_snailfish_pair(&str.chars().collect::<Vec<_>>(), 0);
+            let (result, _) = parse_snailfish_pair(0, &str.chars().collect::<Vec<_>>(), 0).unwrap();
 
             assert_eq!(expected, result);
         }
@@ -461,7 +553,7 @@ mod test {
                 ))),
             ));
 
-            let (result, _) = parse_snailfish_pair(&str.chars().collect::<Vec<_>>(), 0);
+            let (result, _) = parse_snailfish_pair(0, &str.chars().collect::<Vec<_>>(), 0).unwrap();
 
             assert_eq!(expected, result);
         }
@@ -471,9 +563,10 @@ mod test {
             let unparsed_input = "[[[[[9,8],1],2],3],4]".to_owned();
             let unparsed_expected = "[[[[0,9],2],3],4]".to_owned();
 
-            let (input, _) = parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+            let (input, _) =
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let exploded = explode(input, 0, false);
 
@@ -485,9 +578,10 @@ mod test {
             let unparsed_input = "[7,[6,[5,[4,[3,2]]]]]".to_owned();
             let unparsed_expected = "[7,[6,[5,[7,0]]]]".to_owned();
 
-            let (input, _) = parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+            let (input, _) =
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let exploded = explode(input, 0, false);
 
@@ -499,9 +593,10 @@ mod test {
             let unparsed_input = "[[6,[5,[4,[3,2]]]],1]".to_owned();
             let unparsed_expected = "[[6,[5,[7,0]]],3]".to_owned();
 
-            let (input, _) = parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+            let (input, _) =
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let exploded = explode(input, 0, false);
 
@@ -513,9 +608,10 @@ mod test {
             let unparsed_input = "[[3,[2,[1,[7,3]]]],[6,[5,[4,[3,2]]]]]".to_owned();
             let unparsed_expected = "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]".to_owned();
 
-            let (input, _) = parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+            let (input, _) =
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let exploded = explode(input, 0, false);
 
@@ -527,9 +623,10 @@ mod test {
             let unparsed_input = "[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]".to_owned();
             let unparsed_expected = "[[3,[2,[8,0]]],[9,[5,[7,0]]]]".to_owned();
 
-            let (input, _) = parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+            let (input, _) =
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let exploded = explode(input, 0, false);
 
@@ -543,12 +640,12 @@ mod test {
             let unparsed_expected = "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]".to_owned();
 
             let (input_1, _) =
-                parse_snailfish_pair(&unparsed_input_1.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input_1.chars().collect::<Vec<_>>(), 0).unwrap();
             let (input_2, _) =
-                parse_snailfish_pair(&unparsed_input_2.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input_2.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let sum = Snailfish::Pair((input_1.into(), input_2.into()));
 
@@ -568,12 +665,12 @@ mod test {
             let unparsed_expected = "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]".to_owned();
 
             let (input_1, _) =
-                parse_snailfish_pair(&unparsed_input_1.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input_1.chars().collect::<Vec<_>>(), 0).unwrap();
             let (input_2, _) =
-                parse_snailfish_pair(&unparsed_input_2.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input_2.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let result = sum_explode_split(vec![input_1, input_2]);
 
@@ -585,10 +682,10 @@ mod test {
             let unparsed_input_s = vec!["[1,1]", "[2,2]", "[3,3]", "[4,4]"];
             let unparsed_expected = "[[[[1,1],[2,2]],[3,3]],[4,4]]".to_owned();
 
-            let snailfish = parse_lines(&unparsed_input_s);
+            let snailfish = parse_lines(&unparsed_input_s).unwrap();
 
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let result = sum_explode_split(snailfish);
 
@@ -600,10 +697,10 @@ mod test {
             let unparsed_input_s = vec!["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]"];
             let unparsed_expected = "[[[[3,0],[5,3]],[4,4]],[5,5]]".to_owned();
 
-            let snailfish = parse_lines(&unparsed_input_s);
+            let snailfish = parse_lines(&unparsed_input_s).unwrap();
 
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let result = sum_explode_split(snailfish);
 
@@ -615,10 +712,10 @@ mod test {
             let unparsed_input_s = vec!["[1,1]", "[2,2]", "[3,3]", "[4,4]", "[5,5]", "[6,6]"];
             let unparsed_expected = "[[[[5,0],[7,4]],[5,5]],[6,6]]".to_owned();
 
-            let snailfish = parse_lines(&unparsed_input_s);
+            let snailfish = parse_lines(&unparsed_input_s).unwrap();
 
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let result = sum_explode_split(snailfish);
 
@@ -643,10 +740,10 @@ mod test {
             let unparsed_expected =
                 "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]".to_owned();
 
-            let snailfish = parse_lines(&unparsed_input_s);
+            let snailfish = parse_lines(&unparsed_input_s).unwrap();
 
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let result = sum_explode_split(snailfish);
 
@@ -658,7 +755,7 @@ mod test {
             let unparsed_input = "[9,1]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 29;
 
@@ -672,7 +769,7 @@ mod test {
             let unparsed_input = "[1,9]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 21;
 
@@ -686,7 +783,7 @@ mod test {
             let unparsed_input = "[[9,1],[1,9]]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 129;
 
@@ -700,7 +797,7 @@ mod test {
             let unparsed_input = "[[1,2],[[3,4],5]]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 143;
 
@@ -714,7 +811,7 @@ mod test {
             let unparsed_input = "[[[[0,7],4],[[7,8],[6,0]]],[8,1]]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 1384;
 
@@ -727,7 +824,7 @@ mod test {
             let unparsed_input = "[[[[1,1],[2,2]],[3,3]],[4,4]]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 445;
 
@@ -740,7 +837,7 @@ mod test {
             let unparsed_input = "[[[[3,0],[5,3]],[4,4]],[5,5]]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 791;
 
@@ -753,7 +850,7 @@ mod test {
             let unparsed_input = "[[[[5,0],[7,4]],[5,5]],[6,6]]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 1137;
 
@@ -766,7 +863,7 @@ mod test {
             let unparsed_input = "[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]".to_owned();
 
             let (snailfish, _) =
-                parse_snailfish_pair(&unparsed_input.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_input.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let expected = 3488;
 
@@ -793,10 +890,10 @@ mod test {
             let unparsed_expected =
                 "[[[[6,6],[7,6]],[[7,7],[7,0]]],[[[7,7],[7,7]],[[7,8],[9,9]]]]".to_owned();
 
-            let snailfish = parse_lines(&unparsed_input_s);
+            let snailfish = parse_lines(&unparsed_input_s).unwrap();
 
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let result = sum_explode_split(snailfish);
 
@@ -814,13 +911,17 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_18::{
-            Solution, find_combination_with_highest_magnitude, parse_lines, parse_snailfish_pair,
+            INPUT, Solution, find_combination_with_highest_magnitude, parse_lines,
+            parse_snailfish_pair,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(4701));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U32(4701)
+            );
         }
 
         #[test]
@@ -838,24 +939,26 @@ mod test {
                 "[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]",
             ];
 
-            let snailfish = parse_lines(&unparsed_input_s);
+            let snailfish = parse_lines(&unparsed_input_s).unwrap();
 
             let unparsed_expected =
                 "[[[[7,8],[6,6]],[[6,0],[7,7]]],[[[7,8],[8,8]],[[7,9],[0,6]]]]".to_owned();
 
             let (expected, _) =
-                parse_snailfish_pair(&unparsed_expected.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected.chars().collect::<Vec<_>>(), 0).unwrap();
 
             let unparsed_expected_left = "[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]".to_owned();
 
             let (expected_left, _) =
-                parse_snailfish_pair(&unparsed_expected_left.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected_left.chars().collect::<Vec<_>>(), 0)
+                    .unwrap();
 
             let unparsed_expected_right =
                 "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]".to_owned();
 
             let (expected_right, _) =
-                parse_snailfish_pair(&unparsed_expected_right.chars().collect::<Vec<_>>(), 0);
+                parse_snailfish_pair(0, &unparsed_expected_right.chars().collect::<Vec<_>>(), 0)
+                    .unwrap();
 
             let (magnitude, left, right, result) =
                 find_combination_with_highest_magnitude(&snailfish);
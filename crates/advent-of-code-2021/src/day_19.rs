@@ -1,214 +1,186 @@
-use crate::shared::{Day, PartSolution};
+use hashbrown::HashSet;
+use shared::counter::Counter;
+use shared::geom3::{Rotation, Vec3};
+use shared::parse::{ParseError, blocks, parse_separated};
 
-#[derive(Debug)]
-struct Beacon {
-    x: i32,
-    y: i32,
-    z: i32,
-}
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_19/input.txt");
 
-struct Permutation {
-    beacons: Vec<Beacon>,
+/// The beacons a scanner sees, relative to itself and in its own orientation.
+pub struct Scanner {
+    beacons: Vec<Vec3>,
 }
 
-struct Scanner {
-    #[expect(dead_code, reason = "WIP")]
-    permutations: Vec<Permutation>,
+/// A scanner we know the position and orientation of.
+struct Placement {
+    position: Vec3,
+    /// Relative to the first scanner.
+    beacons: Vec<Vec3>,
 }
 
-fn calculate_permutations(scanners_with_single_permutation: Vec<Permutation>) -> Vec<Scanner> {
-    let mut s: Vec<Scanner> = Vec::new();
-
-    let permutation_fns = [
-        // |b: &Beacon| Beacon {
-        //     x: b.x,
-        //     y: b.y,
-        //     z: b.z,
-        // },
-        |b: &Beacon| Beacon {
-            x: b.x,
-            y: -b.z,
-            z: b.y,
-        },
-        |b: &Beacon| Beacon {
-            x: b.x,
-            y: -b.y,
-            z: -b.z,
-        },
-        |b: &Beacon| Beacon {
-            x: b.x,
-            y: b.z,
-            z: -b.y,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.y,
-            y: b.x,
-            z: b.z,
-        },
-        |b: &Beacon| Beacon {
-            x: b.z,
-            y: b.x,
-            z: b.y,
-        },
-        |b: &Beacon| Beacon {
-            x: b.y,
-            y: b.x,
-            z: -b.z,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.z,
-            y: b.x,
-            z: -b.y,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.x,
-            y: -b.y,
-            z: b.z,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.x,
-            y: -b.z,
-            z: -b.y,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.x,
-            y: b.y,
-            z: -b.z,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.x,
-            y: b.z,
-            z: b.y,
-        },
-        |b: &Beacon| Beacon {
-            x: b.y,
-            y: -b.x,
-            z: b.z,
-        },
-        |b: &Beacon| Beacon {
-            x: b.z,
-            y: -b.x,
-            z: -b.y,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.y,
-            y: -b.x,
-            z: -b.z,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.z,
-            y: -b.x,
-            z: b.y,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.z,
-            y: b.y,
-            z: b.x,
-        },
-        |b: &Beacon| Beacon {
-            x: b.y,
-            y: b.z,
-            z: b.x,
-        },
-        |b: &Beacon| Beacon {
-            x: b.z,
-            y: -b.y,
-            z: b.x,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.y,
-            y: -b.z,
-            z: b.x,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.z,
-            y: -b.y,
-            z: -b.x,
-        },
-        |b: &Beacon| Beacon {
-            x: -b.y,
-            y: b.z,
-            z: -b.x,
-        },
-        |b: &Beacon| Beacon {
-            x: b.z,
-            y: b.y,
-            z: -b.x,
-        },
-        |b: &Beacon| Beacon {
-            x: b.y,
-            y: -b.z,
-            z: -b.x,
-        },
-    ];
-    for original_permutation in scanners_with_single_permutation {
-        let mut permutations = Vec::new();
-
-        for permutation_fn in permutation_fns {
-            let mut permutation = Vec::new();
-
-            for beacon in &original_permutation.beacons {
-                permutation.push(permutation_fn(beacon));
+/// Tries every orientation of `scanner` against the beacons of one we already placed, until at
+/// least 12 of its beacons land on the same spots.
+fn align(rotations: &[Rotation], reference: &Placement, scanner: &Scanner) -> Option<Placement> {
+    for &rotation in rotations {
+        let rotated: Vec<Vec3> = scanner
+            .beacons
+            .iter()
+            .map(|&beacon| rotation.apply(beacon))
+            .collect();
+
+        // if 2 beacons are the same, the offset between them is the scanner's position
+        let mut offsets = Counter::new();
+
+        for &known in &reference.beacons {
+            for &beacon in &rotated {
+                let offset = known - beacon;
+
+                if offsets.add(offset) >= 12 {
+                    return Some(Placement {
+                        position: offset,
+                        beacons: rotated.iter().map(|&beacon| beacon + offset).collect(),
+                    });
+                }
             }
-
-            permutations.push(Permutation {
-                beacons: permutation,
-            });
         }
+    }
+
+    None
+}
+
+/// Places every scanner relative to the first one.
+fn place_scanners(
+    scanners: &[Scanner],
+    cancel: &CancellationToken,
+) -> Result<Vec<Placement>, DayError> {
+    let Some((first, rest)) = scanners.split_first() else {
+        return Err(DayError::NoSolution("there are no scanners".to_owned()));
+    };
+
+    let rotations = Rotation::all();
+
+    let mut placed = vec![Placement {
+        position: Vec3::ZERO,
+        beacons: first.beacons.clone(),
+    }];
+    let mut unplaced: Vec<&Scanner> = rest.iter().collect();
 
-        permutations.push(original_permutation);
+    // every pair is compared once, a scanner we can't align with one placed scanner might
+    // overlap with one we place later
+    let mut next = 0;
 
-        s.push(Scanner { permutations });
+    while let Some(reference) = placed.get(next) {
+        let mut newly_placed = Vec::new();
+
+        unplaced.retain(|scanner| {
+            align(&rotations, reference, scanner).is_none_or(|placement| {
+                newly_placed.push(placement);
+
+                false
+            })
+        });
+
+        placed.extend(newly_placed);
+        next += 1;
+
+        cancel.check()?;
     }
 
-    s
+    if !unplaced.is_empty() {
+        return Err(DayError::NoSolution(format!(
+            "{} scanners don't overlap with the others",
+            unplaced.len()
+        )));
+    }
+
+    Ok(placed)
 }
 
-fn parse_beacon_line(line: &str) -> Beacon {
-    let split: Vec<&str> = line.split(',').collect();
-    let x = split[0].parse::<i32>().unwrap();
-    let y = split[1].parse::<i32>().unwrap();
-    let z = split[2].parse::<i32>().unwrap();
+fn count_beacons(placed: &[Placement]) -> usize {
+    placed
+        .iter()
+        .flat_map(|placement| &placement.beacons)
+        .collect::<HashSet<_>>()
+        .len()
+}
 
-    Beacon { x, y, z }
+fn largest_distance(placed: &[Placement]) -> u64 {
+    placed
+        .iter()
+        .flat_map(|first| {
+            placed
+                .iter()
+                .map(|second| first.position.manhattan_distance(second.position))
+        })
+        .max()
+        .unwrap_or_default()
 }
 
-fn parse_lines(lines: &[&str]) -> Vec<Permutation> {
-    let raw_scanners = lines.split(|l| (*l).is_empty());
+fn parse_beacon_line(line_index: usize, line: &str) -> Result<Vec3, ParseError> {
+    let &[x, y, z] = parse_separated(line_index, line, ',')?.as_slice() else {
+        return Err(ParseError::new(
+            line_index,
+            0,
+            "expected `x,y,z`".to_owned(),
+        ));
+    };
+
+    Ok(Vec3::new(x, y, z))
+}
 
+fn parse_lines(lines: &[&str]) -> Result<Vec<Scanner>, ParseError> {
     let mut scanners = Vec::new();
 
-    for scanner_lines in raw_scanners {
+    for block in blocks(lines) {
         let mut beacons = Vec::new();
 
-        for line in scanner_lines {
-            if !line.starts_with("---") {
-                let beacon = parse_beacon_line(line);
-                println!("Beacon: {:?}", beacon);
-                beacons.push(beacon);
+        for (line_index, line) in block.enumerate() {
+            if line.starts_with("---") {
+                // scanner header, scanners are numbered in order anyway
+                continue;
             }
+
+            let beacon = parse_beacon_line(line_index, line)?;
+            ::tracing::event!(::tracing::Level::TRACE, "Beacon: {:?}", beacon);
+            beacons.push(beacon);
         }
 
-        scanners.push(Permutation { beacons });
+        scanners.push(Scanner { beacons });
     }
 
-    scanners
+    Ok(scanners)
 }
+
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_19/input.txt").lines().collect();
+    type Parsed = Vec<Scanner>;
 
-        let scanners = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let _scanners_with_permutations = calculate_permutations(scanners);
+        Ok(parse_lines(&lines)?)
+    }
 
-        PartSolution::None
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let placed = place_scanners(parsed, cancel)?;
+
+        Ok(PartSolution::USize(count_beacons(&placed)))
     }
 
-    fn part_2(&self) -> PartSolution {
-        PartSolution::None
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let placed = place_scanners(parsed, cancel)?;
+
+        Ok(PartSolution::U64(largest_distance(&placed)))
     }
 }
 
@@ -217,37 +189,75 @@ mod test {
     fn get_example() -> Vec<&'static str> {
         include_str!("day_19/example.txt").lines().collect()
     }
+
     mod part_1 {
+        use hashbrown::HashSet;
         use pretty_assertions::assert_eq;
+        use shared::geom3::Vec3;
 
         use super::get_example;
-        use crate::day_19::{Solution, calculate_permutations, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_19::{INPUT, Solution, count_beacons, parse_lines, place_scanners};
+        use crate::shared::{CancellationToken, PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::None);
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::USize(376)
+            );
         }
 
         #[test]
         fn example() {
             let example_lines = get_example();
 
-            let scanners = parse_lines(&example_lines);
+            let scanners = parse_lines(&example_lines).unwrap();
+
+            let placed = place_scanners(&scanners, &CancellationToken::never()).unwrap();
+
+            // placed in the order they're found, not the order of the input
+            let positions: HashSet<Vec3> =
+                placed.iter().map(|placement| placement.position).collect();
+
+            assert_eq!(
+                HashSet::from([
+                    Vec3::new(0, 0, 0),
+                    Vec3::new(68, -1246, -43),
+                    Vec3::new(1105, -1205, 1229),
+                    Vec3::new(-92, -2380, -20),
+                    Vec3::new(-20, -1133, 1061),
+                ]),
+                positions
+            );
 
-            let _scanners_with_permutations = calculate_permutations(scanners);
+            assert_eq!(79, count_beacons(&placed));
         }
     }
 
     mod part_2 {
         use pretty_assertions::assert_eq;
 
-        use crate::day_19::Solution;
-        use crate::shared::{Day as _, PartSolution};
+        use super::get_example;
+        use crate::day_19::{INPUT, Solution, largest_distance, parse_lines, place_scanners};
+        use crate::shared::{CancellationToken, PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::None);
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U64(10_772)
+            );
+        }
+
+        #[test]
+        fn example() {
+            let example_lines = get_example();
+
+            let scanners = parse_lines(&example_lines).unwrap();
+
+            let placed = place_scanners(&scanners, &CancellationToken::never()).unwrap();
+
+            assert_eq!(3621, largest_distance(&placed));
         }
     }
 }
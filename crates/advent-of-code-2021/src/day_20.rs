@@ -1,29 +1,38 @@
-use crate::shared::{Day, PartSolution};
+use std::convert::Infallible;
+
+use shared::automaton::{Automaton, Edges, WINDOW_3X3};
+use shared::grid::Grid;
+use shared::parse::{ParseError, get_line};
+
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_20/input.txt");
 
 #[derive(PartialEq, Eq, Clone, Copy)]
-enum Pixel {
+pub enum Pixel {
     Light,
     Dark,
 }
 
 impl std::fmt::Debug for Pixel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
+        match *self {
             Self::Light => write!(f, "#"),
             Self::Dark => write!(f, "."),
         }
     }
 }
 
-struct Field {
-    picture: Vec<Vec<Pixel>>,
+#[derive(Clone)]
+pub struct Field {
+    picture: Grid<Pixel>,
     outer: Pixel,
     algorithm: Vec<Pixel>,
 }
 
 impl std::fmt::Debug for Field {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for row in &self.picture {
+        for row in self.picture.rows() {
             for col in row {
                 write!(f, "{:?}", col)?;
             }
@@ -35,245 +44,166 @@ impl std::fmt::Debug for Field {
     }
 }
 
-fn to_pixel(input: &str) -> Vec<Pixel> {
-    input
-        .chars()
-        .map(|x| if x == '#' { Pixel::Light } else { Pixel::Dark })
+/// The algorithm has a pixel for every 3x3 window, read as a 9 bit number.
+const ALGORITHM_LENGTH: usize = 1 << 9;
+
+fn parse_pixels(line_index: usize, line: &str) -> Result<Vec<Pixel>, ParseError> {
+    line.chars()
+        .enumerate()
+        .map(|(column_index, c)| match c {
+            '#' => Ok(Pixel::Light),
+            '.' => Ok(Pixel::Dark),
+            _ => Err(ParseError::new(
+                line_index,
+                column_index,
+                format!("expected `#` or `.`, got `{}`", c),
+            )),
+        })
         .collect()
 }
 
-fn parse_lookup(input: &[Pixel]) -> usize {
-    assert_eq!(9, input.len());
-
-    let binary = input
-        .iter()
-        .map(|p| match p {
-            Pixel::Light => '1',
-            Pixel::Dark => '0',
-        })
-        .collect::<String>();
-
-    usize::from_str_radix(&binary, 2).unwrap()
+/// Reads the pixels as a binary number, light pixels being ones.
+fn parse_lookup<'p, I>(input: I) -> usize
+where
+    I: IntoIterator<Item = &'p Pixel>,
+{
+    input.into_iter().fold(0, |lookup, &pixel| {
+        (lookup << 1) | usize::from(pixel == Pixel::Light)
+    })
 }
 
-fn get_lookup(field: &Field, row_index: usize, column_index: usize) -> Vec<Pixel> {
-    let can_go_left = column_index > 0;
-    let can_go_up = row_index > 0;
-
-    let lookups = vec![
-        // left up
-        if can_go_left && can_go_up {
-            field
-                .picture
-                .get(row_index - 1)
-                .and_then(|r| r.get(column_index - 1))
-        } else {
-            None
-        },
-        if can_go_up {
-            // up
-            field
-                .picture
-                .get(row_index - 1)
-                .and_then(|r| r.get(column_index))
-        } else {
-            None
-        },
-        // right up
-        if can_go_up {
-            field
-                .picture
-                .get(row_index - 1)
-                .and_then(|r| r.get(column_index + 1))
-        } else {
-            None
-        },
-        // left
-        if can_go_left && can_go_up {
-            field
-                .picture
-                .get(row_index)
-                .and_then(|r| r.get(column_index - 1))
-        } else {
-            None
-        },
-        // self
-        field
-            .picture
-            .get(row_index)
-            .and_then(|r| r.get(column_index)),
-        // right
-        field
-            .picture
-            .get(row_index)
-            .and_then(|r| r.get(column_index + 1)),
-        // left down
-        // left up
-        if can_go_left && can_go_up {
-            field
-                .picture
-                .get(row_index + 1)
-                .and_then(|r| r.get(column_index - 1))
-        } else {
-            None
-        },
-        // down
-        field
-            .picture
-            .get(row_index + 1)
-            .and_then(|r| r.get(column_index)),
-        // right down
-        field
-            .picture
-            .get(row_index + 1)
-            .and_then(|r| r.get(column_index + 1)),
-    ];
-
-    lookups
-        .iter()
-        .map(|x| match *x {
-            Some(p) => *p,
-            None => field.outer,
-        })
-        .collect()
+/// What the pixel in the middle of `window` becomes.
+fn enhance_pixel(algorithm: &[Pixel], window: [&Pixel; 9]) -> Pixel {
+    algorithm[parse_lookup(window)]
 }
 
-fn enhance(f: Field) -> Field {
-    let outer = f.outer;
-
-    let field = zoom_out(f);
-
-    let mut new_image = Vec::new();
-
-    for (row_index, row) in field.picture.iter().enumerate() {
-        let mut new_row = Vec::new();
-
-        for (col_index, _pixel) in row.iter().enumerate() {
-            let lookup = get_lookup(&field, row_index, col_index);
-
-            let translated = field.algorithm[parse_lookup(&lookup)];
-
-            new_row.push(translated);
-        }
-
-        new_image.push(new_row);
+fn parse_lines(lines: &[&str]) -> Result<Field, ParseError> {
+    // first line is the algorithm
+    let algorithm = parse_pixels(0, get_line(lines, 0)?)?;
+
+    if algorithm.len() != ALGORITHM_LENGTH {
+        return Err(ParseError::new(
+            0,
+            algorithm.len().min(ALGORITHM_LENGTH),
+            format!(
+                "expected {} pixels in the algorithm, got {}",
+                ALGORITHM_LENGTH,
+                algorithm.len()
+            ),
+        ));
     }
 
-    let new_outer = match &outer {
-        Pixel::Light => field.algorithm[0b1_1111_1111],
-        Pixel::Dark => field.algorithm[0b0_0000_0000],
-    };
+    if !get_line(lines, 1)?.is_empty() {
+        return Err(ParseError::new(
+            1,
+            0,
+            "expected an empty line after the algorithm".to_owned(),
+        ));
+    }
 
-    let field = Field {
-        picture: new_image,
-        outer: new_outer,
-        algorithm: field.algorithm,
+    // then the picture, from the third line on
+    let Some(picture_lines) = lines
+        .get(2..)
+        .filter(|picture_lines| !picture_lines.is_empty())
+    else {
+        return Err(ParseError::end_of_input(
+            lines,
+            "expected the picture after the empty line".to_owned(),
+        ));
     };
 
-    field
-}
-
-fn parse_lines(lines: &[&str]) -> Field {
-    // first line is the algorithm
-    let algorithm = to_pixel(lines[0]);
-
-    let mut picture = Vec::new();
+    let rows = picture_lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| parse_pixels(index + 2, line))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    for line in lines.iter().skip(2) {
-        picture.push(to_pixel(line));
-    }
+    // the picture starts after the algorithm and the empty line
+    let picture = Grid::from_rows(rows).map_err(|error| ParseError::from_grid_error(error, 2))?;
 
-    Field {
+    Ok(Field {
         picture,
         outer: Pixel::Dark,
         algorithm,
-    }
-}
-
-fn zoom_out(field: Field) -> Field {
-    let columns = field.picture.first().map(Vec::len).unwrap();
-
-    let add = 2;
-
-    let mut outside = Vec::new();
-    outside.resize(columns + 2 * add, field.outer);
-
-    let mut new = vec![outside.clone(); add];
-
-    for row in field.picture {
-        let mut new_row = vec![field.outer; add];
-
-        for col in row {
-            new_row.push(col);
-        }
-
-        for _ in 0..add {
-            new_row.push(field.outer);
-        }
-
-        new.push(new_row);
-    }
-
-    for _ in 0..add {
-        new.push(outside.clone());
-    }
-
-    Field {
-        picture: new,
-        outer: field.outer,
-        algorithm: field.algorithm,
-    }
+    })
 }
 
 fn count_lit_pixels(field: &Field) -> u32 {
     let mut count: u32 = 0;
 
-    for row in &field.picture {
-        for col in row {
-            if col == &Pixel::Light {
-                count += 1;
-            }
+    for (_, pixel) in field.picture.iter() {
+        if pixel == &Pixel::Light {
+            count += 1;
         }
     }
 
     count
 }
 
-fn enhance_times(field: Field, times: u32) -> Field {
-    let mut new_field = field;
+/// The picture only covers what we know, everything around it is `outer`, which follows the
+/// algorithm too.
+fn enhance_times(field: Field, times: u64) -> Field {
+    let mut automaton = Automaton::new(field.picture, WINDOW_3X3, Edges::Infinite(field.outer));
+
+    let algorithm = field.algorithm;
+
+    let Ok(()) = automaton.run(
+        times,
+        |_, window| enhance_pixel(&algorithm, window),
+        |automaton| {
+            ::tracing::event!(
+                ::tracing::Level::TRACE,
+                "After step {}:\n{}",
+                automaton.generation(),
+                automaton.cells().map(|pixel| format!("{:?}", pixel))
+            );
 
-    for _ in 0..times {
-        new_field = enhance(new_field);
-    }
+            Ok::<_, Infallible>(())
+        },
+    );
+
+    let outer = automaton.background().copied().unwrap_or(Pixel::Dark);
 
-    new_field
+    Field {
+        picture: automaton.into_cells(),
+        outer,
+        algorithm,
+    }
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_20/input.txt").lines().collect();
+    type Parsed = Field;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let field = parse_lines(&lines);
+        Ok(parse_lines(&lines)?)
+    }
 
-        let field = enhance_times(field, 2);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let field = enhance_times(parsed.clone(), 2);
 
         let lit_pixels = count_lit_pixels(&field);
 
-        PartSolution::U32(lit_pixels)
+        Ok(PartSolution::U32(lit_pixels))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_20/input.txt").lines().collect();
-
-        let field = parse_lines(&lines);
-
-        let field = enhance_times(field, 50);
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let field = enhance_times(parsed.clone(), 50);
 
         let lit_pixels = count_lit_pixels(&field);
 
-        PartSolution::U32(lit_pixels)
+        Ok(PartSolution::U32(lit_pixels))
     }
 }
 
@@ -283,34 +213,35 @@ mod test {
         include_str!("day_20/example.txt").lines().collect()
     }
     mod part_1 {
-use pretty_assertions::assert_eq;
-
-
+        use pretty_assertions::assert_eq;
 
         use crate::{
             day_20::{
-                Pixel, Solution, count_lit_pixels, enhance, enhance_times, get_lookup, parse_lines,
-                parse_lookup, to_pixel,
+                INPUT, Pixel, Solution, count_lit_pixels, enhance_pixel, enhance_times,
+                parse_lines, parse_lookup, parse_pixels,
             },
-            shared::{Day, PartSolution},
+            shared::{PartSolution, solve},
         };
 
         use super::get_example;
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(5425));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(5425)
+            );
         }
 
         #[test]
         fn example() {
             let example_lines = get_example();
 
-            let field = parse_lines(&example_lines);
+            let field = parse_lines(&example_lines).unwrap();
 
-            let field = enhance(field);
+            let field = enhance_times(field, 1);
 
-            let field = enhance(field);
+            let field = enhance_times(field, 1);
 
             let lit_pixels = count_lit_pixels(&field);
 
@@ -321,7 +252,7 @@ use pretty_assertions::assert_eq;
         fn example_times() {
             let example_lines = get_example();
 
-            let field = parse_lines(&example_lines);
+            let field = parse_lines(&example_lines).unwrap();
 
             let field = enhance_times(field, 2);
 
@@ -331,8 +262,41 @@ use pretty_assertions::assert_eq;
         }
 
         #[test]
-        fn to_pixel() {
-            let pixels = to_pixel("#..#.");
+        fn invalid_input() {
+            let algorithm = get_example()[0];
+
+            let error = |lines: &[&str]| parse_lines(lines).unwrap_err().to_string();
+
+            assert_eq!("line 1, column 1: expected at least 1 lines", error(&[]));
+            assert_eq!(
+                "line 1, column 4: expected 512 pixels in the algorithm, got 3",
+                error(&["#.#", "", "#"])
+            );
+            assert_eq!(
+                "line 1, column 3: expected `#` or `.`, got `x`",
+                error(&["#.x", "", "#"])
+            );
+            assert_eq!(
+                "line 2, column 1: expected at least 2 lines",
+                error(&[algorithm])
+            );
+            assert_eq!(
+                "line 2, column 1: expected an empty line after the algorithm",
+                error(&[algorithm, "#.#"])
+            );
+            assert_eq!(
+                "line 3, column 1: expected the picture after the empty line",
+                error(&[algorithm, ""])
+            );
+            assert_eq!(
+                "line 4, column 2: expected 3 cells, got 1",
+                error(&[algorithm, "", "#.#", "#"])
+            );
+        }
+
+        #[test]
+        fn to_pixels() {
+            let pixels = parse_pixels(0, "#..#.").unwrap();
 
             assert_eq!(
                 vec![
@@ -348,42 +312,47 @@ use pretty_assertions::assert_eq;
 
         #[test]
         fn to_lookup() {
-            let lookup = parse_lookup(&to_pixel("...#...#."));
+            let lookup = parse_lookup(&parse_pixels(0, "...#...#.").unwrap());
 
             assert_eq!(34, lookup);
         }
 
         #[test]
-        fn get_lookup() {
+        fn lookup_at() {
             let example_lines = get_example();
 
-            let field = parse_lines(&example_lines);
-
-            let lookup = get_lookup(&field, 2, 2);
+            let field = parse_lines(&example_lines).unwrap();
 
-            let parsed_lookup = parse_lookup(&lookup);
+            let window = field
+                .picture
+                .window_3x3((2, 2))
+                .map(|pixel| pixel.unwrap_or(&field.outer));
 
-            let expected_algorithm_lookup = field.algorithm[parse_lookup(&to_pixel("...#...#."))];
+            let expected_algorithm_lookup =
+                field.algorithm[parse_lookup(&parse_pixels(0, "...#...#.").unwrap())];
 
-            assert_eq!(expected_algorithm_lookup, field.algorithm[parsed_lookup]);
+            assert_eq!(
+                expected_algorithm_lookup,
+                enhance_pixel(&field.algorithm, window)
+            );
         }
     }
 
     mod part_2 {
-use pretty_assertions::assert_eq;
-
-
+        use pretty_assertions::assert_eq;
 
         use crate::{
-            day_20::{Solution, count_lit_pixels, enhance_times, parse_lines, test::get_example},
-            shared::{Day, PartSolution},
+            day_20::{
+                INPUT, Solution, count_lit_pixels, enhance_times, parse_lines, test::get_example,
+            },
+            shared::{PartSolution, solve},
         };
 
         #[test]
         fn example_times() {
             let example_lines = get_example();
 
-            let field = parse_lines(&example_lines);
+            let field = parse_lines(&example_lines).unwrap();
 
             let field = enhance_times(field, 50);
 
@@ -394,7 +363,10 @@ use pretty_assertions::assert_eq;
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_2(), PartSolution::U32(14052));
+            assert_eq!(
+                solve(&Solution {}, 2, INPUT).unwrap(),
+                PartSolution::U32(14052)
+            );
         }
     }
 }
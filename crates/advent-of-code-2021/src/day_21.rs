@@ -1,7 +1,10 @@
-use hashbrown::HashMap;
-use regex::Regex;
+use shared::counter::Counter;
+use shared::memo::Memo;
+use shared::parse::{ParseError, RecordParser};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_21/input.txt");
 
 struct DeterministicDie {
     last: u32,
@@ -19,7 +22,7 @@ impl DeterministicDie {
 }
 
 #[derive(Clone, PartialEq, Hash, Eq, Debug)]
-struct Player {
+pub struct Player {
     number: u32,
     position: u32,
     score: u32,
@@ -65,7 +68,8 @@ fn play(mut players: Vec<Player>, until: u32) -> (u32, u32) {
             }
 
             player.r#move(rolled.iter().sum());
-            println!(
+            ::tracing::event!(
+                ::tracing::Level::TRACE,
                 "Player {} rolls {} and moves to place {} for a total score of {}.",
                 player.number,
                 rolled
@@ -86,20 +90,33 @@ fn play(mut players: Vec<Player>, until: u32) -> (u32, u32) {
     (players.iter().map(|p| p.score).min().unwrap(), dice_rolls)
 }
 
-fn parse_lines(lines: &[&str]) -> Vec<Player> {
-    let mut players = Vec::new();
+fn parse_lines(lines: &[&str]) -> Result<Vec<Player>, ParseError> {
+    let parser = RecordParser::new(
+        r"^Player (\d+) starting position: (\d+)$",
+        "Player N starting position: P",
+    );
+
+    let players = parser.parse_all(lines, |record| {
+        let start_position: u32 = record.get(2)?;
 
-    let regex = Regex::new(r"Player (\d+) starting position: (\d+)").unwrap();
+        if !(1..=10).contains(&start_position) {
+            return Err(record.error(
+                2,
+                format!("{} is not a place on the board of 1 to 10", start_position),
+            ));
+        }
 
-    for line in lines {
-        let captures = regex.captures(line.trim()).unwrap();
+        Ok(Player::new(record.get(1)?, start_position))
+    })?;
 
-        let player_number = captures[1].parse::<u32>().unwrap();
-        let start_position = captures[2].parse::<u32>().unwrap();
-        players.push(Player::new(player_number, start_position));
+    if players.is_empty() {
+        return Err(ParseError::end_of_input(
+            lines,
+            "expected at least one player".to_owned(),
+        ));
     }
 
-    players
+    Ok(players)
 }
 
 #[derive(Eq, PartialEq, Hash, Clone, Debug)]
@@ -117,66 +134,38 @@ impl Game {
     }
 }
 
-fn get_quantum_die_rolls() -> HashMap<u32, u32> {
-    let mut rolls = Vec::new();
-    for r1 in 1..=3 {
-        for r2 in 1..=3 {
-            for r3 in 1..=3 {
-                rolls.push(vec![r1, r2, r3]);
-            }
-        }
-    }
-
-    let mut rolls_sum_with_count = HashMap::new();
-
-    for roll in rolls {
-        rolls_sum_with_count
-            .entry(roll.iter().sum::<u32>())
-            .and_modify(|c| *c += 1)
-            .or_insert(1);
-    }
-
-    rolls_sum_with_count
+/// The sums of 3 rolls of a 3-sided die, with how many of the 27 universes roll them.
+fn get_quantum_die_rolls() -> Counter<u32> {
+    (1..=3)
+        .flat_map(|r1| (1..=3).flat_map(move |r2| (1..=3).map(move |r3| r1 + r2 + r3)))
+        .collect()
 }
 
-fn play_quantum(cache: &mut HashMap<Game, Vec<u64>>, game: &Game, until: u32) -> Vec<u64> {
+/// How many universes each player wins in, from `game` on.
+fn play_quantum(memo: &mut Memo<Game, Vec<u64>>, game: &Game, until: u32) -> Vec<u64> {
     let rolls = get_quantum_die_rolls();
 
     let mut results = vec![0; game.players.len()];
 
     let next_player = (game.current_player + 1) % game.players.len();
 
-    for (roll_sum, occurrence) in &rolls {
+    for (&roll_sum, occurrence) in rolls.iter() {
         let mut new_game = game.clone();
-        new_game.players[game.current_player].r#move(*roll_sum);
+        new_game.players[game.current_player].r#move(roll_sum);
 
         if new_game.players[game.current_player].score >= until {
-            results[game.current_player] += u64::from(*occurrence);
+            results[game.current_player] += occurrence;
         } else {
             new_game.current_player = next_player;
 
-            // the borrow checker doesn't like this
-            // let cached = cache.entry(new_game).or_insert_with_key(|ng| play_quantum(cache, ng, until));
-
-            // do we have the value cached?
-            let cached = cache.contains_key(&new_game);
-
-            // calculate the value to insert
-            let to_insert = if cached {
-                None
-            } else {
-                Some(play_quantum(cache, &new_game, until))
-            };
-
-            let new_game_results = match to_insert {
-                None => &cache[&new_game],
-                Some(r) => cache.entry(new_game).or_insert(r),
-            };
+            let new_game_results = memo.get_or_insert_with(new_game, |memo, new_game| {
+                play_quantum(memo, new_game, until)
+            });
 
             new_game_results
                 .iter()
                 .enumerate()
-                .for_each(|(i, c)| results[i] += c * u64::from(*occurrence));
+                .for_each(|(i, c)| results[i] += c * occurrence);
         }
     }
 
@@ -186,24 +175,36 @@ fn play_quantum(cache: &mut HashMap<Game, Vec<u64>>, game: &Game, until: u32) ->
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_21/input.txt").lines().collect();
+    type Parsed = Vec<Player>;
 
-        let players = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let result = play(players, 1000);
+        Ok(parse_lines(&lines)?)
+    }
+
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let result = play(parsed.clone(), 1000);
 
-        PartSolution::U32(result.0 * result.1)
+        Ok(PartSolution::U32(result.0 * result.1))
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_21/input.txt").lines().collect();
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let mut memo = Memo::new();
 
-        let players = parse_lines(&lines);
+        let result = play_quantum(&mut memo, &Game::new(parsed.clone()), 21);
 
-        let result = play_quantum(&mut HashMap::new(), &Game::new(players), 21);
+        ::tracing::event!(::tracing::Level::DEBUG, "Games: {}", memo.stats());
 
-        PartSolution::U64(*result.iter().max().unwrap())
+        Ok(PartSolution::U64(*result.iter().max().unwrap()))
     }
 }
 
@@ -217,28 +218,54 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use super::get_example;
-        use crate::day_21::{Player, Solution, parse_lines, play};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_21::{INPUT, Player, Solution, parse_lines, play};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(900_099));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(900_099)
+            );
         }
 
         #[test]
         fn example_parse_input() {
             let example_lines = get_example();
 
-            let players = parse_lines(&example_lines);
+            let players = parse_lines(&example_lines).unwrap();
 
             assert_eq!(vec![Player::new(1, 4), Player::new(2, 8)], players);
         }
 
+        #[test]
+        fn start_off_the_board() {
+            assert_eq!(
+                "line 2, column 29: 4294967295 is not a place on the board of 1 to 10",
+                parse_lines(&[
+                    "Player 1 starting position: 4",
+                    "Player 2 starting position: 4294967295"
+                ])
+                .unwrap_err()
+                .to_string()
+            );
+            assert_eq!(
+                "line 1, column 29: 0 is not a place on the board of 1 to 10",
+                parse_lines(&["Player 1 starting position: 0"])
+                    .unwrap_err()
+                    .to_string()
+            );
+            assert_eq!(
+                vec![Player::new(1, 10)],
+                parse_lines(&["Player 1 starting position: 10"]).unwrap()
+            );
+        }
+
         #[test]
         fn example() {
             let example_lines = get_example();
 
-            let players = parse_lines(&example_lines);
+            let players = parse_lines(&example_lines).unwrap();
 
             let result = play(players, 1000);
 
@@ -247,17 +274,17 @@ mod test {
     }
 
     mod part_2 {
-        use hashbrown::HashMap;
         use pretty_assertions::assert_eq;
+        use shared::memo::Memo;
 
         use crate::day_21::test::get_example;
-        use crate::day_21::{Game, Solution, parse_lines, play_quantum};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_21::{Game, INPUT, Solution, parse_lines, play_quantum};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
             assert_eq!(
-                (Solution {}).part_2(),
+                solve(&Solution {}, 2, INPUT).unwrap(),
                 PartSolution::U64(306_719_685_234_774)
             );
         }
@@ -266,9 +293,9 @@ mod test {
         fn example() {
             let example_lines = get_example();
 
-            let players = parse_lines(&example_lines);
+            let players = parse_lines(&example_lines).unwrap();
 
-            let result = play_quantum(&mut HashMap::new(), &Game::new(players), 21);
+            let result = play_quantum(&mut Memo::new(), &Game::new(players), 21);
 
             println!("{:?}", result);
             assert_eq!(444_356_092_776_315, result[0]);
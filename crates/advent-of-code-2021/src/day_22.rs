@@ -1,61 +1,55 @@
-use regex::Regex;
+use shared::geom3::{self, Vec3};
+use shared::parse::{ParseError, RecordParser};
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone)]
-struct Point {
-    x: i32,
-    y: i32,
-    z: i32,
-}
+pub const INPUT: &str = include_str!("day_22/input.txt");
+
+/// The region a reboot step turns on or off, both ends included.
+pub type Cuboid = geom3::Cuboid;
 
+/// A reboot step.
 #[derive(Debug, PartialEq, Eq)]
-struct Instruction {
+pub struct Instruction {
     on: bool,
-    start: Point,
-    end: Point,
+    cuboid: Cuboid,
 }
 
-fn parse_lines(lines: &[&str]) -> Vec<Instruction> {
-    let mut instructions = Vec::new();
-
-    let regex =
-        Regex::new(r"(on|off) x=(-?\d+)\.\.(-?\d+),y=(-?\d+)\.\.(-?\d+),z=(-?\d+)\.\.(-?\d+)")
-            .unwrap();
-
-    for line in lines {
-        let captures = regex.captures(line.trim()).unwrap();
-
-        instructions.push(Instruction {
-            on: &captures[1] == "on",
-            start: Point {
-                x: captures[2].parse().unwrap(),
-                y: captures[4].parse().unwrap(),
-                z: captures[6].parse().unwrap(),
-            },
-            end: Point {
-                x: captures[3].parse().unwrap(),
-                y: captures[5].parse().unwrap(),
-                z: captures[7].parse().unwrap(),
-            },
-        });
+impl Instruction {
+    /// Whether the cubes in the region get turned on, rather than off.
+    #[must_use]
+    pub fn on(&self) -> bool {
+        self.on
     }
 
-    instructions
+    #[must_use]
+    pub fn cuboid(&self) -> &Cuboid {
+        &self.cuboid
+    }
+}
+
+fn parse_lines(lines: &[&str]) -> Result<Vec<Instruction>, ParseError> {
+    let parser = RecordParser::new(
+        r"^(on|off) x=(-?\d+)\.\.(-?\d+),y=(-?\d+)\.\.(-?\d+),z=(-?\d+)\.\.(-?\d+)$",
+        "on|off x=..,y=..,z=..",
+    );
+
+    parser.parse_all(lines, |record| {
+        Ok(Instruction {
+            on: record.text(1) == "on",
+            cuboid: Cuboid::new(
+                Vec3::new(record.get(2)?, record.get(4)?, record.get(6)?),
+                Vec3::new(record.get(3)?, record.get(5)?, record.get(7)?),
+            ),
+        })
+    })
 }
 
 fn get_on_cuboids(instructions: &[Instruction]) -> Vec<Cuboid> {
     let mut on_cuboids: Vec<Cuboid> = Vec::new();
 
     for instruction in instructions {
-        let cuboid = Cuboid::new(
-            instruction.start.x,
-            instruction.end.x,
-            instruction.start.y,
-            instruction.end.y,
-            instruction.start.z,
-            instruction.end.z,
-        );
+        let cuboid = instruction.cuboid;
 
         let mut touched_one = true;
 
@@ -89,19 +83,12 @@ fn get_on_cuboids(instructions: &[Instruction]) -> Vec<Cuboid> {
     on_cuboids
 }
 
-fn calculate_on_points_naive(instructions: &[Instruction], min: i32, max: i32) -> u64 {
+fn calculate_on_points_naive(instructions: &[Instruction], min: i64, max: i64) -> u64 {
+    let region = Cuboid::new(Vec3::splat(min), Vec3::splat(max));
+
     get_on_cuboids(instructions)
-        .into_iter()
-        .map(|cuboid| {
-            Cuboid::new(
-                cuboid.x_min.max(min),
-                cuboid.x_max.min(max),
-                cuboid.y_min.max(min),
-                cuboid.y_max.min(max),
-                cuboid.z_min.max(min),
-                cuboid.z_max.min(max),
-            )
-        })
+        .iter()
+        .filter_map(|cuboid| cuboid.intersection(&region))
         .map(|cuboid| cuboid.volume())
         .sum()
 }
@@ -113,131 +100,33 @@ fn calculate_on_points(instructions: &[Instruction]) -> u64 {
         .sum()
 }
 
-#[derive(PartialEq, Eq, Debug)]
-struct Cuboid {
-    x_min: i32,
-    x_max: i32,
-    y_min: i32,
-    y_max: i32,
-    z_min: i32,
-    z_max: i32,
-}
-
-impl Cuboid {
-    fn new(x_min: i32, x_max: i32, y_min: i32, y_max: i32, z_min: i32, z_max: i32) -> Self {
-        Self {
-            x_min,
-            x_max,
-            y_min,
-            y_max,
-            z_min,
-            z_max,
-        }
-    }
-
-    fn subtract(self, other: &Cuboid) -> Vec<Cuboid> {
-        if self.overlaps(other) {
-            [
-                Cuboid::new(
-                    self.x_min,
-                    other.x_min - 1,
-                    self.y_min,
-                    self.y_max,
-                    self.z_min,
-                    self.z_max,
-                ),
-                Cuboid::new(
-                    other.x_max + 1,
-                    self.x_max,
-                    self.y_min,
-                    self.y_max,
-                    self.z_min,
-                    self.z_max,
-                ),
-                Cuboid::new(
-                    self.x_min.max(other.x_min),
-                    self.x_max.min(other.x_max),
-                    self.y_min,
-                    other.y_min - 1,
-                    self.z_min,
-                    self.z_max,
-                ),
-                Cuboid::new(
-                    self.x_min.max(other.x_min),
-                    self.x_max.min(other.x_max),
-                    other.y_max + 1,
-                    self.y_max,
-                    self.z_min,
-                    self.z_max,
-                ),
-                Cuboid::new(
-                    self.x_min.max(other.x_min),
-                    self.x_max.min(other.x_max),
-                    self.y_min.max(other.y_min),
-                    self.y_max.min(other.y_max),
-                    self.z_min,
-                    other.z_min - 1,
-                ),
-                Cuboid::new(
-                    self.x_min.max(other.x_min),
-                    self.x_max.min(other.x_max),
-                    self.y_min.max(other.y_min),
-                    self.y_max.min(other.y_max),
-                    other.z_max + 1,
-                    self.z_max,
-                ),
-            ]
-            .into_iter()
-            .filter(|c| !c.is_empty())
-            .collect()
-        } else {
-            vec![self]
-        }
-    }
-
-    fn is_empty(&self) -> bool {
-        self.x_max < self.x_min || self.y_max < self.y_min || self.z_max < self.z_min
-    }
-
-    fn overlaps(&self, other: &Cuboid) -> bool {
-        !(other.x_min > self.x_max
-            || other.x_max < self.x_min
-            || other.y_min > self.y_max
-            || other.y_max < self.y_min
-            || other.z_min > self.z_max
-            || other.z_max < self.z_min)
-    }
-
-    fn volume(&self) -> u64 {
-        // max plus one as our ranges are inclusive
-        let x_dim = u64::try_from(self.x_max + 1 - self.x_min).unwrap_or_default();
-        let y_dim = u64::try_from(self.y_max + 1 - self.y_min).unwrap_or_default();
-        let z_dim = u64::try_from(self.z_max + 1 - self.z_min).unwrap_or_default();
-        x_dim * y_dim * z_dim
-    }
-}
-
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_22/input.txt").lines().collect();
+    type Parsed = Vec<Instruction>;
 
-        let instructions = parse_lines(&lines);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        let on_points = calculate_on_points_naive(&instructions, -50, 50);
-
-        PartSolution::U64(on_points)
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_22/input.txt").lines().collect();
-
-        let instructions = parse_lines(&lines);
-
-        let on_points = calculate_on_points(&instructions);
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U64(calculate_on_points_naive(
+            parsed, -50, 50,
+        )))
+    }
 
-        PartSolution::U64(on_points)
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::U64(calculate_on_points(parsed)))
     }
 }
 
@@ -257,72 +146,46 @@ mod test {
 
     mod part_1 {
         use pretty_assertions::assert_eq;
+        use shared::geom3::Vec3;
 
         use super::get_example;
         use crate::day_22::test::get_larger_example;
-        use crate::day_22::{Instruction, Point, Solution, calculate_on_points_naive, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_22::{
+            Cuboid, INPUT, Instruction, Solution, calculate_on_points_naive, parse_lines,
+        };
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U64(580_012));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U64(580_012)
+            );
         }
 
         #[test]
         fn example_parse_input() {
             let example_lines = get_example();
 
-            let reboot_steps = parse_lines(&example_lines);
+            let reboot_steps = parse_lines(&example_lines).unwrap();
 
             assert_eq!(
                 vec![
                     Instruction {
                         on: true,
-                        start: Point {
-                            x: 10,
-                            y: 10,
-                            z: 10,
-                        },
-                        end: Point {
-                            x: 12,
-                            y: 12,
-                            z: 12
-                        }
+                        cuboid: Cuboid::new(Vec3::new(10, 10, 10), Vec3::new(12, 12, 12)),
                     },
                     Instruction {
                         on: true,
-                        start: Point {
-                            x: 11,
-                            y: 11,
-                            z: 11,
-                        },
-                        end: Point {
-                            x: 13,
-                            y: 13,
-                            z: 13
-                        }
+                        cuboid: Cuboid::new(Vec3::new(11, 11, 11), Vec3::new(13, 13, 13)),
                     },
                     Instruction {
                         on: false,
-                        start: Point { x: 9, y: 9, z: 9 },
-                        end: Point {
-                            x: 11,
-                            y: 11,
-                            z: 11
-                        }
+                        cuboid: Cuboid::new(Vec3::new(9, 9, 9), Vec3::new(11, 11, 11)),
                     },
                     Instruction {
                         on: true,
-                        start: Point {
-                            x: 10,
-                            y: 10,
-                            z: 10,
-                        },
-                        end: Point {
-                            x: 10,
-                            y: 10,
-                            z: 10
-                        }
+                        cuboid: Cuboid::new(Vec3::new(10, 10, 10), Vec3::new(10, 10, 10)),
                     }
                 ],
                 reboot_steps
@@ -333,7 +196,7 @@ mod test {
         fn example() {
             let example_lines = get_example();
 
-            let instructions = parse_lines(&example_lines);
+            let instructions = parse_lines(&example_lines).unwrap();
 
             let on_points = calculate_on_points_naive(&instructions, -50, 50);
 
@@ -344,16 +207,7 @@ mod test {
         fn calculate_points() {
             let instructions = vec![Instruction {
                 on: true,
-                start: Point {
-                    x: 967,
-                    y: 45373,
-                    z: 27513,
-                },
-                end: Point {
-                    x: 23432,
-                    y: 81175,
-                    z: 53682,
-                },
+                cuboid: Cuboid::new(Vec3::new(967, 45373, 27513), Vec3::new(23432, 81175, 53682)),
             }];
             let on_points = calculate_on_points_naive(&instructions, -50, 50);
 
@@ -364,16 +218,7 @@ mod test {
         fn calculate_points_2() {
             let instructions = vec![Instruction {
                 on: true,
-                start: Point {
-                    x: -55,
-                    y: 1,
-                    z: 10,
-                },
-                end: Point {
-                    x: -45,
-                    y: 10,
-                    z: 10,
-                },
+                cuboid: Cuboid::new(Vec3::new(-55, 1, 10), Vec3::new(-45, 10, 10)),
             }];
 
             let on_points = calculate_on_points_naive(&instructions, -50, 50);
@@ -385,7 +230,7 @@ mod test {
         fn larger_example() {
             let example_lines = get_larger_example();
 
-            let instructions = parse_lines(&example_lines);
+            let instructions = parse_lines(&example_lines).unwrap();
 
             let on_points = calculate_on_points_naive(&instructions, -50, 50);
 
@@ -397,29 +242,22 @@ mod test {
         use pretty_assertions::assert_eq;
 
         use crate::day_22::test::get_example_part_2;
-        use crate::day_22::{Cuboid, Solution, calculate_on_points, parse_lines};
-        use crate::shared::{Day as _, PartSolution};
+        use crate::day_22::{INPUT, Solution, calculate_on_points, parse_lines};
+        use crate::shared::{PartSolution, solve};
 
         #[test]
         fn outcome() {
             assert_eq!(
-                (Solution {}).part_2(),
+                solve(&Solution {}, 2, INPUT).unwrap(),
                 PartSolution::U64(1_334_238_660_555_542)
             );
         }
 
-        #[test]
-        fn cuboid_volume() {
-            let cuboid = Cuboid::new(0, 5, 0, 5, 0, 5);
-
-            assert_eq!(216, cuboid.volume());
-        }
-
         #[test]
         fn example() {
             let example_lines = get_example_part_2();
 
-            let instructions = parse_lines(&example_lines);
+            let instructions = parse_lines(&example_lines).unwrap();
 
             let on_points = calculate_on_points(&instructions);
 
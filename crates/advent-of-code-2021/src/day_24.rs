@@ -1,10 +1,13 @@
 use std::cell::{Cell, RefCell};
-use std::error::Error;
 
-use crate::shared::{Day, PartSolution};
+use shared::parse::{ParseError, parse_token};
+
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+pub const INPUT: &str = include_str!("day_24/input.txt");
 
 #[derive(Hash, Eq, PartialEq)]
-enum Register {
+pub enum Register {
     W,
     X,
     Y,
@@ -21,7 +24,7 @@ impl std::fmt::Display for Register {
         }
     }
 }
-enum RegisterOrValue {
+pub enum RegisterOrValue {
     Register(Register),
     Value(i64),
 }
@@ -35,7 +38,7 @@ impl std::fmt::Display for RegisterOrValue {
     }
 }
 
-enum Instruction {
+pub enum Instruction {
     Input(Register),
     Add(Register, RegisterOrValue),
     Mul(Register, RegisterOrValue),
@@ -103,30 +106,45 @@ impl<'i> Alu<'i> {
         }
     }
 
-    fn process(&self) -> Result<i64, Box<dyn Error>> {
-        for ins in self.instructions {
-            // println!(
-            //     "w: {}, x: {}, y: {}, z: {}",
-            //     self.w.get(),
-            //     self.x.get(),
-            //     self.y.get(),
-            //     self.z.get()
-            // );
-            // println!("Processing {:?}", ins);
+    /// Runs the program, returning `z`.
+    ///
+    /// # Errors
+    ///
+    /// When the program reads more input than it got, divides by 0, or overflows.
+    fn process(&self) -> Result<i64, DayError> {
+        for (index, ins) in self.instructions.iter().enumerate() {
+            let failed = |what: &str| {
+                DayError::NoSolution(format!("`{:?}` at instruction {} {}", ins, index + 1, what))
+            };
+
             match *ins {
                 Instruction::Input(ref r) => {
-                    let pop = self.input.borrow_mut().pop().unwrap();
+                    let pop = self
+                        .input
+                        .borrow_mut()
+                        .pop()
+                        .ok_or_else(|| failed("has no input left"))?;
                     self.set_register(r, From::from(pop));
                 },
                 Instruction::Add(ref a, ref b) => {
                     let a_val = self.get_register(a);
                     let b_val = self.get_from_register_or_self(b);
-                    self.set_register(a, a_val + b_val);
+
+                    let result = a_val
+                        .checked_add(b_val)
+                        .ok_or_else(|| failed("overflows"))?;
+
+                    self.set_register(a, result);
                 },
                 Instruction::Mul(ref a, ref b) => {
                     let a_val = self.get_register(a);
                     let b_val = self.get_from_register_or_self(b);
-                    self.set_register(a, a_val * b_val);
+
+                    let result = a_val
+                        .checked_mul(b_val)
+                        .ok_or_else(|| failed("overflows"))?;
+
+                    self.set_register(a, result);
                 },
                 Instruction::Div(ref a, ref b) => {
                     let a_val = self.get_register(a);
@@ -134,7 +152,7 @@ impl<'i> Alu<'i> {
 
                     let result = a_val
                         .checked_div_euclid(b_val)
-                        .ok_or_else(|| "Division by 0".to_owned())?;
+                        .ok_or_else(|| failed("divides by 0"))?;
 
                     self.set_register(a, result);
                 },
@@ -144,7 +162,7 @@ impl<'i> Alu<'i> {
 
                     let result = a_val
                         .checked_rem_euclid(b_val)
-                        .ok_or_else(|| "Division by 0".to_owned())?;
+                        .ok_or_else(|| failed("divides by 0"))?;
 
                     self.set_register(a, result);
                 },
@@ -160,48 +178,75 @@ impl<'i> Alu<'i> {
     }
 }
 
-fn parse_lines(input: &[&str]) -> Vec<Instruction> {
+fn parse_register(line_index: usize, line: &str, token: &str) -> Result<Register, ParseError> {
+    match token {
+        "w" => Ok(Register::W),
+        "x" => Ok(Register::X),
+        "y" => Ok(Register::Y),
+        "z" => Ok(Register::Z),
+        _ => Err(ParseError::at_token(
+            line_index,
+            line,
+            token,
+            format!("`{}` is not a register", token),
+        )),
+    }
+}
+
+fn parse_lines(input: &[&str]) -> Result<Vec<Instruction>, ParseError> {
     let mut instructions = Vec::new();
-    for line in input {
+    for (line_index, &line) in input.iter().enumerate() {
         let split = line.split(' ').collect::<Vec<_>>();
 
-        let part2 = split.get(1).unwrap();
-
-        let param1 = match *part2 {
-            "w" => Register::W,
-            "x" => Register::X,
-            "y" => Register::Y,
-            "z" => Register::Z,
-            _ => unreachable!(),
+        let (operation, param1, param2) = match *split.as_slice() {
+            [operation, param1] => (operation, param1, None),
+            [operation, param1, param2] => (operation, param1, Some(param2)),
+            _ => {
+                return Err(ParseError::new(
+                    line_index,
+                    0,
+                    "expected an operation with one or two parameters".to_owned(),
+                ));
+            },
         };
 
-        let param2 = if let Some(part3) = split.get(2) {
-            let p2 = match *part3 {
-                "w" => RegisterOrValue::Register(Register::W),
-                "x" => RegisterOrValue::Register(Register::X),
-                "y" => RegisterOrValue::Register(Register::Y),
-                "z" => RegisterOrValue::Register(Register::Z),
-                x => RegisterOrValue::Value(x.parse::<i64>().unwrap()),
-            };
-            Some(p2)
-        } else {
-            None
+        let param1 = parse_register(line_index, line, param1)?;
+
+        let param2 = param2
+            .map(|part3| {
+                if let Ok(register) = parse_register(line_index, line, part3) {
+                    Ok(RegisterOrValue::Register(register))
+                } else {
+                    parse_token::<i64>(line_index, line, part3).map(RegisterOrValue::Value)
+                }
+            })
+            .transpose()?;
+
+        let instruction = match (operation, param2) {
+            ("inp", None) => Instruction::Input(param1),
+            ("add", Some(param2)) => Instruction::Add(param1, param2),
+            ("mul", Some(param2)) => Instruction::Mul(param1, param2),
+            ("div", Some(param2)) => Instruction::Div(param1, param2),
+            ("mod", Some(param2)) => Instruction::Mod(param1, param2),
+            ("eql", Some(param2)) => Instruction::Eql(param1, param2),
+            (_, _) => {
+                return Err(ParseError::at_token(
+                    line_index,
+                    line,
+                    operation,
+                    format!(
+                        "`{}` is not an operation with {} parameters",
+                        operation,
+                        split.len() - 1
+                    ),
+                ));
+            },
         };
 
-        let part1 = split.first().unwrap();
-
-        instructions.push(match *part1 {
-            "inp" => Instruction::Input(param1),
-            "add" => Instruction::Add(param1, param2.unwrap()),
-            "mul" => Instruction::Mul(param1, param2.unwrap()),
-            "div" => Instruction::Div(param1, param2.unwrap()),
-            "mod" => Instruction::Mod(param1, param2.unwrap()),
-            "eql" => Instruction::Eql(param1, param2.unwrap()),
-            _ => unreachable!(),
-        });
+        instructions.push(instruction);
     }
 
-    instructions
+    Ok(instructions)
 }
 
 fn number_to_vec(mut input: u64) -> Vec<u32> {
@@ -218,13 +263,31 @@ fn number_to_vec(mut input: u64) -> Vec<u32> {
     vec
 }
 
-fn find_maximum_version_number(instructions: &[Instruction]) -> u64 {
-    let mut v: u64 = 99_999_999_999_999;
+/// How many digits a model number has.
+const MODEL_NUMBER_DIGITS: usize = 14;
+
+/// `None` when no 14-digit model number is valid.
+fn find_maximum_version_number(
+    instructions: &[Instruction],
+    cancel: &CancellationToken,
+) -> Result<Option<u64>, DayError> {
+    // otherwise every number fails the same way, which takes forever to find out
+    let inputs = instructions
+        .iter()
+        .filter(|instruction| matches!(**instruction, Instruction::Input(_)))
+        .count();
+
+    if inputs != MODEL_NUMBER_DIGITS {
+        return Err(DayError::NoSolution(format!(
+            "the program reads {} digits, but a model number has {}",
+            inputs, MODEL_NUMBER_DIGITS
+        )));
+    }
 
-    loop {
-        let input = number_to_vec(v);
+    for v in (11_111_111_111_111..=99_999_999_999_999_u64).rev() {
+        cancel.check()?;
 
-        v -= 1;
+        let input = number_to_vec(v);
 
         if input.contains(&0) {
             continue;
@@ -235,13 +298,14 @@ fn find_maximum_version_number(instructions: &[Instruction]) -> u64 {
         if let Ok(n) = alu.process()
             && n == 0
         {
-            println!("{} is a valid number", v);
-            break;
+            ::tracing::event!(::tracing::Level::DEBUG, "{} is a valid number", v);
+            return Ok(Some(v));
         }
 
-        println!(
+        ::tracing::event!(
+            ::tracing::Level::TRACE,
             "{:?}: {}, {}, {}, {}",
-            v + 1,
+            v,
             alu.w.get(),
             alu.x.get(),
             alu.y.get(),
@@ -249,26 +313,37 @@ fn find_maximum_version_number(instructions: &[Instruction]) -> u64 {
         );
     }
 
-    v
+    Ok(None)
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_24/input.txt").lines().collect();
-
-        let instructions = parse_lines(&lines);
+    type Parsed = Vec<Instruction>;
 
-        let result = find_maximum_version_number(&instructions);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::U64(result)
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let _lines: Vec<&str> = include_str!("day_24/input.txt").lines().collect();
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let result = find_maximum_version_number(parsed, cancel)?
+            .ok_or_else(|| DayError::NoSolution("no model number is valid".to_owned()))?;
+
+        Ok(PartSolution::U64(result))
+    }
 
-        PartSolution::None
+    fn part_2(
+        &self,
+        _parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::None)
     }
 }
 
@@ -282,12 +357,27 @@ mod test {
     mod part_1 {
         use pretty_assertions::assert_eq;
 
+        use std::time::Duration;
+
         use super::get_example;
-        use crate::day_24::{Alu, number_to_vec, parse_lines};
+        use crate::day_24::{Alu, INPUT, Solution, number_to_vec, parse_lines};
+        use crate::shared::{CancellationToken, Day as _, DayError};
 
         #[test]
         fn outcome() {
-            // assert_eq!((Solution {}).part_1(), PartSolution::U32(329));
+            // assert_eq!(solve(&Solution {}, 1, INPUT).unwrap(), PartSolution::U32(329));
+        }
+
+        #[test]
+        fn times_out() {
+            let timeout = Duration::from_millis(10);
+
+            let instructions = (Solution {}).parse(INPUT).unwrap();
+
+            assert_eq!(
+                Err(DayError::TimedOut(timeout)),
+                (Solution {}).part_1(&instructions, &CancellationToken::with_timeout(timeout))
+            );
         }
 
         #[test]
@@ -299,7 +389,7 @@ mod test {
         fn example() {
             let example_lines = get_example();
 
-            let instructions = parse_lines(&example_lines);
+            let instructions = parse_lines(&example_lines).unwrap();
 
             let alu = Alu::new(&instructions, vec![12, 4]);
 
@@ -307,5 +397,50 @@ mod test {
 
             assert_eq!(1, result.unwrap());
         }
+
+        #[test]
+        fn runs_out_of_input() {
+            let instructions = parse_lines(&["inp w", "inp x"]).unwrap();
+
+            assert_eq!(
+                "No solution: `inp x` at instruction 2 has no input left",
+                Alu::new(&instructions, vec![5])
+                    .process()
+                    .unwrap_err()
+                    .to_string()
+            );
+
+            // all 14 digits are read before anything else
+            assert_eq!(
+                Err(DayError::NoSolution(
+                    "the program reads 2 digits, but a model number has 14".to_owned()
+                )),
+                (Solution {}).part_1(&instructions, &CancellationToken::never())
+            );
+        }
+
+        #[test]
+        fn overflow() {
+            let instructions =
+                parse_lines(&["add z 9223372036854775807", "mul z 2", "add z 1"]).unwrap();
+
+            assert_eq!(
+                "No solution: `mul z 2` at instruction 2 overflows",
+                Alu::new(&instructions, vec![])
+                    .process()
+                    .unwrap_err()
+                    .to_string()
+            );
+
+            let instructions = parse_lines(&["add z 9223372036854775807", "add z 1"]).unwrap();
+
+            assert_eq!(
+                "No solution: `add z 1` at instruction 2 overflows",
+                Alu::new(&instructions, vec![])
+                    .process()
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
     }
 }
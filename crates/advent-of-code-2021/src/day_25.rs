@@ -1,11 +1,15 @@
 use core::fmt;
 
-use hashbrown::HashSet;
+use shared::automaton::{Automaton, Edges, WINDOW_3X3};
+use shared::grid::Grid;
+use shared::parse::ParseError;
 
-use crate::shared::{Day, PartSolution};
+use crate::shared::{CancellationToken, Day, DayError, PartSolution};
 
-#[derive(PartialEq, Eq, Debug)]
-enum Cucumber {
+pub const INPUT: &str = include_str!("day_25/input.txt");
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Cucumber {
     East,
     South,
 }
@@ -19,156 +23,154 @@ impl fmt::Display for Cucumber {
     }
 }
 
-#[derive(PartialEq, Eq, Debug)]
-struct Board {
-    cucumbers: Vec<Vec<Option<Cucumber>>>,
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Board {
+    cucumbers: Grid<Option<Cucumber>>,
 }
 
-impl fmt::Display for Board {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in &self.cucumbers {
-            for maybe_cucumber in row {
-                match *maybe_cucumber {
-                    Some(ref c) => write!(f, "{}", c),
-                    None => write!(f, "."),
-                }?;
-            }
-
-            writeln!(f)?;
-        }
-        Ok(())
+impl From<&Board> for Grid<char> {
+    fn from(board: &Board) -> Self {
+        board.cucumbers.map(|maybe_cucumber| match *maybe_cucumber {
+            Some(Cucumber::East) => '>',
+            Some(Cucumber::South) => 'v',
+            None => '.',
+        })
     }
 }
 
-impl Board {
-    fn x_dim(&self) -> usize {
-        self.cucumbers.len()
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", Grid::from(self))
     }
+}
 
-    fn y_dim(&self) -> usize {
-        self.cucumbers.first().map(Vec::len).unwrap_or_default()
+/// What `here` holds after the herd `facing` one way moves, with `behind` and `ahead` the spots
+/// before and after it in that direction.
+fn move_herd(
+    facing: Cucumber,
+    behind: Option<Cucumber>,
+    here: Option<Cucumber>,
+    ahead: Option<Cucumber>,
+) -> Option<Cucumber> {
+    match here {
+        // the one behind moves in
+        None => (behind == Some(facing)).then_some(facing),
+        // we move out
+        Some(cucumber) => (cucumber != facing || ahead.is_some()).then_some(cucumber),
     }
+}
 
-    fn try_move(
-        &mut self,
-        x: usize,
-        y: usize,
-        invalid_targets: &HashSet<(usize, usize)>,
-    ) -> Option<(usize, usize)> {
-        let x_dim = self.x_dim();
-        let y_dim = self.y_dim();
-
-        let cucumber = self.cucumbers.get(x).and_then(|r| match r.get(y) {
-            Some(c) => c.as_ref(),
-            None => None,
-        });
-
-        match cucumber {
-            Some(&Cucumber::East) => {
-                let new_y = (y + 1) % y_dim;
-
-                if self.cucumbers[x][new_y].is_none() && !invalid_targets.contains(&(x, new_y)) {
-                    self.cucumbers[x][y] = None;
-                    self.cucumbers[x][new_y] = Some(Cucumber::East);
-
-                    Some((x, new_y))
-                } else {
-                    None
-                }
-            },
-            Some(&Cucumber::South) => {
-                let new_x = (x + 1) % x_dim;
-
-                if self.cucumbers[new_x][y].is_none() && !invalid_targets.contains(&(new_x, y)) {
-                    self.cucumbers[x][y] = None;
-                    self.cucumbers[new_x][y] = Some(Cucumber::South);
-
-                    Some((new_x, y))
-                } else {
-                    None
-                }
-            },
-            None => None,
-        }
-    }
+/// What a spot holds after a whole step. The east-facing herd moves first, so we work out where
+/// they end up in this row and the ones above and below, then let the south-facing herd move.
+fn step_spot(here: Option<Cucumber>, window: [&Option<Cucumber>; 9]) -> Option<Cucumber> {
+    let [
+        &north_west,
+        &north,
+        &north_east,
+        &west,
+        _,
+        &east,
+        &south_west,
+        &south,
+        &south_east,
+    ] = window;
+
+    let north = move_herd(Cucumber::East, north_west, north, north_east);
+    let center = move_herd(Cucumber::East, west, here, east);
+    let south = move_herd(Cucumber::East, south_west, south, south_east);
+
+    move_herd(Cucumber::South, north, center, south)
 }
 
-fn parse_lines(input: &[&str]) -> Board {
+fn parse_lines(input: &[&str]) -> Result<Board, ParseError> {
     let mut cucumbers = Vec::new();
 
-    for line in input {
+    for (line_index, line) in input.iter().enumerate() {
         let mut board_line = Vec::new();
 
-        for c in line.chars() {
+        for (column_index, c) in line.chars().enumerate() {
             board_line.push(match c {
                 'v' => Some(Cucumber::South),
                 '>' => Some(Cucumber::East),
                 '.' => None,
-                _ => unreachable!(),
+                _ => {
+                    return Err(ParseError::new(
+                        line_index,
+                        column_index,
+                        format!("`{}` is not a sea cucumber or an empty spot", c),
+                    ));
+                },
             });
         }
 
         cucumbers.push(board_line);
     }
 
-    Board { cucumbers }
-}
-
-fn move_cucumbers_in_direction(board: &mut Board, direction: Cucumber) -> bool {
-    let mut moved = HashSet::new();
-    let mut invalid = HashSet::new();
-
-    let expected = Some(direction);
-
-    for x in 0..board.x_dim() {
-        for y in 0..board.y_dim() {
-            if !moved.contains(&(x, y))
-                && board.cucumbers[x][y] == expected
-                && let Some(r) = board.try_move(x, y, &invalid)
-            {
-                invalid.insert((x, y));
-                moved.insert(r);
-            }
-        }
-    }
-
-    !moved.is_empty()
+    Ok(Board {
+        cucumbers: Grid::from_rows(cucumbers)?,
+    })
 }
 
-fn move_cucumbers(board: &mut Board) -> u32 {
-    println!("Initial state: \n{}", board);
-    let mut step = 0;
-    loop {
-        step += 1;
-        let moved_east = move_cucumbers_in_direction(board, Cucumber::East);
-        let moved_south = move_cucumbers_in_direction(board, Cucumber::South);
-
-        println!("After step {}:\n{}", step, board);
-        if !moved_east && !moved_south {
-            break;
-        }
-    }
-
-    step
+/// Moves the cucumbers until they're stuck, returning the first step where none moved.
+fn move_cucumbers(board: &mut Board, cancel: &CancellationToken) -> Result<u32, DayError> {
+    ::tracing::event!(::tracing::Level::TRACE, "Initial state: \n{}", board);
+
+    let mut automaton = Automaton::new(board.cucumbers.clone(), WINDOW_3X3, Edges::Wrap);
+
+    // some boards never settle, so only the timeout stops those
+    let steps = automaton.run_until_stable(
+        u64::MAX,
+        |&here, window| step_spot(here, window),
+        |automaton| {
+            ::tracing::event!(
+                ::tracing::Level::TRACE,
+                "After step {}:\n{}",
+                automaton.generation(),
+                Grid::from(&Board {
+                    cucumbers: automaton.cells().clone()
+                })
+            );
+
+            cancel.check()
+        },
+    )?;
+
+    board.cucumbers = automaton.into_cells();
+
+    steps
+        .and_then(|steps| u32::try_from(steps).ok())
+        .ok_or_else(|| DayError::NoSolution("the cucumbers never stop moving".into()))
 }
 
 pub struct Solution {}
 
 impl Day for Solution {
-    fn part_1(&self) -> PartSolution {
-        let lines: Vec<&str> = include_str!("day_25/input.txt").lines().collect();
-
-        let mut board = parse_lines(&lines);
+    type Parsed = Board;
 
-        let steps_taken = move_cucumbers(&mut board);
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+        let lines: Vec<&str> = input.lines().collect();
 
-        PartSolution::U32(steps_taken)
+        Ok(parse_lines(&lines)?)
     }
 
-    fn part_2(&self) -> PartSolution {
-        let _lines: Vec<&str> = include_str!("day_25/input.txt").lines().collect();
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let mut board = parsed.clone();
 
-        PartSolution::None
+        let steps_taken = move_cucumbers(&mut board, cancel)?;
+
+        Ok(PartSolution::U32(steps_taken))
+    }
+
+    fn part_2(
+        &self,
+        _parsed: &Self::Parsed,
+        _cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        Ok(PartSolution::None)
     }
 }
 
@@ -183,27 +185,34 @@ mod test {
     }
 
     mod part_1 {
+        use std::time::Duration;
+
         use pretty_assertions::assert_eq;
+        use shared::automaton::{Automaton, Edges, WINDOW_3X3};
+        use shared::grid::Grid;
 
         use super::get_example;
         use crate::day_25::test::get_smaller_example;
         use crate::day_25::{
-            Board, Cucumber, Solution, move_cucumbers, move_cucumbers_in_direction, parse_lines,
+            Board, Cucumber, INPUT, Solution, move_cucumbers, parse_lines, step_spot,
         };
-        use crate::shared::{Day as _, PartSolution};
+        use crate::shared::{CancellationToken, Day as _, DayError, PartSolution, solve};
 
         #[test]
         fn outcome() {
-            assert_eq!((Solution {}).part_1(), PartSolution::U32(329));
+            assert_eq!(
+                solve(&Solution {}, 1, INPUT).unwrap(),
+                PartSolution::U32(329)
+            );
         }
         #[test]
         fn parse_test() {
             let input = "...>>>>>...";
 
-            let parsed = parse_lines(&[input]);
+            let parsed = parse_lines(&[input]).unwrap();
 
             let expected = Board {
-                cucumbers: vec![vec![
+                cucumbers: Grid::from_rows::<()>(vec![vec![
                     None,
                     None,
                     None,
@@ -215,7 +224,8 @@ mod test {
                     None,
                     None,
                     None,
-                ]],
+                ]])
+                .unwrap(),
             };
 
             assert_eq!(expected, parsed);
@@ -225,37 +235,39 @@ mod test {
         fn smaller_example() {
             let example_lines = get_smaller_example();
 
-            let mut board = parse_lines(&example_lines);
+            let board = parse_lines(&example_lines).unwrap();
 
-            move_cucumbers_in_direction(&mut board, Cucumber::East);
-            move_cucumbers_in_direction(&mut board, Cucumber::South);
+            let mut automaton = Automaton::new(board.cucumbers, WINDOW_3X3, Edges::Wrap);
 
-            move_cucumbers_in_direction(&mut board, Cucumber::East);
-            move_cucumbers_in_direction(&mut board, Cucumber::South);
+            automaton
+                .run(
+                    4,
+                    |&here, window| step_spot(here, window),
+                    |_| Ok::<_, ()>(()),
+                )
+                .unwrap();
 
-            move_cucumbers_in_direction(&mut board, Cucumber::East);
-            move_cucumbers_in_direction(&mut board, Cucumber::South);
-
-            move_cucumbers_in_direction(&mut board, Cucumber::East);
-            move_cucumbers_in_direction(&mut board, Cucumber::South);
+            let board = Board {
+                cucumbers: automaton.into_cells(),
+            };
 
             let expected = [
                 ">......", "..v....", "..>.v..", ".>.v...", "...>...", ".......", "v......",
             ];
 
-            let parsed_expected = parse_lines(&expected);
+            let parsed_expected = parse_lines(&expected).unwrap();
             println!("{}", parsed_expected);
             println!("{}", board);
-            assert_eq!(parse_lines(&expected), board);
+            assert_eq!(parse_lines(&expected).unwrap(), board);
         }
 
         #[test]
         fn example() {
             let example_lines = get_example();
 
-            let mut board = parse_lines(&example_lines);
+            let mut board = parse_lines(&example_lines).unwrap();
 
-            move_cucumbers(&mut board);
+            move_cucumbers(&mut board, &CancellationToken::never()).unwrap();
 
             let expected = [
                 "..>>v>vv..",
@@ -269,18 +281,31 @@ mod test {
                 ".>v.vv.v..",
             ];
 
-            assert_eq!(parse_lines(&expected), board);
+            assert_eq!(parse_lines(&expected).unwrap(), board);
         }
 
         #[test]
         fn example_after_steps() {
             let example_lines = get_example();
 
-            let mut board = parse_lines(&example_lines);
+            let mut board = parse_lines(&example_lines).unwrap();
 
-            let steps_taken = move_cucumbers(&mut board);
+            let steps_taken = move_cucumbers(&mut board, &CancellationToken::never()).unwrap();
 
             assert_eq!(58, steps_taken);
         }
+
+        #[test]
+        fn times_out() {
+            let timeout = Duration::from_millis(10);
+
+            // the cucumber goes round and round
+            let board = parse_lines(&[">."]).unwrap();
+
+            assert_eq!(
+                Err(DayError::TimedOut(timeout)),
+                (Solution {}).part_1(&board, &CancellationToken::with_timeout(timeout))
+            );
+        }
     }
 }
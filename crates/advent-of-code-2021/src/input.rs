@@ -0,0 +1,45 @@
+use std::borrow::Cow;
+use std::io::{self, Read as _};
+use std::path::PathBuf;
+use std::{fmt, fs};
+
+use color_eyre::eyre::{self, WrapErr as _};
+
+/// Where a day's puzzle input is read from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputSource {
+    /// The input compiled into the binary.
+    Embedded,
+    File(PathBuf),
+    Stdin,
+}
+
+impl InputSource {
+    pub fn read(&self, embedded_input: &'static str) -> Result<Cow<'static, str>, eyre::Report> {
+        match *self {
+            InputSource::Embedded => Ok(Cow::Borrowed(embedded_input)),
+            InputSource::File(ref path) => fs::read_to_string(path)
+                .map(Cow::Owned)
+                .wrap_err_with(|| format!("Failed to read input from `{}`", path.display())),
+            InputSource::Stdin => {
+                let mut input = String::new();
+
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .wrap_err("Failed to read input from stdin")?;
+
+                Ok(Cow::Owned(input))
+            },
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            InputSource::Embedded => write!(f, "embedded"),
+            InputSource::File(ref path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "stdin"),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+//! The solutions to Advent of Code 2021, one module per day.
+//!
+//! Every day has a `Solution` implementing `shared::Day`, and `INPUT`, the puzzle input it was
+//! solved with. `registry::get_solutions` has them all, keyed by day.
+#![expect(clippy::missing_assert_message, reason = "Non-production code")]
+#![expect(clippy::too_many_lines, reason = "Non-production code")]
+
+pub mod pool;
+pub mod registry;
+pub mod shared;
+
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_09;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+pub mod day_17;
+pub mod day_18;
+pub mod day_19;
+pub mod day_20;
+pub mod day_21;
+pub mod day_22;
+// pub mod day_23;
+pub mod day_24;
+pub mod day_25;
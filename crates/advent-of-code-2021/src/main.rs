@@ -1,80 +1,155 @@
-#![expect(clippy::cast_possible_truncation, reason = "Non-production code")]
-#![expect(clippy::missing_assert_message, reason = "Non-production code")]
-#![expect(clippy::too_many_lines, reason = "Non-production code")]
+use std::time::Instant;
 
-use shared::Day;
+use advent_of_code_2021::pool;
+use advent_of_code_2021::registry::{self, Registry};
+use advent_of_code_2021::shared::{CancellationToken, DayError};
+use clap::Parser as _;
+use cli::{Cli, Command};
+use color_eyre::eyre;
+use input::InputSource;
+use output::PartOutcome;
+use shared::memo;
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::filter::LevelFilter;
 
-mod shared;
+mod bench;
+mod build_env;
+mod cli;
+mod input;
+mod output;
 mod utils;
-
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod day_09;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_17;
-mod day_18;
-mod day_19;
-// mod day_20;
-mod day_21;
-mod day_22;
-// mod day_23;
-mod day_24;
-mod day_25;
+mod verify;
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-fn print_answer(day: u32, part: u32, result: &str) {
-    println!("Answer to Day {}, part {} is ... {}", day, part, result);
+/// Solves the selected days and parts on `cli.jobs` threads, and prints the answers in day order.
+/// Parts that time out are reported, but don't fail the run.
+fn solve(
+    cli: &Cli,
+    solutions: &Registry,
+    days: &[u32],
+    input_sources: &[InputSource],
+) -> Result<(), eyre::Report> {
+    let inputs = days
+        .iter()
+        .zip(input_sources)
+        .map(|(day, input_source)| input_source.read(solutions[day].embedded_input))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let parsed_inputs = registry::parse_inputs(
+        cli.jobs,
+        solutions,
+        &days
+            .iter()
+            .zip(&inputs)
+            .map(|(&day, input)| (day, &**input))
+            .collect::<Vec<_>>(),
+    );
+
+    let tasks = days
+        .iter()
+        .zip(input_sources)
+        .zip(&parsed_inputs)
+        .flat_map(|((&day, input_source), parsed_input)| {
+            [1, 2]
+                .into_iter()
+                .filter(|&part| cli.runs_part(part))
+                .map(move |part| (day, part, input_source, parsed_input))
+        })
+        .collect::<Vec<_>>();
+
+    let mut failures = 0;
+    let mut timeouts = 0;
+
+    pool::run_ordered(
+        cli.jobs,
+        &tasks,
+        |&(day, part, input_source, parsed_input)| {
+            let cancel = CancellationToken::with_timeout(cli.timeout);
+            let start = Instant::now();
+
+            // parts run one at a time on a thread, so what its memos did since is this part
+            memo::reset_stats();
+
+            let result = parsed_input.solve(&*solutions[&day].solution, part, &cancel);
+
+            PartOutcome {
+                day,
+                part,
+                result,
+                parse_elapsed: parsed_input.elapsed,
+                elapsed: start.elapsed(),
+                memo: memo::take_stats(),
+                input_source,
+            }
+        },
+        |outcome| {
+            // a timeout is printed like any failure, but only means we gave up waiting
+            match outcome.result {
+                Err(DayError::TimedOut(_)) => timeouts += 1,
+                Err(_) => failures += 1,
+                Ok(_) => (),
+            }
+
+            cli.output.print(&outcome)
+        },
+    )?;
+
+    if timeouts > 0 {
+        eprintln!(
+            "{} part(s) timed out, `--timeout 0` lets them run to the end",
+            timeouts
+        );
+    }
+
+    if failures > 0 {
+        return Err(eyre::eyre!("{} part(s) failed", failures));
+    }
+
+    Ok(())
+}
+
+/// Sends the solvers' logging to stderr, so stdout only has the answers.
+///
+/// `verbosity` sets the level for everything, `RUST_LOG` directives are applied on top of it.
+fn init_tracing(verbosity: u8) {
+    let level = match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+
+    let filter = EnvFilter::builder()
+        .with_default_directive(level.into())
+        .from_env_lossy();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
 }
 
 fn main() -> Result<(), color_eyre::Report> {
     color_eyre::install()?;
 
-    let solutions: Vec<Box<dyn Day>> = vec![
-        Box::new(day_01::Solution {}),
-        Box::new(day_02::Solution {}),
-        Box::new(day_03::Solution {}),
-        Box::new(day_04::Solution {}),
-        Box::new(day_05::Solution {}),
-        Box::new(day_06::Solution {}),
-        Box::new(day_07::Solution {}),
-        Box::new(day_08::Solution {}),
-        Box::new(day_09::Solution {}),
-        Box::new(day_10::Solution {}),
-        Box::new(day_11::Solution {}),
-        Box::new(day_12::Solution {}),
-        Box::new(day_13::Solution {}),
-        Box::new(day_14::Solution {}),
-        Box::new(day_15::Solution {}),
-        Box::new(day_16::Solution {}),
-        Box::new(day_17::Solution {}),
-        Box::new(day_18::Solution {}),
-        Box::new(day_19::Solution {}),
-        // Box::new(day_20::Solution {}),
-        Box::new(day_21::Solution {}),
-        Box::new(day_22::Solution {}),
-        // Box::new(day_23::Solution {}),
-        Box::new(day_24::Solution {}),
-        Box::new(day_25::Solution {}),
-    ];
-
-    for (day, solution) in (1_u32..).zip(solutions) {
-        print_answer(day, 1, &solution.part_1().to_string());
-        print_answer(day, 2, &solution.part_2().to_string());
-    }
+    let cli = Cli::parse();
 
-    Ok(())
+    init_tracing(cli.verbose);
+
+    let solutions = registry::get_solutions();
+
+    let days = cli.resolve_days(&solutions.keys().copied().collect::<Vec<_>>())?;
+
+    let input_sources = cli.input_sources(&days)?;
+
+    match cli.command {
+        Some(Command::Bench(ref args)) => bench::run(&cli, args, &solutions, &days, &input_sources),
+        Some(Command::Verify(ref args)) => {
+            let manifest = verify::Manifest::load(&args.manifest)?;
+
+            verify::run(&cli, &manifest, &solutions, &days, &input_sources)
+        },
+        None => solve(&cli, &solutions, &days, &input_sources),
+    }
 }
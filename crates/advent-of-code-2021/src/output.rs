@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{Number, Value as JsonValue};
+use shared::memo::Stats;
+
+use crate::input::InputSource;
+use advent_of_code_2021::shared::{DayError, PartSolution};
+
+/// How the answers are written to stdout.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// A sentence per answer, failures go to stderr.
+    #[default]
+    Text,
+    /// One JSON record per day and part, one per line (NDJSON).
+    Json,
+}
+
+/// The outcome of running a single part of a day.
+pub struct PartOutcome<'s> {
+    pub day: u32,
+    pub part: u32,
+    pub result: Result<PartSolution, DayError>,
+    /// How long parsing the day's input took, the same for both parts.
+    pub parse_elapsed: Duration,
+    /// How long the part took, without parsing.
+    pub elapsed: Duration,
+    /// What the part's memos did, empty when it used none.
+    pub memo: Stats,
+    pub input_source: &'s InputSource,
+}
+
+/// What a line of `--output json` looks like.
+#[derive(Serialize)]
+struct Record {
+    day: u32,
+    part: u32,
+    /// The `PartSolution` variant, `null` when the part failed.
+    r#type: Option<&'static str>,
+    /// An array of rows for `Vec` and `Grid`, `null` for `None` or when the part failed.
+    value: JsonValue,
+    error: Option<String>,
+    parse_ns: u128,
+    elapsed_ns: u128,
+    /// Both 0 when the part used no memo.
+    memo_hits: u64,
+    memo_misses: u64,
+    input: String,
+}
+
+impl Record {
+    fn new(outcome: &PartOutcome<'_>) -> Self {
+        let (r#type, value, error) = match outcome.result {
+            Ok(ref solution) => (Some(solution.variant_name()), to_json_value(solution), None),
+            Err(ref error) => (None, JsonValue::Null, Some(error.to_string())),
+        };
+
+        Self {
+            day: outcome.day,
+            part: outcome.part,
+            r#type,
+            value,
+            error,
+            parse_ns: outcome.parse_elapsed.as_nanos(),
+            elapsed_ns: outcome.elapsed.as_nanos(),
+            memo_hits: outcome.memo.hits,
+            memo_misses: outcome.memo.misses,
+            input: outcome.input_source.to_string(),
+        }
+    }
+}
+
+fn to_json_value(solution: &PartSolution) -> JsonValue {
+    match *solution {
+        PartSolution::I32(x) => JsonValue::from(x),
+        PartSolution::I64(x) => JsonValue::from(x),
+        PartSolution::U32(x) => JsonValue::from(x),
+        PartSolution::U64(x) => JsonValue::from(x),
+        // JSON numbers only go up to `u64::MAX` here, past that it's a string
+        PartSolution::U128(x) => {
+            Number::from_u128(x).map_or_else(|| JsonValue::from(x.to_string()), JsonValue::Number)
+        },
+        PartSolution::USize(x) => JsonValue::from(x),
+        PartSolution::String(ref x) => JsonValue::from(x.clone()),
+        PartSolution::Vec(ref x) => JsonValue::from(x.clone()),
+        PartSolution::Grid(ref x) => JsonValue::from(x.lines()),
+        PartSolution::None => JsonValue::Null,
+    }
+}
+
+impl OutputFormat {
+    pub fn print(self, outcome: &PartOutcome<'_>) -> Result<(), serde_json::Error> {
+        match self {
+            OutputFormat::Text => match outcome.result {
+                Ok(ref solution) if outcome.memo.is_empty() => println!(
+                    "Answer to Day {}, part {} is ... {}",
+                    outcome.day, outcome.part, solution
+                ),
+                Ok(ref solution) => println!(
+                    "Answer to Day {}, part {} is ... {} (memo: {})",
+                    outcome.day, outcome.part, solution, outcome.memo
+                ),
+                Err(ref error) => {
+                    eprintln!(
+                        "Day {}, part {} failed: {}",
+                        outcome.day, outcome.part, error
+                    );
+                },
+            },
+            OutputFormat::Json => println!("{}", serde_json::to_string(&Record::new(outcome))?),
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use shared::grid::Grid;
+    use shared::memo::Stats;
+
+    use crate::input::InputSource;
+    use crate::output::{PartOutcome, Record};
+    use advent_of_code_2021::shared::{DayError, PartSolution};
+
+    fn to_json(result: Result<PartSolution, DayError>, input_source: &InputSource) -> String {
+        let outcome = PartOutcome {
+            day: 13,
+            part: 2,
+            result,
+            parse_elapsed: Duration::from_micros(250),
+            elapsed: Duration::from_micros(1500),
+            memo: Stats::default(),
+            input_source,
+        };
+
+        serde_json::to_string(&Record::new(&outcome)).unwrap()
+    }
+
+    #[test]
+    fn number() {
+        assert_eq!(
+            r#"{"day":13,"part":2,"type":"U64","value":42,"error":null,"parse_ns":250000,"elapsed_ns":1500000,"memo_hits":0,"memo_misses":0,"input":"embedded"}"#,
+            to_json(Ok(PartSolution::U64(42)), &InputSource::Embedded)
+        );
+    }
+
+    #[test]
+    fn vec_is_an_array() {
+        assert_eq!(
+            r##"{"day":13,"part":2,"type":"Vec","value":["#.#",".#."],"error":null,"parse_ns":250000,"elapsed_ns":1500000,"memo_hits":0,"memo_misses":0,"input":"inputs/day_13.txt"}"##,
+            to_json(
+                Ok(PartSolution::Vec(vec!["#.#".to_owned(), ".#.".to_owned()])),
+                &InputSource::File(PathBuf::from("inputs/day_13.txt"))
+            )
+        );
+    }
+
+    #[test]
+    fn grid_is_an_array_of_rows() {
+        assert_eq!(
+            r##"{"day":13,"part":2,"type":"Grid","value":["#.#",".#."],"error":null,"parse_ns":250000,"elapsed_ns":1500000,"memo_hits":0,"memo_misses":0,"input":"embedded"}"##,
+            to_json(
+                Ok(PartSolution::Grid(
+                    Grid::parse("#.#\n.#.", |_, c| Ok::<_, ()>(c)).unwrap()
+                )),
+                &InputSource::Embedded
+            )
+        );
+    }
+
+    #[test]
+    fn u128_past_u64_is_a_string() {
+        assert_eq!(
+            r#"{"day":13,"part":2,"type":"U128","value":"18446744073709551616","error":null,"parse_ns":250000,"elapsed_ns":1500000,"memo_hits":0,"memo_misses":0,"input":"embedded"}"#,
+            to_json(
+                Ok(PartSolution::U128(u128::from(u64::MAX) + 1)),
+                &InputSource::Embedded
+            )
+        );
+        assert_eq!(
+            r#"{"day":13,"part":2,"type":"I64","value":-42,"error":null,"parse_ns":250000,"elapsed_ns":1500000,"memo_hits":0,"memo_misses":0,"input":"embedded"}"#,
+            to_json(Ok(PartSolution::I64(-42)), &InputSource::Embedded)
+        );
+    }
+
+    #[test]
+    fn none_is_null() {
+        assert_eq!(
+            r#"{"day":13,"part":2,"type":"None","value":null,"error":null,"parse_ns":250000,"elapsed_ns":1500000,"memo_hits":0,"memo_misses":0,"input":"stdin"}"#,
+            to_json(Ok(PartSolution::None), &InputSource::Stdin)
+        );
+    }
+
+    #[test]
+    fn failure_has_error() {
+        assert_eq!(
+            r#"{"day":13,"part":2,"type":null,"value":null,"error":"No solution: there are no folds","parse_ns":250000,"elapsed_ns":1500000,"memo_hits":0,"memo_misses":0,"input":"embedded"}"#,
+            to_json(
+                Err(DayError::NoSolution("there are no folds".to_owned())),
+                &InputSource::Embedded
+            )
+        );
+    }
+
+    #[test]
+    fn memo_stats() {
+        let outcome = PartOutcome {
+            day: 12,
+            part: 1,
+            result: Ok(PartSolution::USize(10)),
+            parse_elapsed: Duration::from_micros(250),
+            elapsed: Duration::from_micros(1500),
+            memo: Stats {
+                hits: 48,
+                misses: 50,
+            },
+            input_source: &InputSource::Embedded,
+        };
+
+        assert_eq!(
+            r#"{"day":12,"part":1,"type":"USize","value":10,"error":null,"parse_ns":250000,"elapsed_ns":1500000,"memo_hits":48,"memo_misses":50,"input":"embedded"}"#,
+            serde_json::to_string(&Record::new(&outcome)).unwrap()
+        );
+    }
+}
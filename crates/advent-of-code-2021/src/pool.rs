@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+/// Runs `work` on each of the `tasks` on up to `jobs` threads, and hands the results to `emit`
+/// in the order of `tasks`, each one as soon as it and everything before it is done.
+///
+/// `emit` runs on the calling thread. When it fails, no new tasks are started and its error is
+/// returned once the running ones finish.
+///
+/// A panic in `work` or `emit` is not caught: it ends the whole run, and is raised again here
+/// once the other threads finish. Work that can panic, like a day's parts, should catch it
+/// itself, as `registry::ParsedInput::solve` does.
+///
+/// # Errors
+///
+/// The first error `emit` returns.
+pub fn run_ordered<T, R, W, E, X>(
+    jobs: NonZeroUsize,
+    tasks: &[T],
+    work: W,
+    mut emit: E,
+) -> Result<(), X>
+where
+    T: Sync,
+    R: Send,
+    W: Fn(&T) -> R + Sync,
+    E: FnMut(R) -> Result<(), X>,
+{
+    let next_task = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        let (next_task, stop, work) = (&next_task, &stop, &work);
+
+        for _ in 0..jobs.get().min(tasks.len()) {
+            let sender = sender.clone();
+
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let index = next_task.fetch_add(1, Ordering::Relaxed);
+
+                    let Some(task) = tasks.get(index) else {
+                        break;
+                    };
+
+                    if sender.send((index, work(task))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        // Otherwise the receiver below never sees the end of the results.
+        drop(sender);
+
+        let mut finished = BTreeMap::new();
+        let mut next_to_emit = 0;
+
+        for (index, result) in receiver {
+            finished.insert(index, result);
+
+            while let Some(result) = finished.remove(&next_to_emit) {
+                next_to_emit += 1;
+
+                if let Err(error) = emit(result) {
+                    stop.store(true, Ordering::Relaxed);
+
+                    return Err(error);
+                }
+            }
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::num::NonZeroUsize;
+    use std::thread;
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::pool::run_ordered;
+
+    fn jobs(jobs: usize) -> NonZeroUsize {
+        NonZeroUsize::new(jobs).unwrap()
+    }
+
+    #[test]
+    fn emits_in_task_order() {
+        // the early tasks take longest, so they finish last
+        let tasks = (0..8_u64).collect::<Vec<_>>();
+
+        let mut emitted = Vec::new();
+
+        run_ordered(
+            jobs(4),
+            &tasks,
+            |&task| {
+                thread::sleep(Duration::from_millis(8 - task));
+
+                task * 10
+            },
+            |result| {
+                emitted.push(result);
+
+                Ok::<_, ()>(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(vec![0, 10, 20, 30, 40, 50, 60, 70], emitted);
+    }
+
+    #[test]
+    fn more_jobs_than_tasks() {
+        let mut emitted = Vec::new();
+
+        run_ordered(
+            jobs(16),
+            &[1, 2],
+            |&task| task,
+            |result| {
+                emitted.push(result);
+
+                Ok::<_, ()>(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(vec![1, 2], emitted);
+    }
+
+    #[test]
+    fn emit_error_stops() {
+        let tasks = (0..100).collect::<Vec<_>>();
+
+        let mut emitted = Vec::new();
+
+        let result = run_ordered(
+            jobs(1),
+            &tasks,
+            |&task| task,
+            |result| {
+                if result == 3 {
+                    return Err("three");
+                }
+
+                emitted.push(result);
+
+                Ok(())
+            },
+        );
+
+        assert_eq!(Err("three"), result);
+        assert_eq!(vec![0, 1, 2], emitted);
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+
+use crate::pool;
+use crate::shared::{
+    AnyDay, AnyParsed, CancellationToken, Day, DayError, PartSolution, catch_panic,
+};
+use crate::{
+    day_01, day_02, day_03, day_04, day_05, day_06, day_07, day_08, day_09, day_10, day_11, day_12,
+    day_13, day_14, day_15, day_16, day_17, day_18, day_19, day_20, day_21, day_22, day_24, day_25,
+};
+
+pub struct Entry {
+    pub solution: Box<dyn AnyDay>,
+    /// The puzzle input compiled into the binary, used when no other input is given.
+    pub embedded_input: &'static str,
+}
+
+impl Entry {
+    fn new<D: Day + 'static>(solution: D, embedded_input: &'static str) -> Self {
+        Self {
+            solution: Box::new(solution),
+            embedded_input,
+        }
+    }
+}
+
+pub type Registry = BTreeMap<u32, Entry>;
+
+/// A day's input after `AnyDay::parse`, shared by both of its parts.
+pub struct ParsedInput {
+    pub result: Result<AnyParsed, DayError>,
+    pub elapsed: Duration,
+}
+
+impl ParsedInput {
+    /// Runs `part` on the parsed input, or fails it with the error parsing gave.
+    ///
+    /// # Errors
+    ///
+    /// When parsing failed, or the part did. A panicking part fails with
+    /// `DayError::Panicked`, so the parts running next to it carry on.
+    pub fn solve(
+        &self,
+        solution: &dyn AnyDay,
+        part: u32,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        match self.result {
+            Ok(ref parsed) => catch_panic(|| solution.part(part, parsed, cancel)),
+            Err(ref error) => Err(error.clone()),
+        }
+    }
+}
+
+/// Parses the input of every `(day, input)` on `jobs` threads, in the same order. A panicking
+/// parser fails that input with `DayError::Panicked`.
+#[must_use]
+pub fn parse_inputs(
+    jobs: NonZeroUsize,
+    solutions: &Registry,
+    inputs: &[(u32, &str)],
+) -> Vec<ParsedInput> {
+    let mut parsed_inputs = Vec::with_capacity(inputs.len());
+
+    let Ok(()) = pool::run_ordered(
+        jobs,
+        inputs,
+        |&(day, input)| {
+            let start = Instant::now();
+
+            let result = catch_panic(|| solutions[&day].solution.parse(input));
+
+            let elapsed = start.elapsed();
+
+            ::tracing::event!(
+                ::tracing::Level::DEBUG,
+                "Parsed the input of day {} in {:?}",
+                day,
+                elapsed
+            );
+
+            ParsedInput { result, elapsed }
+        },
+        |parsed_input| {
+            parsed_inputs.push(parsed_input);
+
+            Ok::<_, Infallible>(())
+        },
+    );
+
+    parsed_inputs
+}
+
+/// All solutions, keyed by the day of the puzzle they solve.
+///
+/// Days without a (working) solution are simply absent, so nothing after them shifts.
+#[must_use]
+pub fn get_solutions() -> Registry {
+    let mut solutions: Registry = BTreeMap::new();
+
+    solutions.insert(1, Entry::new(day_01::Solution {}, day_01::INPUT));
+    solutions.insert(2, Entry::new(day_02::Solution {}, day_02::INPUT));
+    solutions.insert(3, Entry::new(day_03::Solution {}, day_03::INPUT));
+    solutions.insert(4, Entry::new(day_04::Solution {}, day_04::INPUT));
+    solutions.insert(5, Entry::new(day_05::Solution {}, day_05::INPUT));
+    solutions.insert(6, Entry::new(day_06::Solution {}, day_06::INPUT));
+    solutions.insert(7, Entry::new(day_07::Solution {}, day_07::INPUT));
+    solutions.insert(8, Entry::new(day_08::Solution {}, day_08::INPUT));
+    solutions.insert(9, Entry::new(day_09::Solution {}, day_09::INPUT));
+    solutions.insert(10, Entry::new(day_10::Solution {}, day_10::INPUT));
+    solutions.insert(11, Entry::new(day_11::Solution {}, day_11::INPUT));
+    solutions.insert(12, Entry::new(day_12::Solution {}, day_12::INPUT));
+    solutions.insert(13, Entry::new(day_13::Solution {}, day_13::INPUT));
+    solutions.insert(14, Entry::new(day_14::Solution {}, day_14::INPUT));
+    solutions.insert(15, Entry::new(day_15::Solution {}, day_15::INPUT));
+    solutions.insert(16, Entry::new(day_16::Solution {}, day_16::INPUT));
+    solutions.insert(17, Entry::new(day_17::Solution {}, day_17::INPUT));
+    solutions.insert(18, Entry::new(day_18::Solution {}, day_18::INPUT));
+    solutions.insert(19, Entry::new(day_19::Solution {}, day_19::INPUT));
+    solutions.insert(20, Entry::new(day_20::Solution {}, day_20::INPUT));
+    solutions.insert(21, Entry::new(day_21::Solution {}, day_21::INPUT));
+    solutions.insert(22, Entry::new(day_22::Solution {}, day_22::INPUT));
+    // solutions.insert(23, Entry::new(day_23::Solution {}, day_23::INPUT));
+    solutions.insert(24, Entry::new(day_24::Solution {}, day_24::INPUT));
+    solutions.insert(25, Entry::new(day_25::Solution {}, day_25::INPUT));
+
+    solutions
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use std::num::NonZeroUsize;
+
+    use crate::day_20;
+    use crate::registry::{Entry, Registry, get_solutions, parse_inputs};
+    use crate::shared::{CancellationToken, Day, DayError, PartSolution};
+
+    /// Parses anything but `panic`, and panics in part 2.
+    struct Fragile {}
+
+    #[expect(clippy::panic_in_result_fn, reason = "Panicking is what we test")]
+    impl Day for Fragile {
+        type Parsed = ();
+
+        fn parse(&self, input: &str) -> Result<Self::Parsed, DayError> {
+            assert!(input != "panic", "can't parse `{}`", input);
+
+            Ok(())
+        }
+
+        fn part_1(
+            &self,
+            _parsed: &Self::Parsed,
+            _cancel: &CancellationToken,
+        ) -> Result<PartSolution, DayError> {
+            Ok(PartSolution::U32(1))
+        }
+
+        fn part_2(
+            &self,
+            _parsed: &Self::Parsed,
+            _cancel: &CancellationToken,
+        ) -> Result<PartSolution, DayError> {
+            panic!("part 2 is broken");
+        }
+    }
+
+    #[test]
+    fn keyed_by_puzzle_day() {
+        let days = get_solutions().into_keys().collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![
+                1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 24,
+                25
+            ],
+            days
+        );
+    }
+
+    #[test]
+    fn parse_errors_fail_both_parts() {
+        let solutions = get_solutions();
+
+        let parsed_inputs = parse_inputs(
+            NonZeroUsize::new(2).unwrap(),
+            &solutions,
+            &[(1, "199\nx\n"), (1, "199\n200\n")],
+        );
+
+        let solution = &*solutions[&1].solution;
+
+        for part in [1, 2] {
+            assert_eq!(
+                "Invalid input: line 2, column 1: could not parse `x`: invalid digit found in string",
+                parsed_inputs[0]
+                    .solve(solution, part, &CancellationToken::never())
+                    .unwrap_err()
+                    .to_string()
+            );
+        }
+
+        assert_eq!(
+            Ok(PartSolution::U64(1)),
+            parsed_inputs[1].solve(solution, 1, &CancellationToken::never())
+        );
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        let solutions = get_solutions();
+
+        // only day 20's algorithm, without the picture
+        let truncated = day_20::INPUT.lines().next().unwrap();
+
+        let parsed_inputs = parse_inputs(
+            NonZeroUsize::new(1).unwrap(),
+            &solutions,
+            &[(20, truncated)],
+        );
+
+        assert_eq!(
+            "Invalid input: line 2, column 1: expected at least 2 lines",
+            parsed_inputs[0]
+                .solve(&*solutions[&20].solution, 1, &CancellationToken::never())
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn panics_only_fail_their_part() {
+        let mut solutions = Registry::new();
+
+        solutions.insert(1, Entry::new(Fragile {}, ""));
+
+        let parsed_inputs = parse_inputs(
+            NonZeroUsize::new(2).unwrap(),
+            &solutions,
+            &[(1, "panic"), (1, "fine")],
+        );
+
+        let solution = &*solutions[&1].solution;
+
+        assert_eq!(
+            "Panicked: can't parse `panic`",
+            parsed_inputs[0]
+                .solve(solution, 1, &CancellationToken::never())
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            Ok(PartSolution::U32(1)),
+            parsed_inputs[1].solve(solution, 1, &CancellationToken::never())
+        );
+        assert_eq!(
+            Err(DayError::Panicked("part 2 is broken".to_owned())),
+            parsed_inputs[1].solve(solution, 2, &CancellationToken::never())
+        );
+    }
+}
@@ -1,29 +1,330 @@
-pub trait Day {
-    fn part_1(&self) -> PartSolution;
-    fn part_2(&self) -> PartSolution;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+use shared::grid::Grid;
+use shared::parse::ParseError;
+
+/// A day's solution. It is shared between the threads of `--jobs`, so any state a part needs has
+/// to live inside that call.
+///
+/// The input is parsed once, and both parts get the same `Parsed` data.
+pub trait Day: Send + Sync {
+    /// Parts may run at the same time, so they get it by reference and have to clone what they
+    /// want to change.
+    type Parsed: Send + Sync + 'static;
+
+    /// # Errors
+    ///
+    /// When `input` isn't what the puzzle describes.
+    fn parse(&self, input: &str) -> Result<Self::Parsed, DayError>;
+    /// Long-running parts should `cancel.check()?` in their loops.
+    ///
+    /// # Errors
+    ///
+    /// When there is no answer, or `cancel` ran out.
+    fn part_1(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError>;
+    /// Like `part_1`.
+    ///
+    /// # Errors
+    ///
+    /// When there is no answer, or `cancel` ran out.
+    fn part_2(
+        &self,
+        parsed: &Self::Parsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError>;
+}
+
+/// The `Parsed` data of some `Day`.
+pub type AnyParsed = Box<dyn Any + Send + Sync>;
+
+/// A `Day` with its `Parsed` type erased, so all days fit in one registry.
+pub trait AnyDay: Send + Sync {
+    /// # Errors
+    ///
+    /// Like `Day::parse`.
+    fn parse(&self, input: &str) -> Result<AnyParsed, DayError>;
+
+    /// `parsed` has to come from `parse` of the same day.
+    ///
+    /// # Errors
+    ///
+    /// Like `Day::part_1` and `Day::part_2`.
+    ///
+    /// # Panics
+    ///
+    /// When `parsed` came from another day.
+    fn part(
+        &self,
+        part: u32,
+        parsed: &AnyParsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError>;
+}
+
+impl<D: Day> AnyDay for D {
+    fn parse(&self, input: &str) -> Result<AnyParsed, DayError> {
+        Ok(Box::new(Day::parse(self, input)?))
+    }
+
+    #[expect(
+        clippy::panic_in_result_fn,
+        reason = "Mixing up days is a bug in the runner, not in the input"
+    )]
+    fn part(
+        &self,
+        part: u32,
+        parsed: &AnyParsed,
+        cancel: &CancellationToken,
+    ) -> Result<PartSolution, DayError> {
+        let Some(parsed) = parsed.downcast_ref::<D::Parsed>() else {
+            panic!("Parsed data of another day");
+        };
+
+        if part == 1 {
+            self.part_1(parsed, cancel)
+        } else {
+            self.part_2(parsed, cancel)
+        }
+    }
+}
+
+/// Parses `input` and solves `part` of it without a time limit, like the runner would.
+///
+/// # Errors
+///
+/// When `input` doesn't parse, or the part has no answer for it.
+pub fn solve<D: Day>(day: &D, part: u32, input: &str) -> Result<PartSolution, DayError> {
+    AnyDay::part(
+        day,
+        part,
+        &AnyDay::parse(day, input)?,
+        &CancellationToken::never(),
+    )
+}
+
+/// Tells a part when it has run out of time.
+#[derive(Clone, Copy, Debug)]
+pub struct CancellationToken {
+    timeout: Duration,
+    /// `None` when there is no limit.
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    /// A token that never cancels.
+    #[must_use]
+    pub fn never() -> Self {
+        Self {
+            timeout: Duration::ZERO,
+            deadline: None,
+        }
+    }
+
+    /// Cancels once `timeout` has passed from now. A zero `timeout` means no limit.
+    #[must_use]
+    pub fn with_timeout(timeout: Duration) -> Self {
+        if timeout.is_zero() {
+            return Self::never();
+        }
+
+        Self {
+            timeout,
+            deadline: Instant::now().checked_add(timeout),
+        }
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// `Err(DayError::TimedOut)` once cancelled, so a loop can bail out with `?`.
+    ///
+    /// # Errors
+    ///
+    /// Once cancelled.
+    pub fn check(&self) -> Result<(), DayError> {
+        if self.is_cancelled() {
+            return Err(DayError::TimedOut(self.timeout));
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum PartSolution {
     I32(i32),
+    I64(i64),
     U32(u32),
     U64(u64),
+    U128(u128),
     USize(usize),
+    String(String),
     Vec(Vec<String>),
+    /// A picture, e.g. the letters day 13 draws with `#` and `.`.
+    Grid(Grid<char>),
     None,
 }
 
+impl PartSolution {
+    /// The name of the variant, e.g. `U64`.
+    #[must_use]
+    pub fn variant_name(&self) -> &'static str {
+        match *self {
+            PartSolution::I32(_) => "I32",
+            PartSolution::I64(_) => "I64",
+            PartSolution::U32(_) => "U32",
+            PartSolution::U64(_) => "U64",
+            PartSolution::U128(_) => "U128",
+            PartSolution::USize(_) => "USize",
+            PartSolution::String(_) => "String",
+            PartSolution::Vec(_) => "Vec",
+            PartSolution::Grid(_) => "Grid",
+            PartSolution::None => "None",
+        }
+    }
+}
+
 impl std::fmt::Display for PartSolution {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let string = match *self {
             PartSolution::I32(x) => x.to_string(),
+            PartSolution::I64(x) => x.to_string(),
             PartSolution::U32(x) => x.to_string(),
             PartSolution::U64(x) => x.to_string(),
+            PartSolution::U128(x) => x.to_string(),
             PartSolution::USize(x) => x.to_string(),
+            PartSolution::String(ref x) => x.clone(),
             PartSolution::Vec(ref x) => format!("\n{}", x.join("\n")),
+            PartSolution::Grid(ref x) => format!("\n{}", x),
             PartSolution::None => "None".to_owned(),
         };
 
         write!(f, "{}", string)
     }
 }
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum DayError {
+    Parse(ParseError),
+    /// The input is well-formed, but doesn't have an answer.
+    NoSolution(String),
+    /// The part ran out of time, the `Duration` is the limit it had.
+    TimedOut(Duration),
+    /// Parsing or the part panicked, with the panic message.
+    Panicked(String),
+}
+
+impl std::fmt::Display for DayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            DayError::Parse(ref parse_error) => write!(f, "Invalid input: {}", parse_error),
+            DayError::NoSolution(ref reason) => write!(f, "No solution: {}", reason),
+            DayError::TimedOut(timeout) => write!(f, "Timed out after {:?}", timeout),
+            DayError::Panicked(ref message) => write!(f, "Panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DayError {}
+
+/// Runs `f`, turning a panic into `DayError::Panicked`, so a bug in one day doesn't take down the
+/// others running next to it.
+///
+/// # Errors
+///
+/// When `f` fails or panics.
+pub fn catch_panic<T, F>(f: F) -> Result<T, DayError>
+where
+    F: FnOnce() -> Result<T, DayError>,
+{
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        // `panic!` gives a `&str` or a `String`, anything else is rare enough to not describe
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|&message| message.to_owned())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "no message".to_owned());
+
+        Err(DayError::Panicked(message))
+    })
+}
+
+impl From<ParseError> for DayError {
+    fn from(parse_error: ParseError) -> Self {
+        DayError::Parse(parse_error)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use shared::grid::Grid;
+
+    use crate::shared::{CancellationToken, DayError, PartSolution, catch_panic};
+
+    #[test]
+    fn part_solution_display() {
+        assert_eq!("-42", PartSolution::I64(-42).to_string());
+        assert_eq!(
+            "340282366920938463463374607431768211455",
+            PartSolution::U128(u128::MAX).to_string()
+        );
+        assert_eq!("FGHJ", PartSolution::String("FGHJ".to_owned()).to_string());
+        assert_eq!(
+            "\n##\n##",
+            PartSolution::Grid(Grid::new(2, 2, '#')).to_string()
+        );
+    }
+
+    #[test]
+    fn cancellation_token_times_out() {
+        assert_eq!(Ok(()), CancellationToken::never().check());
+        assert_eq!(
+            Ok(()),
+            CancellationToken::with_timeout(Duration::ZERO).check()
+        );
+        assert_eq!(
+            Ok(()),
+            CancellationToken::with_timeout(Duration::from_secs(3600)).check()
+        );
+
+        let cancel = CancellationToken::with_timeout(Duration::from_nanos(1));
+
+        std::thread::sleep(Duration::from_millis(1));
+
+        assert_eq!(
+            Err(DayError::TimedOut(Duration::from_nanos(1))),
+            cancel.check()
+        );
+        assert_eq!(
+            "Timed out after 1ns",
+            cancel.check().unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn panics_are_errors() {
+        assert_eq!(Ok(3), catch_panic(|| Ok(3)));
+        assert_eq!(
+            Err(DayError::Panicked("out of cheese".to_owned())),
+            catch_panic::<(), _>(|| panic!("out of cheese"))
+        );
+        assert_eq!(
+            "Panicked: 7 is too many",
+            catch_panic::<(), _>(|| panic!("{} is too many", 7))
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
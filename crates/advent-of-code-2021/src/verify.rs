@@ -0,0 +1,349 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use color_eyre::eyre::{self, WrapErr as _};
+use serde::Deserialize;
+use serde_json::from_str as from_json_str;
+
+use crate::cli::Cli;
+use crate::input::InputSource;
+use advent_of_code_2021::pool;
+use advent_of_code_2021::registry::{self, Registry};
+use advent_of_code_2021::shared::{CancellationToken, PartSolution};
+
+/// An answer as written in the manifest.
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(untagged)]
+enum Expected {
+    Signed(i64),
+    /// Only reachable from JSON, TOML integers are at most `i64::MAX`.
+    Unsigned(u64),
+    Text(String),
+    /// One string per line, for answers like day 13's.
+    Lines(Vec<String>),
+}
+
+impl Expected {
+    fn render(&self) -> String {
+        match *self {
+            Expected::Signed(x) => x.to_string(),
+            Expected::Unsigned(x) => x.to_string(),
+            Expected::Text(ref x) => x.clone(),
+            Expected::Lines(ref x) => x.join("\n"),
+        }
+    }
+}
+
+/// How an answer is compared against what's in the manifest.
+fn render(solution: &PartSolution) -> String {
+    match *solution {
+        PartSolution::Vec(ref x) => x.join("\n"),
+        PartSolution::Grid(ref x) => x.to_string(),
+        PartSolution::I32(_)
+        | PartSolution::I64(_)
+        | PartSolution::U32(_)
+        | PartSolution::U64(_)
+        | PartSolution::U128(_)
+        | PartSolution::USize(_)
+        | PartSolution::String(_)
+        | PartSolution::None => solution.to_string(),
+    }
+}
+
+/// The expected answers for one set of inputs, keyed by day and part.
+///
+/// In TOML:
+///
+/// ```toml
+/// [day_01]
+/// part_1 = 1722
+/// part_2 = 1748
+/// ```
+///
+/// or the same structure in JSON. `1` works as well as `day_01` or `part_1`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Manifest(BTreeMap<(u32, u32), Expected>);
+
+type RawManifest = BTreeMap<String, BTreeMap<String, Expected>>;
+
+impl Manifest {
+    /// Loads a `.toml` or `.json` manifest.
+    pub fn load(path: &Path) -> Result<Self, eyre::Report> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read `{}`", path.display()))?;
+
+        let raw = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => toml::from_str::<RawManifest>(&contents)?,
+            Some("json") => from_json_str::<RawManifest>(&contents)?,
+            Some(_) | None => {
+                return Err(eyre::eyre!(
+                    "`{}` should end in `.toml` or `.json`",
+                    path.display()
+                ));
+            },
+        };
+
+        Self::from_raw(raw).wrap_err_with(|| format!("Invalid manifest `{}`", path.display()))
+    }
+
+    fn from_raw(raw: RawManifest) -> Result<Self, eyre::Report> {
+        let mut expected = BTreeMap::new();
+
+        for (day_key, parts) in raw {
+            let day = parse_key(&day_key, "day_")?;
+
+            for (part_key, answer) in parts {
+                let part = parse_key(&part_key, "part_")?;
+
+                if !(1..=2).contains(&part) {
+                    return Err(eyre::eyre!("`{}` is not part 1 or 2", part_key));
+                }
+
+                expected.insert((day, part), answer);
+            }
+        }
+
+        Ok(Self(expected))
+    }
+}
+
+fn parse_key(key: &str, prefix: &str) -> Result<u32, eyre::Report> {
+    key.strip_prefix(prefix)
+        .unwrap_or(key)
+        .parse::<u32>()
+        .wrap_err_with(|| {
+            format!(
+                "`{}` is not a valid key, expected `{}N` or `N`",
+                key, prefix
+            )
+        })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Error(String),
+}
+
+/// Keeps a table cell on a single line.
+fn cell(value: &str) -> String {
+    match value.lines().count() {
+        0 | 1 => value.to_owned(),
+        lines => format!("({} lines)", lines),
+    }
+}
+
+/// Solves every selected day and part that has an expected answer in `manifest` on `cli.jobs`
+/// threads, and prints a pass / fail table. Any mismatch or failure is an error.
+pub fn run(
+    cli: &Cli,
+    manifest: &Manifest,
+    solutions: &Registry,
+    days: &[u32],
+    input_sources: &[InputSource],
+) -> Result<(), eyre::Report> {
+    let mut days_to_parse = Vec::new();
+    let mut parts_per_day = Vec::new();
+
+    for (&day, input_source) in days.iter().zip(input_sources) {
+        let parts = [1, 2]
+            .into_iter()
+            .filter(|&part| cli.runs_part(part))
+            .filter_map(|part| Some((part, manifest.0.get(&(day, part))?)))
+            .collect::<Vec<_>>();
+
+        if parts.is_empty() {
+            continue;
+        }
+
+        days_to_parse.push((day, input_source.read(solutions[&day].embedded_input)?));
+        parts_per_day.push(parts);
+    }
+
+    let parsed_inputs = registry::parse_inputs(
+        cli.jobs,
+        solutions,
+        &days_to_parse
+            .iter()
+            .map(|&(day, ref input)| (day, &**input))
+            .collect::<Vec<_>>(),
+    );
+
+    let tasks = days_to_parse
+        .iter()
+        .zip(&parsed_inputs)
+        .zip(parts_per_day)
+        .flat_map(|((&(day, _), parsed_input), parts)| {
+            parts
+                .into_iter()
+                .map(move |(part, expected)| (day, part, expected, parsed_input))
+        })
+        .collect::<Vec<_>>();
+
+    let mut verdicts = Vec::new();
+
+    pool::run_ordered(
+        cli.jobs,
+        &tasks,
+        |&(day, part, expected, parsed_input)| {
+            let cancel = CancellationToken::with_timeout(cli.timeout);
+
+            let result = parsed_input.solve(&*solutions[&day].solution, part, &cancel);
+
+            let verdict = match result {
+                Ok(ref solution) if render(solution) == expected.render() => Verdict::Pass,
+                Ok(ref solution) => Verdict::Fail {
+                    expected: expected.render(),
+                    actual: render(solution),
+                },
+                Err(ref error) => Verdict::Error(error.to_string()),
+            };
+
+            (day, part, verdict)
+        },
+        |verdict| {
+            verdicts.push(verdict);
+
+            Ok::<_, eyre::Report>(())
+        },
+    )?;
+
+    println!(
+        "{:>3}  {:>4}  {:<6}  {:<20}  {:<20}",
+        "Day", "Part", "Status", "Expected", "Actual"
+    );
+
+    for &(day, part, ref verdict) in &verdicts {
+        let (status, expected, actual) = match *verdict {
+            Verdict::Pass => ("pass", String::new(), String::new()),
+            Verdict::Fail {
+                ref expected,
+                ref actual,
+            } => ("FAIL", cell(expected), cell(actual)),
+            Verdict::Error(ref error) => ("ERROR", String::new(), error.clone()),
+        };
+
+        println!(
+            "{:>3}  {:>4}  {:<6}  {:<20}  {:<20}",
+            day, part, status, expected, actual
+        );
+    }
+
+    let failed = verdicts
+        .iter()
+        .filter(|&&(_, _, ref verdict)| *verdict != Verdict::Pass)
+        .count();
+
+    for &(day, part, ref verdict) in &verdicts {
+        if let Verdict::Fail {
+            ref expected,
+            ref actual,
+        } = *verdict
+            && (expected.contains('\n') || actual.contains('\n'))
+        {
+            println!();
+            println!("Day {}, part {} expected:\n{}", day, part, expected);
+            println!("but got:\n{}", actual);
+        }
+    }
+
+    println!();
+    println!("{} passed, {} failed", verdicts.len() - failed, failed);
+
+    if failed > 0 {
+        return Err(eyre::eyre!(
+            "{} of {} answers did not match",
+            failed,
+            verdicts.len()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use pretty_assertions::assert_eq;
+    use serde_json::from_str as from_json_str;
+
+    use crate::verify::{Expected, Manifest, RawManifest, render};
+    use advent_of_code_2021::shared::PartSolution;
+
+    fn from_toml(contents: &str) -> Manifest {
+        Manifest::from_raw(toml::from_str::<RawManifest>(contents).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn toml_and_json_agree() {
+        let toml = from_toml(
+            r##"
+            [day_01]
+            part_1 = 1722
+
+            [13]
+            2 = ["#.", ".#"]
+            "##,
+        );
+
+        let json = Manifest::from_raw(
+            from_json_str::<RawManifest>(
+                r##"{ "1": { "part_1": 1722 }, "day_13": { "part_2": ["#.", ".#"] } }"##,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            Manifest(BTreeMap::from([
+                ((1, 1), Expected::Signed(1722)),
+                (
+                    (13, 2),
+                    Expected::Lines(vec!["#.".to_owned(), ".#".to_owned()])
+                ),
+            ])),
+            toml
+        );
+        assert_eq!(toml, json);
+    }
+
+    #[test]
+    fn rejects_bad_keys() {
+        let parse = |contents| {
+            Manifest::from_raw(toml::from_str::<RawManifest>(contents).unwrap())
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!(
+            "`day_one` is not a valid key, expected `day_N` or `N`",
+            parse("[day_one]\npart_1 = 1")
+        );
+        assert_eq!("`part_3` is not part 1 or 2", parse("[day_01]\npart_3 = 1"));
+    }
+
+    #[test]
+    fn compares_rendered_answers() {
+        assert_eq!(
+            render(&PartSolution::U64(1_767_323_539_209)),
+            Expected::Signed(1_767_323_539_209).render()
+        );
+        assert_eq!(
+            render(&PartSolution::U32(42)),
+            Expected::Text("42".to_owned()).render()
+        );
+        assert_eq!(
+            render(&PartSolution::Vec(vec!["#.".to_owned(), ".#".to_owned()])),
+            Expected::Lines(vec!["#.".to_owned(), ".#".to_owned()]).render()
+        );
+    }
+
+    #[test]
+    fn embedded_answers_parse() {
+        let manifest = from_toml(include_str!("../answers.toml"));
+
+        assert_eq!(Some(&Expected::Signed(1722)), manifest.0.get(&(1, 1)));
+    }
+}
@@ -1,16 +1,149 @@
 #![expect(clippy::tests_outside_test_module, reason = "Integration tests")]
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use advent_of_code_2021::registry::{get_solutions, parse_inputs};
+use advent_of_code_2021::shared::{CancellationToken, Day as _, DayError, PartSolution, solve};
+use advent_of_code_2021::{day_01, day_16, day_18, day_22, day_24};
 use pretty_assertions::assert_eq;
 
 #[test]
-fn assert_world_ok() {
-    let cls1 = || true;
-    let cls2 = || true;
-    assert_eq!(cls1(), cls2());
+fn every_day_but_23_is_registered() {
+    let solutions = get_solutions();
+
+    assert_eq!(
+        (1..=25).filter(|&day| day != 23).collect::<Vec<_>>(),
+        solutions.keys().copied().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn registry_solves_embedded_inputs() {
+    let solutions = get_solutions();
+
+    let inputs = [1, 2].map(|day| (day, solutions[&day].embedded_input));
+
+    let parsed_inputs = parse_inputs(NonZeroUsize::MIN, &solutions, &inputs);
+
+    let answers = inputs
+        .iter()
+        .zip(&parsed_inputs)
+        .flat_map(|(&(day, _), parsed_input)| {
+            [1, 2].map(|part| {
+                parsed_input
+                    .solve(
+                        &*solutions[&day].solution,
+                        part,
+                        &CancellationToken::never(),
+                    )
+                    .unwrap()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            PartSolution::U64(1722),
+            PartSolution::U64(1748),
+            PartSolution::I64(2_070_300),
+            PartSolution::I64(2_078_985_210),
+        ],
+        answers
+    );
+}
+
+#[test]
+fn solve_from_outside() {
+    assert_eq!(
+        Ok(PartSolution::U64(1722)),
+        solve(&day_01::Solution {}, 1, day_01::INPUT)
+    );
+    assert_eq!(
+        Ok(PartSolution::U32(31)),
+        solve(&day_16::Solution {}, 1, "A0016C880162017C3686B18A3D4780")
+    );
+    assert_eq!(
+        Ok(PartSolution::U64(1)),
+        solve(&day_16::Solution {}, 2, "9C0141080250320F1802104A08")
+    );
 }
 
 #[test]
-fn assert_world_ok2() {
-    let cls1 = || false;
-    let cls2 = || false;
-    assert_eq!(cls1(), cls2());
+fn invalid_input_is_an_error() {
+    let DayError::Parse(_) = solve(&day_16::Solution {}, 1, "8A00ZZ").unwrap_err() else {
+        panic!("Expected a parse error");
+    };
+}
+
+#[test]
+fn packet_tree() {
+    // an operator with a literal 1 and a literal 2 inside, comparing them
+    let packet = day_16::Solution {}.parse("D8005AC2A8F0").unwrap();
+
+    assert_eq!(6, packet.version());
+    assert_eq!(2, packet.sub_packets().len());
+    assert_eq!(
+        vec![0, 0],
+        packet
+            .sub_packets()
+            .iter()
+            .map(|sub_packet| sub_packet.sub_packets().len())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(1, packet.value());
+    assert_eq!(
+        u32::from(packet.version())
+            + packet
+                .sub_packets()
+                .iter()
+                .map(|sub_packet| u32::from(sub_packet.version()))
+                .sum::<u32>(),
+        packet.version_sum()
+    );
+}
+
+#[test]
+fn snailfish_magnitude() {
+    let numbers = day_18::Solution {}
+        .parse("[[1,2],[[3,4],5]]\n[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")
+        .unwrap();
+
+    assert_eq!(
+        vec![143, 1384],
+        numbers
+            .iter()
+            .map(day_18::Snailfish::magnitude)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn reboot_cuboids() {
+    let instructions = day_22::Solution {}
+        .parse("on x=10..12,y=10..12,z=10..12\noff x=-1..1,y=0..0,z=5..6")
+        .unwrap();
+
+    assert_eq!(
+        vec![(true, 27), (false, 6)],
+        instructions
+            .iter()
+            .map(|instruction| (instruction.on(), instruction.cuboid().volume()))
+            .collect::<Vec<_>>()
+    );
+
+    let cuboid: &day_22::Cuboid = instructions[0].cuboid();
+
+    assert!(!cuboid.overlaps(instructions[1].cuboid()));
+}
+
+#[test]
+fn cancellation_from_outside() {
+    let timeout = Duration::from_millis(10);
+
+    let instructions = day_24::Solution {}.parse(day_24::INPUT).unwrap();
+
+    assert_eq!(
+        Err(DayError::TimedOut(timeout)),
+        day_24::Solution {}.part_1(&instructions, &CancellationToken::with_timeout(timeout))
+    );
 }
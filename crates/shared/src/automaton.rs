@@ -0,0 +1,454 @@
+use std::hash::{DefaultHasher, Hash, Hasher as _};
+use std::mem;
+
+use hashbrown::HashMap;
+
+use crate::grid::{Coordinates, Grid};
+
+/// The cell itself and the 8 around it, row after row, as `(row, column)` offsets.
+pub const WINDOW_3X3: [(isize, isize); 9] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 0),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// What a cell sees past the edge of the grid.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Edges<T> {
+    /// The opposite edge.
+    Wrap,
+    /// Cells that are always this value.
+    Fixed(T),
+    /// An endless plane of this value, which follows the rule like any other cell. The grid grows
+    /// every step by how far the neighborhood reaches, as that's how far changes can spread.
+    Infinite(T),
+}
+
+/// Where a cycle starts, and how many steps it takes to come back there.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Cycle {
+    pub start: u64,
+    pub length: u64,
+}
+
+/// A grid where every step, every cell gets a new value from its old value and those of its
+/// neighbors, all at once.
+///
+/// The neighborhood is a list of `N` offsets, and the rule gets the neighbors in that order. The
+/// new values go into a second grid, which is swapped in when the step is done.
+#[derive(Clone, Debug)]
+pub struct Automaton<T, const N: usize> {
+    cells: Grid<T>,
+    buffer: Grid<T>,
+    neighborhood: [(isize, isize); N],
+    edges: Edges<T>,
+    generation: u64,
+}
+
+impl<T: Clone + PartialEq, const N: usize> Automaton<T, N> {
+    #[must_use]
+    pub fn new(cells: Grid<T>, neighborhood: [(isize, isize); N], edges: Edges<T>) -> Self {
+        Self {
+            buffer: cells.clone(),
+            cells,
+            neighborhood,
+            edges,
+            generation: 0,
+        }
+    }
+
+    #[must_use]
+    pub fn cells(&self) -> &Grid<T> {
+        &self.cells
+    }
+
+    #[must_use]
+    pub fn into_cells(self) -> Grid<T> {
+        self.cells
+    }
+
+    /// What's past the edges, `None` when they wrap.
+    #[must_use]
+    pub fn background(&self) -> Option<&T> {
+        match self.edges {
+            Edges::Wrap => None,
+            Edges::Fixed(ref background) | Edges::Infinite(ref background) => Some(background),
+        }
+    }
+
+    /// The number of steps taken so far.
+    #[must_use]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Applies `rule` to every cell, returning whether anything changed.
+    pub fn step<R>(&mut self, mut rule: R) -> bool
+    where
+        R: FnMut(&T, [&T; N]) -> T,
+    {
+        let growth = self.growth();
+
+        let width = self.cells.width() + 2 * growth;
+        let height = self.cells.height() + 2 * growth;
+
+        if self.buffer.width() != width || self.buffer.height() != height {
+            self.buffer = Grid::new(
+                width,
+                height,
+                at(&self.cells, &self.edges, growth, (0, 0), (0, 0)).clone(),
+            );
+        }
+
+        let mut changed = false;
+
+        for (coordinates, next) in self.buffer.iter_mut() {
+            let cell = at(&self.cells, &self.edges, growth, coordinates, (0, 0));
+
+            let neighbors = self
+                .neighborhood
+                .map(|offset| at(&self.cells, &self.edges, growth, coordinates, offset));
+
+            *next = rule(cell, neighbors);
+
+            changed |= next != cell;
+        }
+
+        if let Edges::Infinite(ref mut background) = self.edges {
+            let next = rule(background, [&*background; N]);
+
+            changed |= next != *background;
+
+            *background = next;
+        }
+
+        mem::swap(&mut self.cells, &mut self.buffer);
+
+        self.generation += 1;
+
+        changed
+    }
+
+    /// Takes `steps` steps, calling `on_step` after each one.
+    ///
+    /// # Errors
+    ///
+    /// The first error `on_step` returns, which stops the run.
+    pub fn run<R, C, E>(&mut self, steps: u64, mut rule: R, mut on_step: C) -> Result<(), E>
+    where
+        R: FnMut(&T, [&T; N]) -> T,
+        C: FnMut(&Self) -> Result<(), E>,
+    {
+        for _ in 0..steps {
+            self.step(&mut rule);
+
+            on_step(self)?;
+        }
+
+        Ok(())
+    }
+
+    /// Steps until a step changes nothing, calling `on_step` after each one. Returns the number of
+    /// steps taken, including the one that changed nothing, or `None` when things still changed
+    /// after `max_steps`.
+    ///
+    /// # Errors
+    ///
+    /// The first error `on_step` returns, which stops the run.
+    pub fn run_until_stable<R, C, E>(
+        &mut self,
+        max_steps: u64,
+        mut rule: R,
+        mut on_step: C,
+    ) -> Result<Option<u64>, E>
+    where
+        R: FnMut(&T, [&T; N]) -> T,
+        C: FnMut(&Self) -> Result<(), E>,
+    {
+        for steps in 1..=max_steps {
+            let changed = self.step(&mut rule);
+
+            on_step(self)?;
+
+            if !changed {
+                return Ok(Some(steps));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Steps until we get back to a state we've seen before, calling `on_step` after each step.
+    /// `None` when there's no cycle within `max_steps`, which is always the case with
+    /// `Edges::Infinite`, as the grid grows every step.
+    ///
+    /// States are only remembered by their hash, so a collision could fake a cycle, but with 64
+    /// bits that's not going to happen at the sizes we run.
+    ///
+    /// # Errors
+    ///
+    /// The first error `on_step` returns, which stops the run.
+    pub fn find_cycle<R, C, E>(
+        &mut self,
+        max_steps: u64,
+        mut rule: R,
+        mut on_step: C,
+    ) -> Result<Option<Cycle>, E>
+    where
+        T: Hash,
+        R: FnMut(&T, [&T; N]) -> T,
+        C: FnMut(&Self) -> Result<(), E>,
+    {
+        let mut seen: HashMap<u64, u64> = HashMap::new();
+
+        for steps in 0.. {
+            let state = self.state_hash();
+
+            if let Some(&start) = seen.get(&state) {
+                return Ok(Some(Cycle {
+                    start,
+                    length: self.generation - start,
+                }));
+            }
+
+            if steps == max_steps {
+                break;
+            }
+
+            seen.insert(state, self.generation);
+
+            self.step(&mut rule);
+
+            on_step(self)?;
+        }
+
+        Ok(None)
+    }
+
+    /// A hash of the cells and the background, which is the same every run.
+    fn state_hash(&self) -> u64
+    where
+        T: Hash,
+    {
+        let mut hasher = DefaultHasher::new();
+
+        self.cells.hash(&mut hasher);
+        self.background().hash(&mut hasher);
+
+        hasher.finish()
+    }
+
+    /// How far the grid grows on each side every step.
+    fn growth(&self) -> usize {
+        match self.edges {
+            Edges::Infinite(_) => self
+                .neighborhood
+                .iter()
+                .map(|&(row, column)| row.unsigned_abs().max(column.unsigned_abs()))
+                .max()
+                .unwrap_or_default(),
+            Edges::Wrap | Edges::Fixed(_) => 0,
+        }
+    }
+}
+
+/// The cell `offset` away from `coordinates`, which are on a grid `growth` bigger on every side
+/// than `cells`.
+fn at<'c, T>(
+    cells: &'c Grid<T>,
+    edges: &'c Edges<T>,
+    growth: usize,
+    (row, column): Coordinates,
+    (row_offset, column_offset): (isize, isize),
+) -> &'c T {
+    match *edges {
+        Edges::Wrap => &cells[cells.offset_wrapping((row, column), (row_offset, column_offset))],
+        Edges::Fixed(ref background) | Edges::Infinite(ref background) => row
+            .checked_add_signed(row_offset)
+            .and_then(|moved| moved.checked_sub(growth))
+            .zip(
+                column
+                    .checked_add_signed(column_offset)
+                    .and_then(|moved| moved.checked_sub(growth)),
+            )
+            .and_then(|moved| cells.get(moved))
+            .unwrap_or(background),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::automaton::{Automaton, Cycle, Edges, WINDOW_3X3};
+    use crate::grid::{Grid, OFFSETS_4, OFFSETS_8};
+
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "Rules get the cell by reference"
+    )]
+    fn life(cell: &bool, neighbors: [&bool; 8]) -> bool {
+        let alive = neighbors.into_iter().filter(|&&alive| alive).count();
+
+        alive == 3 || (*cell && alive == 2)
+    }
+
+    fn parse(text: &str) -> Grid<bool> {
+        Grid::parse(text, |_, c| Ok::<_, ()>(c == '#')).unwrap()
+    }
+
+    fn render(grid: &Grid<bool>) -> Vec<String> {
+        grid.map(|&alive| if alive { '#' } else { '.' }).lines()
+    }
+
+    #[test]
+    fn blinker() {
+        let mut automaton = Automaton::new(
+            parse(".....\n.....\n.###.\n.....\n....."),
+            OFFSETS_8,
+            Edges::Fixed(false),
+        );
+
+        assert!(automaton.step(life));
+
+        assert_eq!(
+            vec![".....", "..#..", "..#..", "..#..", "....."],
+            render(automaton.cells())
+        );
+        assert_eq!(1, automaton.generation());
+
+        assert_eq!(
+            Ok::<_, ()>(Some(Cycle {
+                start: 1,
+                length: 2
+            })),
+            automaton.find_cycle(10, life, |_| Ok(()))
+        );
+        assert_eq!(3, automaton.generation());
+    }
+
+    #[test]
+    fn no_cycle_within_bound() {
+        // the blinker needs 2 steps to come back
+        let mut automaton = Automaton::new(
+            parse(".....\n.....\n.###.\n.....\n....."),
+            OFFSETS_8,
+            Edges::Fixed(false),
+        );
+
+        assert_eq!(Ok::<_, ()>(None), automaton.find_cycle(1, life, |_| Ok(())));
+        assert_eq!(1, automaton.generation());
+
+        // the grid grows, so it never repeats
+        let mut automaton = Automaton::new(parse("#"), WINDOW_3X3, Edges::Infinite(false));
+
+        assert_eq!(
+            Ok::<_, ()>(None),
+            automaton.find_cycle(5, |&cell, _| cell, |_| Ok(()))
+        );
+        assert_eq!(5, automaton.generation());
+    }
+
+    #[test]
+    fn glider_wraps() {
+        let start = parse(".#....\n..#...\n###...\n......\n......\n......");
+
+        let mut automaton = Automaton::new(start.clone(), OFFSETS_8, Edges::Wrap);
+
+        let mut rendered = Vec::new();
+
+        // a glider moves one cell diagonally every 4 steps, so after 24 it's back
+        automaton
+            .run(24, life, |automaton| {
+                rendered.push(render(automaton.cells()));
+
+                Ok::<_, ()>(())
+            })
+            .unwrap();
+
+        assert_eq!(24, rendered.len());
+        assert_eq!(&start, automaton.cells());
+    }
+
+    #[test]
+    fn until_stable() {
+        // turns into a block, which never changes
+        let mut automaton = Automaton::new(
+            parse("....\n.##.\n.#..\n...."),
+            OFFSETS_8,
+            Edges::Fixed(false),
+        );
+
+        let steps = automaton
+            .run_until_stable(10, life, |_| Ok::<_, ()>(()))
+            .unwrap();
+
+        assert_eq!(
+            vec!["....", ".##.", ".##.", "...."],
+            render(automaton.cells())
+        );
+        assert_eq!(Some(2), steps);
+
+        // a blinker never settles
+        let mut automaton = Automaton::new(
+            parse(".....\n.....\n.###.\n.....\n....."),
+            OFFSETS_8,
+            Edges::Fixed(false),
+        );
+
+        assert_eq!(
+            Ok::<_, ()>(None),
+            automaton.run_until_stable(7, life, |_| Ok(()))
+        );
+        assert_eq!(7, automaton.generation());
+    }
+
+    #[test]
+    fn rule_sees_neighbors_in_order() {
+        let mut automaton = Automaton::new(
+            Grid::from_rows::<()>(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap(),
+            OFFSETS_4,
+            Edges::Wrap,
+        );
+
+        // up, right, down and left, as digits
+        automaton.step(|_, [up, right, down, left]| ((up * 10 + right) * 10 + down) * 10 + left);
+
+        assert_eq!(
+            &Grid::from_rows::<()>(vec![vec![4243, 5351, 6162], vec![1516, 2624, 3435]]).unwrap(),
+            automaton.cells()
+        );
+    }
+
+    #[test]
+    fn infinite_background() {
+        // every cell flips, so does the background
+        let mut automaton = Automaton::new(parse("#"), WINDOW_3X3, Edges::Infinite(false));
+
+        assert!(automaton.step(|&cell, _| !cell));
+
+        assert_eq!(vec!["###", "#.#", "###"], render(automaton.cells()));
+        assert_eq!(Some(&true), automaton.background());
+
+        // a cell turns on when any cell in its window is on
+        let mut automaton = Automaton::new(parse("#"), WINDOW_3X3, Edges::Infinite(false));
+
+        automaton
+            .run(
+                2,
+                |_, window| window.into_iter().any(|&on| on),
+                |_| Ok::<_, ()>(()),
+            )
+            .unwrap();
+
+        assert_eq!(5, automaton.cells().width());
+        assert_eq!(25, automaton.cells().iter().filter(|&(_, &on)| on).count());
+        assert_eq!(Some(&false), automaton.background());
+    }
+}
@@ -0,0 +1,337 @@
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+
+/// Why a `BitReader` couldn't give us what we asked for.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum BitError {
+    /// Asked for `wanted` bits at `position`, with only `left` to go. The position counts from
+    /// the start of the whole stream, not of a sub-reader.
+    EndOfStream {
+        position: usize,
+        wanted: usize,
+        left: usize,
+    },
+    /// Asked for `wanted` bits at once, more than the type we read into holds.
+    TooWide { wanted: usize },
+    /// `found` at `column` of a hex string isn't a hex digit.
+    NotHex { column: usize, found: char },
+}
+
+impl Display for BitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            BitError::EndOfStream {
+                position,
+                wanted,
+                left,
+            } => write!(
+                f,
+                "asked for {} bits at bit {}, but only {} are left",
+                wanted, position, left
+            ),
+            BitError::TooWide { wanted } => write!(f, "{} bits don't fit", wanted),
+            BitError::NotHex { found, .. } => write!(f, "`{}` is not a hex digit", found),
+        }
+    }
+}
+
+impl std::error::Error for BitError {}
+
+/// Reads bits, most significant first, from a stream of bytes.
+///
+/// Positions are in bits, and relative to where the reader starts.
+#[derive(Clone, Debug)]
+pub struct BitReader<'b> {
+    bytes: Cow<'b, [u8]>,
+    /// Bit offset into `bytes` where this reader starts.
+    start: usize,
+    /// Bit offset into `bytes` of the next bit to read.
+    next: usize,
+    /// Bit offset into `bytes` where this reader stops.
+    end: usize,
+}
+
+impl<'b> BitReader<'b> {
+    #[must_use]
+    pub fn new(bytes: &'b [u8]) -> Self {
+        Self::with_bytes(Cow::Borrowed(bytes))
+    }
+
+    fn with_bytes(bytes: Cow<'b, [u8]>) -> Self {
+        let end = bytes.len() * 8;
+
+        Self {
+            bytes,
+            start: 0,
+            next: 0,
+            end,
+        }
+    }
+
+    /// A reader over the bits `hex` spells out, 4 per digit.
+    ///
+    /// # Errors
+    ///
+    /// When `hex` has anything but hex digits.
+    pub fn from_hex(hex: &str) -> Result<BitReader<'static>, BitError> {
+        let nibbles = hex
+            .chars()
+            .enumerate()
+            .map(|(column, found)| {
+                found
+                    .to_digit(16)
+                    .and_then(|digit| u8::try_from(digit).ok())
+                    .ok_or(BitError::NotHex { column, found })
+            })
+            .collect::<Result<Vec<u8>, BitError>>()?;
+
+        let bytes = nibbles
+            .chunks(2)
+            .map(|pair| match *pair {
+                [high, low] => (high << 4) | low,
+                // an odd number of digits, so the last one only fills the top half
+                _ => pair.first().copied().unwrap_or_default() << 4,
+            })
+            .collect();
+
+        let mut reader = BitReader::with_bytes(Cow::Owned(bytes));
+
+        reader.end = nibbles.len() * 4;
+
+        Ok(reader)
+    }
+
+    /// How many bits we read so far.
+    #[must_use]
+    pub fn position(&self) -> usize {
+        self.next - self.start
+    }
+
+    /// Where the next bit is in the whole stream, like the positions in errors, even for a
+    /// sub-reader.
+    #[must_use]
+    pub fn stream_position(&self) -> usize {
+        self.next
+    }
+
+    /// How many bits are left to read.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.end - self.next
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// The next `bits` bits, as a number. Reading 0 bits gives 0.
+    ///
+    /// # Errors
+    ///
+    /// When `bits` is over 64, or when there aren't that many bits left. Nothing is read then.
+    pub fn read(&mut self, bits: usize) -> Result<u64, BitError> {
+        if bits > 64 {
+            return Err(BitError::TooWide { wanted: bits });
+        }
+
+        self.check_left(bits)?;
+
+        let mut value: u64 = 0;
+        let mut left = bits;
+
+        // a byte, or what's left of it, at a time
+        while left > 0 {
+            let byte = u64::from(self.bytes[self.next / 8]);
+            let used = self.next % 8;
+            let take = (8 - used).min(left);
+
+            let chunk = (byte >> (8 - used - take)) & ((1 << take) - 1);
+
+            value = (value << take) | chunk;
+
+            self.next += take;
+            left -= take;
+        }
+
+        Ok(value)
+    }
+
+    /// The next `bits` bits, as a `T`.
+    ///
+    /// # Errors
+    ///
+    /// When the value doesn't fit in `T`, or like `read`.
+    pub fn read_as<T: TryFrom<u64>>(&mut self, bits: usize) -> Result<T, BitError> {
+        let value = self.read(bits)?;
+
+        T::try_from(value).map_err(|_| BitError::TooWide { wanted: bits })
+    }
+
+    /// The next bit, set or not.
+    ///
+    /// # Errors
+    ///
+    /// When we're at the end.
+    pub fn read_bit(&mut self) -> Result<bool, BitError> {
+        Ok(self.read(1)? == 1)
+    }
+
+    /// A reader over the next `bits` bits. We skip past them, so once the sub-reader is done we
+    /// continue right after.
+    ///
+    /// # Errors
+    ///
+    /// When there aren't that many bits left.
+    pub fn sub_reader(&mut self, bits: usize) -> Result<BitReader<'_>, BitError> {
+        self.check_left(bits)?;
+
+        let start = self.next;
+
+        self.next += bits;
+
+        Ok(BitReader {
+            bytes: Cow::Borrowed(&self.bytes),
+            start,
+            next: start,
+            end: start + bits,
+        })
+    }
+
+    fn check_left(&self, bits: usize) -> Result<(), BitError> {
+        if bits > self.remaining() {
+            return Err(BitError::EndOfStream {
+                position: self.next,
+                wanted: bits,
+                left: self.remaining(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::bits::{BitError, BitReader};
+
+    #[test]
+    fn read_across_bytes() {
+        let bytes = [0b1011_0011, 0b0101_1100, 0xFF];
+        let mut reader = BitReader::new(&bytes);
+
+        assert_eq!(Ok(0b101), reader.read(3));
+        assert_eq!(Ok(0b1_0011_0101), reader.read(9));
+        assert_eq!(Ok(true), reader.read_bit());
+        assert_eq!(Ok(0), reader.read(0));
+        assert_eq!(13, reader.position());
+        assert_eq!(11, reader.remaining());
+        assert_eq!(Ok(0b100_1111_1111), reader.read(11));
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn read_64_bits() {
+        let bytes = [0x12, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE, 0xF0, 0x80];
+        let mut reader = BitReader::new(&bytes);
+
+        assert_eq!(Ok(0x1234_5678_9ABC_DEF0), reader.read(64));
+        assert_eq!(Ok(true), reader.read_bit());
+
+        assert_eq!(Err(BitError::TooWide { wanted: 65 }), reader.read(65));
+        assert_eq!(7, reader.remaining());
+
+        assert_eq!(
+            BitError::TooWide { wanted: 9 },
+            BitReader::new(&[0xFF, 0xFF]).read_as::<u8>(9).unwrap_err()
+        );
+    }
+
+    #[test]
+    fn from_hex() {
+        let mut reader = BitReader::from_hex("D2FE28").unwrap();
+
+        assert_eq!(24, reader.remaining());
+        assert_eq!(Ok(6_u8), reader.read_as(3));
+        assert_eq!(Ok(4_u8), reader.read_as(3));
+
+        let mut odd = BitReader::from_hex("A").unwrap();
+
+        assert_eq!(Ok(0b1010), odd.read(4));
+        assert!(odd.is_empty());
+
+        assert_eq!(
+            BitError::NotHex {
+                column: 2,
+                found: 'G'
+            },
+            BitReader::from_hex("12G4").unwrap_err()
+        );
+    }
+
+    #[test]
+    fn end_of_stream() {
+        let mut reader = BitReader::from_hex("F").unwrap();
+
+        reader.read(3).unwrap();
+
+        assert_eq!(
+            Err(BitError::EndOfStream {
+                position: 3,
+                wanted: 2,
+                left: 1
+            }),
+            reader.read(2)
+        );
+
+        // nothing was read
+        assert_eq!(Ok(1), reader.read(1));
+        assert_eq!(
+            BitError::EndOfStream {
+                position: 4,
+                wanted: 1,
+                left: 0
+            },
+            reader.read_bit().unwrap_err()
+        );
+    }
+
+    #[test]
+    fn sub_reader() {
+        let mut reader = BitReader::from_hex("ABCD").unwrap();
+
+        assert_eq!(Ok(0b1), reader.read(1));
+
+        {
+            let mut sub_reader = reader.sub_reader(6).unwrap();
+
+            assert_eq!(0, sub_reader.position());
+            assert_eq!(Ok(0b010), sub_reader.read(3));
+            assert_eq!(3, sub_reader.position());
+            assert_eq!(4, sub_reader.stream_position());
+            assert_eq!(
+                BitError::EndOfStream {
+                    position: 4,
+                    wanted: 4,
+                    left: 3
+                },
+                sub_reader.read(4).unwrap_err()
+            );
+            assert_eq!(Ok(0b101), sub_reader.read(3));
+            assert!(sub_reader.is_empty());
+        }
+
+        assert_eq!(7, reader.position());
+        assert_eq!(Ok(0b1_1100_1101), reader.read(9));
+        assert_eq!(
+            BitError::EndOfStream {
+                position: 16,
+                wanted: 1,
+                left: 0
+            },
+            reader.sub_reader(1).unwrap_err()
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt::{self, Display};
+
+/// A row of `len` bits, as long as it needs to be. Bit 0 is the first one, which is the most
+/// significant when reading the row as a number.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct BitSet {
+    /// 64 bits per word, the last word only partly used. Unused bits are always 0.
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitSet {
+    /// `len` bits, none of them set.
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: vec![0; len.div_ceil(64)],
+            len,
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether bit `index` is set, `None` past the end.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        (index < self.len).then(|| (self.words[index / 64] >> (index % 64)) & 1 == 1)
+    }
+
+    /// # Panics
+    ///
+    /// When `index` is past the end.
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(
+            index < self.len,
+            "bit {} is past the end of {} bits",
+            index,
+            self.len
+        );
+
+        let word = &mut self.words[index / 64];
+
+        if value {
+            *word |= 1 << (index % 64);
+        } else {
+            *word &= !(1 << (index % 64));
+        }
+    }
+
+    /// How many bits are set.
+    #[must_use]
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Every bit, from the first.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|index| (self.words[index / 64] >> (index % 64)) & 1 == 1)
+    }
+
+    /// The same bits, with every one flipped.
+    #[must_use]
+    pub fn complement(&self) -> Self {
+        self.iter().map(|bit| !bit).collect()
+    }
+
+    /// The bits as a binary number, the first bit the most significant. `None` when it doesn't
+    /// fit, only leading zeros can go past 64 bits.
+    #[must_use]
+    pub fn to_u64(&self) -> Option<u64> {
+        self.iter().try_fold(0_u64, |value, bit| {
+            if value.leading_zeros() == 0 {
+                return None;
+            }
+
+            Some((value << 1) | u64::from(bit))
+        })
+    }
+}
+
+impl FromIterator<bool> for BitSet {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut set = Self::default();
+
+        for bit in iter {
+            if set.len % 64 == 0 {
+                set.words.push(0);
+            }
+
+            set.len += 1;
+            set.set(set.len - 1, bit);
+        }
+
+        set
+    }
+}
+
+impl Display for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in self.iter() {
+            write!(f, "{}", if bit { '1' } else { '0' })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::bitset::BitSet;
+
+    fn parse(text: &str) -> BitSet {
+        text.chars().map(|c| c == '1').collect()
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut set = BitSet::new(130);
+
+        assert_eq!(130, set.len());
+        assert_eq!(0, set.count_ones());
+
+        set.set(0, true);
+        set.set(64, true);
+        set.set(129, true);
+        set.set(64, false);
+
+        assert_eq!(Some(true), set.get(0));
+        assert_eq!(Some(false), set.get(64));
+        assert_eq!(Some(true), set.get(129));
+        assert_eq!(None, set.get(130));
+        assert_eq!(2, set.count_ones());
+    }
+
+    #[test]
+    #[should_panic(expected = "bit 3 is past the end of 3 bits")]
+    fn set_past_the_end() {
+        BitSet::new(3).set(3, true);
+    }
+
+    #[test]
+    fn from_text() {
+        let set = parse("10110");
+
+        assert_eq!(5, set.len());
+        assert_eq!("10110", set.to_string());
+        assert_eq!(Some(0b10110), set.to_u64());
+        assert_eq!("01001", set.complement().to_string());
+        assert_eq!(Some(0b01001), set.complement().to_u64());
+
+        assert!(parse("").is_empty());
+        assert_eq!(Some(0), parse("").to_u64());
+    }
+
+    #[test]
+    fn wide() {
+        let ones = "1".repeat(64);
+
+        assert_eq!(Some(u64::MAX), parse(&ones).to_u64());
+
+        // leading zeros still fit
+        assert_eq!(
+            Some(u64::MAX),
+            parse(&format!("{}{}", "0".repeat(100), ones)).to_u64()
+        );
+        assert_eq!(None, parse(&format!("1{}", ones)).to_u64());
+
+        // the unused part of the last word stays empty
+        let complement = parse(&format!("1{}", ones)).complement();
+
+        assert_eq!(65, complement.len());
+        assert_eq!(0, complement.count_ones());
+    }
+}
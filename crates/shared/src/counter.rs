@@ -0,0 +1,298 @@
+use std::cmp::Reverse;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, Sub};
+
+use hashbrown::HashMap;
+
+/// How many times each key was seen. Keys that were never seen, or whose count dropped to 0,
+/// aren't stored.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Counter<K: Eq + Hash> {
+    counts: HashMap<K, u64>,
+}
+
+impl<K: Eq + Hash> Default for Counter<K> {
+    fn default() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Eq + Hash> Counter<K> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts `key` once more, returning its new count.
+    pub fn add(&mut self, key: K) -> u64 {
+        self.add_n(key, 1)
+    }
+
+    /// Counts `key` `count` more times, returning its new count.
+    pub fn add_n(&mut self, key: K, count: u64) -> u64 {
+        // adding nothing doesn't store the key
+        if count == 0 {
+            return self.get(&key);
+        }
+
+        let entry = self.counts.entry(key).or_default();
+
+        *entry += count;
+
+        *entry
+    }
+
+    /// Adds every count of `other` to ours.
+    pub fn merge(&mut self, other: &Self)
+    where
+        K: Clone,
+    {
+        for (key, &count) in &other.counts {
+            self.add_n(key.clone(), count);
+        }
+    }
+
+    /// 0 for keys we never saw.
+    #[must_use]
+    pub fn get(&self, key: &K) -> u64 {
+        self.counts.get(key).copied().unwrap_or_default()
+    }
+
+    /// Forgets `key`, returning the count it had.
+    pub fn remove(&mut self, key: &K) -> u64 {
+        self.counts.remove(key).unwrap_or_default()
+    }
+
+    /// The number of distinct keys.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The sum of all counts.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// The counts, in no particular order. Cheaper than `iter` when the keys don't matter.
+    pub fn values(&self) -> impl Iterator<Item = u64> + '_ {
+        self.counts.values().copied()
+    }
+
+    /// The number of keys whose count matches `predicate`.
+    #[must_use]
+    pub fn len_where<P: Fn(u64) -> bool>(&self, predicate: P) -> usize {
+        self.values().filter(|&count| predicate(count)).count()
+    }
+
+    /// The keys and their counts, ordered by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, u64)>
+    where
+        K: Ord,
+    {
+        let mut entries: Vec<(&K, u64)> = self
+            .counts
+            .iter()
+            .map(|(key, &count)| (key, count))
+            .collect();
+
+        entries.sort_unstable_by_key(|&(key, _)| key);
+
+        entries.into_iter()
+    }
+
+    /// The keys and their counts, the highest count first. Equal counts are ordered by key.
+    #[must_use]
+    pub fn most_common(&self) -> Vec<(&K, u64)>
+    where
+        K: Ord,
+    {
+        let mut entries: Vec<(&K, u64)> = self.iter().collect();
+
+        entries.sort_by_key(|&(_, count)| Reverse(count));
+
+        entries
+    }
+
+    /// The key with the lowest count, the smallest key when several have it.
+    #[must_use]
+    pub fn min(&self) -> Option<(&K, u64)>
+    where
+        K: Ord,
+    {
+        self.iter().min_by_key(|&(_, count)| count)
+    }
+
+    /// The key with the highest count, the smallest key when several have it.
+    #[must_use]
+    pub fn max(&self) -> Option<(&K, u64)>
+    where
+        K: Ord,
+    {
+        self.most_common().into_iter().next()
+    }
+}
+
+impl<K: Eq + Hash> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut counter = Self::new();
+
+        counter.extend(iter);
+
+        counter
+    }
+}
+
+impl<K: Eq + Hash> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.add(key);
+        }
+    }
+}
+
+impl<K: Eq + Hash> FromIterator<(K, u64)> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = (K, u64)>>(iter: I) -> Self {
+        let mut counter = Self::new();
+
+        for (key, count) in iter {
+            counter.add_n(key, count);
+        }
+
+        counter
+    }
+}
+
+impl<K: Eq + Hash> AddAssign for Counter<K> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (key, count) in rhs.counts {
+            self.add_n(key, count);
+        }
+    }
+}
+
+impl<K: Eq + Hash> Add for Counter<K> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+
+        self
+    }
+}
+
+/// Counts don't go below 0, keys that would are dropped.
+impl<K: Eq + Hash> Sub for Counter<K> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        for (key, count) in rhs.counts {
+            if let Some(ours) = self.counts.get_mut(&key) {
+                if *ours > count {
+                    *ours -= count;
+                } else {
+                    self.counts.remove(&key);
+                }
+            }
+        }
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::counter::Counter;
+
+    #[test]
+    fn add_and_get() {
+        let mut counter = Counter::new();
+
+        assert_eq!(1, counter.add('a'));
+        assert_eq!(2, counter.add('a'));
+        assert_eq!(5, counter.add_n('b', 5));
+        assert_eq!(0, counter.add_n('c', 0));
+        assert_eq!(2, counter.add_n('a', 0));
+
+        assert_eq!(2, counter.get(&'a'));
+        assert_eq!(0, counter.get(&'c'));
+        assert_eq!(2, counter.len());
+        assert_eq!(7, counter.total());
+
+        assert_eq!(5, counter.remove(&'b'));
+        assert_eq!(0, counter.get(&'b'));
+    }
+
+    #[test]
+    fn stable_order() {
+        let counter: Counter<char> = "mississippi".chars().collect();
+
+        assert_eq!(
+            vec![(&'i', 4), (&'m', 1), (&'p', 2), (&'s', 4)],
+            counter.iter().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            vec![(&'i', 4), (&'s', 4), (&'p', 2), (&'m', 1)],
+            counter.most_common()
+        );
+
+        assert_eq!(Some((&'m', 1)), counter.min());
+        assert_eq!(Some((&'i', 4)), counter.max());
+
+        assert_eq!(None, Counter::<char>::new().max());
+    }
+
+    #[test]
+    fn values() {
+        let counter: Counter<char> = "mississippi".chars().collect();
+
+        let mut values = counter.values().collect::<Vec<_>>();
+
+        values.sort_unstable();
+
+        assert_eq!(vec![1, 2, 4, 4], values);
+
+        assert_eq!(2, counter.len_where(|count| count >= 4));
+        assert_eq!(4, counter.len_where(|count| count >= 1));
+        assert_eq!(0, counter.len_where(|count| count > 4));
+    }
+
+    #[test]
+    fn merge() {
+        let mut counter: Counter<char> = "aab".chars().collect();
+
+        counter.merge(&"bc".chars().collect());
+
+        assert_eq!(
+            vec![(&'a', 2), (&'b', 2), (&'c', 1)],
+            counter.iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        let left: Counter<char> = "aabc".chars().collect();
+        let right: Counter<char> = [('a', 1), ('c', 3), ('d', 1)].into_iter().collect();
+
+        assert_eq!(
+            vec![(&'a', 3), (&'b', 1), (&'c', 4), (&'d', 1)],
+            (left.clone() + right.clone()).iter().collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            vec![(&'a', 1), (&'b', 1)],
+            (left - right).iter().collect::<Vec<_>>()
+        );
+    }
+}
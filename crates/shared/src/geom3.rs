@@ -0,0 +1,398 @@
+use std::ops::{Add, Neg, Sub};
+
+/// A point, or the offset between two points, on an integer grid in 3 dimensions.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Default)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Vec3 {
+    pub const ZERO: Self = Self::new(0, 0, 0);
+
+    #[must_use]
+    pub const fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// The same value on every axis.
+    #[must_use]
+    pub const fn splat(value: i64) -> Self {
+        Self::new(value, value, value)
+    }
+
+    /// The distance when only moving along the axes.
+    #[must_use]
+    pub fn manhattan_distance(self, other: Self) -> u64 {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y) + self.z.abs_diff(other.z)
+    }
+
+    fn dot(self, other: Self) -> i64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    fn cross(self, other: Self) -> Self {
+        Self::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    fn to_array(self) -> [i64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    fn from_array([x, y, z]: [i64; 3]) -> Self {
+        Self::new(x, y, z)
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.x, -self.y, -self.z)
+    }
+}
+
+/// One of the 24 ways to turn something around the origin so its axes line up with the axes
+/// again, without mirroring it. Stored as a matrix where every row and column has a single 1 or
+/// -1.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Rotation {
+    rows: [[i64; 3]; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Self = Self {
+        rows: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    /// All 24 rotations, the identity first.
+    #[must_use]
+    pub fn all() -> Vec<Self> {
+        const PERMUTATIONS: [[usize; 3]; 6] = [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ];
+
+        let mut rotations = Vec::with_capacity(24);
+
+        // every axis can go to any other axis, facing either way. Half of those mirror, which
+        // shows as a determinant of -1
+        for permutation in PERMUTATIONS {
+            for signs in 0..8_u8 {
+                let mut rows = [[0; 3]; 3];
+
+                for (row, (cells, &column)) in rows.iter_mut().zip(&permutation).enumerate() {
+                    cells[column] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+
+                let rotation = Self { rows };
+
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+
+        rotations
+    }
+
+    /// Turns `vector` around the origin.
+    #[must_use]
+    pub fn apply(self, vector: Vec3) -> Vec3 {
+        let vector = vector.to_array();
+
+        Vec3::from_array(self.rows.map(|row| {
+            row.iter()
+                .zip(&vector)
+                .map(|(cell, axis)| cell * axis)
+                .sum()
+        }))
+    }
+
+    /// The rotation that does `self` first, and `next` after it.
+    #[must_use]
+    pub fn then(self, next: Self) -> Self {
+        let mut rows = [[0; 3]; 3];
+
+        for (row, cells) in rows.iter_mut().enumerate() {
+            for (column, cell) in cells.iter_mut().enumerate() {
+                *cell = (0..3)
+                    .map(|index| next.rows[row][index] * self.rows[index][column])
+                    .sum();
+            }
+        }
+
+        Self { rows }
+    }
+
+    /// The rotation that undoes `self`.
+    #[must_use]
+    pub fn inverse(self) -> Self {
+        let mut rows = [[0; 3]; 3];
+
+        for (row, cells) in self.rows.iter().enumerate() {
+            for (column, &cell) in cells.iter().enumerate() {
+                rows[column][row] = cell;
+            }
+        }
+
+        Self { rows }
+    }
+
+    fn determinant(self) -> i64 {
+        let [first, second, third] = self.rows.map(Vec3::from_array);
+
+        first.dot(second.cross(third))
+    }
+}
+
+/// An axis-aligned box, `min` and `max` both included. It's empty when `max` is below `min` on
+/// any axis.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Cuboid {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+impl Cuboid {
+    #[must_use]
+    pub const fn new(min: Vec3, max: Vec3) -> Self {
+        Self { min, max }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.max.x < self.min.x || self.max.y < self.min.y || self.max.z < self.min.z
+    }
+
+    #[must_use]
+    pub fn contains(&self, point: Vec3) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    #[must_use]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The part both boxes have in common, if any.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = Self::new(
+            Vec3::new(
+                self.min.x.max(other.min.x),
+                self.min.y.max(other.min.y),
+                self.min.z.max(other.min.z),
+            ),
+            Vec3::new(
+                self.max.x.min(other.max.x),
+                self.max.y.min(other.max.y),
+                self.max.z.min(other.max.z),
+            ),
+        );
+
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// What's left of `self` after cutting `other` out of it, as at most 6 boxes that don't
+    /// overlap.
+    #[must_use]
+    pub fn subtract(self, other: &Self) -> Vec<Self> {
+        let Some(cut) = self.intersection(other) else {
+            return vec![self];
+        };
+
+        // slice off the parts left and right of the cut on x, then in front and behind on y
+        // within the cut's x range, then below and above on z within the cut's x and y range
+        [
+            Self::new(self.min, Vec3::new(cut.min.x - 1, self.max.y, self.max.z)),
+            Self::new(Vec3::new(cut.max.x + 1, self.min.y, self.min.z), self.max),
+            Self::new(
+                Vec3::new(cut.min.x, self.min.y, self.min.z),
+                Vec3::new(cut.max.x, cut.min.y - 1, self.max.z),
+            ),
+            Self::new(
+                Vec3::new(cut.min.x, cut.max.y + 1, self.min.z),
+                Vec3::new(cut.max.x, self.max.y, self.max.z),
+            ),
+            Self::new(
+                Vec3::new(cut.min.x, cut.min.y, self.min.z),
+                Vec3::new(cut.max.x, cut.max.y, cut.min.z - 1),
+            ),
+            Self::new(
+                Vec3::new(cut.min.x, cut.min.y, cut.max.z + 1),
+                Vec3::new(cut.max.x, cut.max.y, self.max.z),
+            ),
+        ]
+        .into_iter()
+        .filter(|piece| !piece.is_empty())
+        .collect()
+    }
+
+    /// The number of integer points inside, 0 when empty.
+    #[must_use]
+    pub fn volume(&self) -> u64 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        // plus one as both ends are included
+        (self.min.x.abs_diff(self.max.x) + 1)
+            * (self.min.y.abs_diff(self.max.y) + 1)
+            * (self.min.z.abs_diff(self.max.z) + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use hashbrown::HashSet;
+    use pretty_assertions::assert_eq;
+
+    use crate::geom3::{Cuboid, Rotation, Vec3};
+
+    #[test]
+    fn vector_arithmetic() {
+        let a = Vec3::new(1, -2, 3);
+        let b = Vec3::new(-4, 5, 6);
+
+        assert_eq!(Vec3::new(-3, 3, 9), a + b);
+        assert_eq!(Vec3::new(5, -7, -3), a - b);
+        assert_eq!(Vec3::new(-1, 2, -3), -a);
+        assert_eq!(15, a.manhattan_distance(b));
+        assert_eq!(0, a.manhattan_distance(a));
+    }
+
+    #[test]
+    fn there_are_24_rotations() {
+        let rotations = Rotation::all();
+
+        assert_eq!(24, rotations.len());
+        assert_eq!(Some(&Rotation::IDENTITY), rotations.first());
+
+        let point = Vec3::new(1, 2, 3);
+
+        // a point without symmetry ends up somewhere else for every rotation
+        let rotated: HashSet<Vec3> = rotations.iter().map(|r| r.apply(point)).collect();
+
+        assert_eq!(24, rotated.len());
+    }
+
+    #[test]
+    fn rotations_form_a_group() {
+        let rotations = Rotation::all();
+
+        let all: HashSet<Rotation> = rotations.iter().copied().collect();
+
+        for &first in &rotations {
+            assert_eq!(Rotation::IDENTITY, first.then(first.inverse()));
+            assert_eq!(Rotation::IDENTITY, first.inverse().then(first));
+
+            for &second in &rotations {
+                assert!(all.contains(&first.then(second)));
+            }
+        }
+    }
+
+    #[test]
+    fn composition_order() {
+        let point = Vec3::new(1, 2, 3);
+
+        for first in Rotation::all() {
+            for second in Rotation::all() {
+                assert_eq!(
+                    second.apply(first.apply(point)),
+                    first.then(second).apply(point)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rotations_keep_lengths() {
+        let point = Vec3::new(-7, 2, 5);
+
+        for rotation in Rotation::all() {
+            assert_eq!(
+                point.manhattan_distance(Vec3::ZERO),
+                rotation.apply(point).manhattan_distance(Vec3::ZERO)
+            );
+        }
+    }
+
+    #[test]
+    fn cuboid_volume() {
+        assert_eq!(216, Cuboid::new(Vec3::splat(0), Vec3::splat(5)).volume());
+        assert_eq!(1, Cuboid::new(Vec3::splat(-3), Vec3::splat(-3)).volume());
+        assert_eq!(0, Cuboid::new(Vec3::splat(1), Vec3::splat(0)).volume());
+    }
+
+    #[test]
+    fn cuboid_intersection() {
+        let a = Cuboid::new(Vec3::splat(0), Vec3::splat(4));
+        let b = Cuboid::new(Vec3::splat(3), Vec3::splat(6));
+        let c = Cuboid::new(Vec3::splat(5), Vec3::splat(6));
+
+        assert_eq!(
+            Some(Cuboid::new(Vec3::splat(3), Vec3::splat(4))),
+            a.intersection(&b)
+        );
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&c));
+        assert!(a.contains(Vec3::new(0, 4, 2)));
+        assert!(!a.contains(Vec3::new(0, 5, 2)));
+    }
+
+    #[test]
+    fn cuboid_subtract() {
+        let a = Cuboid::new(Vec3::splat(0), Vec3::splat(4));
+        let b = Cuboid::new(Vec3::new(1, 2, -3), Vec3::new(2, 7, 3));
+
+        let pieces = a.subtract(&b);
+
+        assert_eq!(
+            a.volume() - a.intersection(&b).unwrap().volume(),
+            pieces.iter().map(Cuboid::volume).sum::<u64>()
+        );
+
+        for (index, piece) in pieces.iter().enumerate() {
+            assert!(!piece.overlaps(&b));
+
+            for other in pieces.iter().skip(index + 1) {
+                assert!(!piece.overlaps(other));
+            }
+        }
+
+        assert_eq!(
+            vec![a],
+            a.subtract(&Cuboid::new(Vec3::splat(5), Vec3::splat(6)))
+        );
+        assert_eq!(Vec::<Cuboid>::new(), a.subtract(&a));
+    }
+}
@@ -0,0 +1,520 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+/// `(row, column)`, both 0-based, with row 0 at the top.
+pub type Coordinates = (usize, usize);
+
+/// Up, right, down, left, as `(row, column)` offsets.
+pub const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Clockwise, starting at the top left, as `(row, column)` offsets.
+pub const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+/// A rectangle of cells, stored row after row in a single `Vec`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// Why text or rows don't make up a `Grid`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GridError<E> {
+    /// `row` has `found` cells where the first row has `expected`.
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The cell at `row`, `column` didn't parse.
+    Cell { row: usize, column: usize, error: E },
+}
+
+impl<E: Display> Display for GridError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GridError::Ragged {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} cells, expected {}",
+                row + 1,
+                found,
+                expected
+            ),
+            GridError::Cell {
+                row,
+                column,
+                ref error,
+            } => write!(f, "row {}, column {}: {}", row + 1, column + 1, error),
+        }
+    }
+}
+
+impl<E: fmt::Debug + Display> std::error::Error for GridError<E> {}
+
+impl<T> Grid<T> {
+    /// A `width` by `height` grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// A grid from its rows, top to bottom.
+    ///
+    /// # Errors
+    ///
+    /// `GridError::Ragged` when the rows aren't all as long as the first one.
+    pub fn from_rows<E>(rows: Vec<Vec<T>>) -> Result<Self, GridError<E>> {
+        let width = rows.first().map_or(0, Vec::len);
+        let height = rows.len();
+
+        let mut cells = Vec::with_capacity(width * height);
+
+        for (row_index, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(GridError::Ragged {
+                    row: row_index,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+
+            cells.extend(row);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a block of text, one line per row and one character per cell. `parse_cell` gets the
+    /// coordinates of the character too, so it can say where things went wrong.
+    ///
+    /// # Errors
+    ///
+    /// The first error of `parse_cell`, or `GridError::Ragged` when the lines aren't all as long
+    /// as the first one.
+    pub fn parse<E, F>(text: &str, mut parse_cell: F) -> Result<Self, GridError<E>>
+    where
+        F: FnMut(Coordinates, char) -> Result<T, E>,
+    {
+        let mut rows = Vec::new();
+
+        for (row_index, line) in text.lines().enumerate() {
+            rows.push(
+                line.chars()
+                    .enumerate()
+                    .map(|(column_index, c)| {
+                        parse_cell((row_index, column_index), c).map_err(|error| GridError::Cell {
+                            row: row_index,
+                            column: column_index,
+                            error,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+
+        Self::from_rows(rows)
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Number of cells.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[must_use]
+    pub fn contains(&self, (row, column): Coordinates) -> bool {
+        row < self.height && column < self.width
+    }
+
+    fn flat_index(&self, coordinates: Coordinates) -> Option<usize> {
+        self.contains(coordinates)
+            .then_some(coordinates.0 * self.width + coordinates.1)
+    }
+
+    /// `None` outside of the grid.
+    #[must_use]
+    pub fn get(&self, coordinates: Coordinates) -> Option<&T> {
+        self.flat_index(coordinates).map(|index| &self.cells[index])
+    }
+
+    /// `None` outside of the grid.
+    pub fn get_mut(&mut self, coordinates: Coordinates) -> Option<&mut T> {
+        self.flat_index(coordinates)
+            .map(|index| &mut self.cells[index])
+    }
+
+    /// The row at `row` from left to right, `None` outside of the grid.
+    #[must_use]
+    pub fn row(&self, row: usize) -> Option<&[T]> {
+        (row < self.height).then(|| &self.cells[row * self.width..(row + 1) * self.width])
+    }
+
+    /// The rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// The column at `column` from top to bottom, `None` outside of the grid.
+    #[must_use]
+    pub fn column(&self, column: usize) -> Option<impl Iterator<Item = &T>> {
+        (column < self.width).then(|| self.cells.iter().skip(column).step_by(self.width))
+    }
+
+    /// The columns from left to right, each from top to bottom.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|column| self.cells.iter().skip(column).step_by(self.width))
+    }
+
+    /// All coordinates, row after row.
+    pub fn coordinates(&self) -> impl Iterator<Item = Coordinates> + use<T> {
+        let width = self.width;
+
+        (0..self.height).flat_map(move |row| (0..width).map(move |column| (row, column)))
+    }
+
+    /// All cells with their coordinates, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coordinates, &T)> {
+        self.coordinates().zip(&self.cells)
+    }
+
+    /// All cells with their coordinates, row after row.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Coordinates, &mut T)> {
+        self.coordinates().zip(&mut self.cells)
+    }
+
+    /// A grid of the same size, with `f` applied to every cell.
+    #[must_use]
+    pub fn map<U, F>(&self, f: F) -> Grid<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// `coordinates` moved by `offset`, `None` when that falls off the grid.
+    #[must_use]
+    pub fn offset(
+        &self,
+        (row, column): Coordinates,
+        (row_offset, column_offset): (isize, isize),
+    ) -> Option<Coordinates> {
+        let moved = (
+            row.checked_add_signed(row_offset)?,
+            column.checked_add_signed(column_offset)?,
+        );
+
+        self.contains(moved).then_some(moved)
+    }
+
+    /// `coordinates` moved by `offset`, where falling off one edge comes back in at the opposite
+    /// edge.
+    ///
+    /// # Panics
+    ///
+    /// When the grid is empty.
+    #[must_use]
+    pub fn offset_wrapping(
+        &self,
+        (row, column): Coordinates,
+        (row_offset, column_offset): (isize, isize),
+    ) -> Coordinates {
+        assert!(!self.is_empty(), "Cannot wrap around an empty grid");
+
+        (
+            wrap(row, row_offset, self.height),
+            wrap(column, column_offset, self.width),
+        )
+    }
+
+    /// The neighbors up, right, down and left of `coordinates` that are on the grid.
+    pub fn neighbors_4(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> {
+        OFFSETS_4
+            .into_iter()
+            .filter_map(move |offset| self.offset(coordinates, offset))
+    }
+
+    /// The neighbors of `coordinates`, diagonals included, that are on the grid.
+    pub fn neighbors_8(&self, coordinates: Coordinates) -> impl Iterator<Item = Coordinates> {
+        OFFSETS_8
+            .into_iter()
+            .filter_map(move |offset| self.offset(coordinates, offset))
+    }
+
+    /// The neighbors up, right, down and left of `coordinates`, wrapping around at the edges.
+    pub fn neighbors_4_wrapping(
+        &self,
+        coordinates: Coordinates,
+    ) -> impl Iterator<Item = Coordinates> {
+        OFFSETS_4
+            .into_iter()
+            .map(move |offset| self.offset_wrapping(coordinates, offset))
+    }
+
+    /// The neighbors of `coordinates`, diagonals included, wrapping around at the edges.
+    pub fn neighbors_8_wrapping(
+        &self,
+        coordinates: Coordinates,
+    ) -> impl Iterator<Item = Coordinates> {
+        OFFSETS_8
+            .into_iter()
+            .map(move |offset| self.offset_wrapping(coordinates, offset))
+    }
+
+    /// The 3x3 block around `coordinates`, row after row, with `None` for cells off the grid.
+    #[must_use]
+    pub fn window_3x3(&self, coordinates: Coordinates) -> [Option<&T>; 9] {
+        let mut window = [None; 9];
+
+        for (cell, (row_offset, column_offset)) in window
+            .iter_mut()
+            .zip((-1..=1).flat_map(|row_offset| (-1..=1).map(move |column| (row_offset, column))))
+        {
+            *cell = self
+                .offset(coordinates, (row_offset, column_offset))
+                .and_then(|moved| self.get(moved));
+        }
+
+        window
+    }
+}
+
+impl<T: Display> Grid<T> {
+    /// Each row as a string, top to bottom.
+    #[must_use]
+    pub fn lines(&self) -> Vec<String> {
+        self.rows()
+            .map(|row| row.iter().map(ToString::to_string).collect())
+            .collect()
+    }
+}
+
+/// `position` moved by `offset` on a circle of `size` positions.
+fn wrap(position: usize, offset: isize, size: usize) -> usize {
+    let steps = offset.unsigned_abs() % size;
+
+    if offset >= 0 {
+        (position + steps) % size
+    } else {
+        (position + size - steps) % size
+    }
+}
+
+impl<T> Index<Coordinates> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Coordinates) -> &Self::Output {
+        let (width, height) = (self.width, self.height);
+
+        self.get(index)
+            .unwrap_or_else(|| panic!("{:?} is outside of the {}x{} grid", index, width, height))
+    }
+}
+
+impl<T> IndexMut<Coordinates> for Grid<T> {
+    fn index_mut(&mut self, index: Coordinates) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+
+        self.get_mut(index)
+            .unwrap_or_else(|| panic!("{:?} is outside of the {}x{} grid", index, width, height))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.lines().join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::grid::{Grid, GridError};
+
+    fn digits(text: &str) -> Grid<u32> {
+        Grid::parse(text, |_, c| c.to_digit(10).ok_or(c)).unwrap()
+    }
+
+    #[test]
+    fn parses_text() {
+        let grid = digits("123\n456\n");
+
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&6), grid.get((1, 2)));
+        assert_eq!(None, grid.get((2, 0)));
+        assert_eq!(None, grid.get((0, 3)));
+        assert_eq!(4, grid[(1, 0)]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Err(GridError::Cell {
+                row: 1,
+                column: 1,
+                error: 'x',
+            }),
+            Grid::parse("12\n3x\n", |_, c| c.to_digit(10).ok_or(c))
+        );
+
+        assert_eq!(
+            "row 2 has 1 cells, expected 2",
+            Grid::parse("12\n3\n", |_, c| c.to_digit(10).ok_or(c))
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "(2, 0) is outside of the 3x2 grid")]
+    fn index_outside_panics() {
+        let _: u32 = digits("123\n456")[(2, 0)];
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits("123\n456");
+
+        assert_eq!(Some(&[4, 5, 6][..]), grid.row(1));
+        assert_eq!(None, grid.row(2));
+        assert_eq!(
+            vec![vec![1, 2, 3], vec![4, 5, 6]],
+            grid.rows().map(<[u32]>::to_vec).collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            Some(vec![2, 5]),
+            grid.column(1).map(|column| column.copied().collect())
+        );
+        assert!(grid.column(3).is_none());
+        assert_eq!(
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]],
+            grid.columns()
+                .map(|column| column.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = digits("123\n456\n789");
+
+        assert_eq!(
+            vec![(0, 1), (1, 0)],
+            grid.neighbors_4((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 1), (1, 1), (1, 0)],
+            grid.neighbors_8((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(8, grid.neighbors_8((1, 1)).count());
+
+        assert_eq!(
+            vec![(2, 0), (0, 1), (1, 0), (0, 2)],
+            grid.neighbors_4_wrapping((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                (1, 1),
+                (1, 2),
+                (1, 0),
+                (2, 0),
+                (0, 0),
+                (0, 2),
+                (0, 1),
+                (2, 1)
+            ],
+            grid.neighbors_8_wrapping((2, 2)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn window_3x3() {
+        let grid = digits("123\n456\n789");
+
+        assert_eq!(
+            [
+                None,
+                None,
+                None,
+                None,
+                Some(&1),
+                Some(&2),
+                None,
+                Some(&4),
+                Some(&5)
+            ],
+            grid.window_3x3((0, 0))
+        );
+    }
+
+    #[test]
+    fn map_and_display() {
+        let grid = digits("10\n01").map(|&d| if d == 1 { '#' } else { '.' });
+
+        assert_eq!("#.\n.#", grid.to_string());
+        assert_eq!(vec!["#.", ".#"], grid.lines());
+    }
+
+    #[test]
+    fn from_rows_and_new() {
+        assert_eq!(
+            Ok(Grid::new(2, 1, false)),
+            Grid::<bool>::from_rows::<()>(vec![vec![false, false]])
+        );
+
+        let mut grid = Grid::new(2, 2, 0);
+
+        grid[(1, 0)] = 7;
+        *grid.get_mut((0, 1)).unwrap() = 3;
+
+        assert_eq!(
+            vec![((0, 0), &0), ((0, 1), &3), ((1, 0), &7), ((1, 1), &0)],
+            grid.iter().collect::<Vec<_>>()
+        );
+    }
+}
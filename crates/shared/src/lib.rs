@@ -1 +1,9 @@
-
+pub mod automaton;
+pub mod bits;
+pub mod bitset;
+pub mod counter;
+pub mod geom3;
+pub mod grid;
+pub mod memo;
+pub mod parse;
+pub mod search;
@@ -0,0 +1,337 @@
+use std::cell::Cell;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::hash::Hash;
+
+use hashbrown::{HashMap, HashSet};
+
+/// How often a `Memo` had the answer, and how often it had to work it out.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Stats {
+    /// Whether any `Memo` was asked anything.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hits == 0 && self.misses == 0
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{} hits, {} misses", self.hits, self.misses)
+    }
+}
+
+thread_local! {
+    /// The stats of every `Memo` dropped on this thread since the last `take_stats`.
+    static DROPPED: Cell<Stats> = const { Cell::new(Stats { hits: 0, misses: 0 }) };
+}
+
+/// The stats of every `Memo` dropped on this thread since the last call, added up. A runner takes
+/// them after a part, so it can report them without the part handing them back.
+#[must_use]
+pub fn take_stats() -> Stats {
+    DROPPED.take()
+}
+
+/// Forgets the stats `take_stats` would give, so they start from 0 for the next part.
+pub fn reset_stats() {
+    DROPPED.set(Stats::default());
+}
+
+/// Remembers the result of a function for every state it was called with.
+///
+/// Either call `get_or_insert_with` from a recursive function, or describe a single step with
+/// `solve` and let it keep the stack, for states that go too deep to recurse on.
+#[derive(Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: Stats,
+}
+
+impl<K: Eq + Hash, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self {
+            cache: HashMap::new(),
+            stats: Stats::default(),
+        }
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Memo<K, V> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The result for `key`, only calling `compute` the first time we see it. `compute` gets the
+    /// memo back to recurse with.
+    pub fn get_or_insert_with<F>(&mut self, key: K, compute: F) -> V
+    where
+        F: FnOnce(&mut Self, &K) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+
+        let value = compute(self, &key);
+
+        self.cache.insert(key, value.clone());
+
+        value
+    }
+
+    /// The result for `key`, without recursion.
+    ///
+    /// `step` works out one state, asking `Lookup::get` for the results of the states it depends
+    /// on. When any of those is missing, its result is thrown away, the missing states are solved
+    /// first, and `step` is called again.
+    ///
+    /// # Panics
+    ///
+    /// When a state ends up depending on itself.
+    pub fn solve<F>(&mut self, key: &K, mut step: F) -> V
+    where
+        F: FnMut(&K, &mut Lookup<'_, K, V>) -> V,
+    {
+        if let Some(value) = self.cache.get(key) {
+            self.stats.hits += 1;
+
+            return value.clone();
+        }
+
+        // states waiting for others to be solved first
+        let mut waiting = HashSet::new();
+        let mut stack = vec![key.clone()];
+
+        while let Some(current) = stack.pop() {
+            if self.cache.contains_key(&current) {
+                // asked for by more than one state before we got to it
+                continue;
+            }
+
+            let mut lookup = Lookup {
+                cache: &self.cache,
+                hits: 0,
+                missing: Vec::new(),
+            };
+
+            let value = step(&current, &mut lookup);
+
+            let Lookup { hits, missing, .. } = lookup;
+
+            if missing.is_empty() {
+                self.stats.hits += hits;
+                self.stats.misses += 1;
+
+                waiting.remove(&current);
+                self.cache.insert(current, value);
+
+                continue;
+            }
+
+            assert!(
+                missing.iter().all(|state| !waiting.contains(state)),
+                "a state depends on itself"
+            );
+
+            waiting.insert(current.clone());
+            stack.push(current);
+            stack.extend(missing);
+        }
+
+        self.cache[key].clone()
+    }
+
+    /// The remembered result for `key`, without counting it as a hit.
+    #[must_use]
+    pub fn peek(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    /// The number of remembered results.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+}
+
+impl<K, V> Drop for Memo<K, V> {
+    fn drop(&mut self) {
+        DROPPED.set(Stats {
+            hits: DROPPED.get().hits + self.stats.hits,
+            misses: DROPPED.get().misses + self.stats.misses,
+        });
+    }
+}
+
+/// What a `Memo::solve` step uses to get the results it depends on.
+pub struct Lookup<'m, K, V> {
+    cache: &'m HashMap<K, V>,
+    hits: u64,
+    missing: Vec<K>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Lookup<'_, K, V> {
+    /// The result for `key`, `None` when it's not known yet. The step will be called again once
+    /// it is.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.cache.get(key).cloned();
+
+        if value.is_some() {
+            self.hits += 1;
+        } else {
+            self.missing.push(key.clone());
+        }
+
+        value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::memo::{Lookup, Memo, Stats, reset_stats, take_stats};
+
+    fn fibonacci(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+        if n < 2 {
+            return n;
+        }
+
+        memo.get_or_insert_with(n - 1, |memo, &key| fibonacci(memo, key))
+            + memo.get_or_insert_with(n - 2, |memo, &key| fibonacci(memo, key))
+    }
+
+    #[test]
+    fn recursive() {
+        let mut memo = Memo::new();
+
+        assert_eq!(12_586_269_025, fibonacci(&mut memo, 50));
+        assert_eq!(50, memo.len());
+        assert_eq!(
+            Stats {
+                hits: 48,
+                misses: 50
+            },
+            memo.stats()
+        );
+    }
+
+    #[test]
+    fn explicit_stack() {
+        let mut memo = Memo::new();
+
+        // deep enough to overflow the stack when recursing
+        let value = memo.solve(&100_000_u64, |&n, lookup| {
+            if n == 0 {
+                0
+            } else {
+                lookup.get(&(n - 1)).unwrap_or_default() + 1
+            }
+        });
+
+        assert_eq!(100_000, value);
+        assert_eq!(100_001, memo.len());
+        assert_eq!(
+            Stats {
+                hits: 100_000,
+                misses: 100_001
+            },
+            memo.stats()
+        );
+
+        assert_eq!(Some(&10), memo.peek(&10));
+    }
+
+    #[test]
+    fn explicit_stack_shared_states() {
+        let mut memo = Memo::new();
+
+        let step = |&n: &u64, lookup: &mut Lookup<'_, u64, u64>| {
+            if n < 2 {
+                n
+            } else {
+                let first = lookup.get(&(n - 1));
+                let second = lookup.get(&(n - 2));
+
+                first.unwrap_or_default() + second.unwrap_or_default()
+            }
+        };
+
+        assert_eq!(12_586_269_025, memo.solve(&50, step));
+        assert_eq!(51, memo.len());
+        assert_eq!(
+            Stats {
+                hits: 98,
+                misses: 51
+            },
+            memo.stats()
+        );
+
+        // solved before, so nothing to do
+        assert_eq!(55, memo.solve(&10, step));
+        assert_eq!(99, memo.stats().hits);
+    }
+
+    #[test]
+    #[should_panic(expected = "a state depends on itself")]
+    fn explicit_stack_cycle() {
+        let mut memo: Memo<u32, u32> = Memo::new();
+
+        memo.solve(&0, |&n, lookup| {
+            lookup.get(&((n + 1) % 3)).unwrap_or_default()
+        });
+    }
+
+    #[test]
+    fn dropped_stats_add_up() {
+        reset_stats();
+
+        {
+            let mut memo = Memo::new();
+
+            fibonacci(&mut memo, 10);
+        }
+
+        let mut memo = Memo::new();
+
+        fibonacci(&mut memo, 5);
+
+        // not dropped yet
+        assert_eq!(
+            Stats {
+                hits: 8,
+                misses: 10
+            },
+            take_stats()
+        );
+
+        drop(memo);
+
+        assert_eq!(Stats { hits: 3, misses: 5 }, take_stats());
+        assert!(take_stats().is_empty());
+
+        Memo::<u64, u64>::new().get_or_insert_with(1, |_, &key| key);
+        reset_stats();
+
+        assert!(take_stats().is_empty());
+    }
+}
@@ -0,0 +1,588 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use regex::{Captures, Regex};
+
+use crate::bits::BitError;
+use crate::grid::GridError;
+
+/// Something in the input we couldn't parse, and where we found it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ParseError {
+    /// 1-based, like an editor shows it.
+    line: usize,
+    /// 1-based, counted in characters.
+    column: usize,
+    reason: String,
+}
+
+impl ParseError {
+    /// `line_index` and `column_index` are 0-based, as we get them from `enumerate()`.
+    #[must_use]
+    pub fn new(line_index: usize, column_index: usize, reason: String) -> Self {
+        Self {
+            line: line_index + 1,
+            column: column_index + 1,
+            reason,
+        }
+    }
+
+    /// An error about `token`, which is a subslice of `line`.
+    #[must_use]
+    pub fn at_token(line_index: usize, line: &str, token: &str, reason: String) -> Self {
+        Self::new(line_index, column_of(line, token), reason)
+    }
+
+    /// The input ended while we expected more, reported just past the last line.
+    #[must_use]
+    pub fn end_of_input(lines: &[&str], reason: String) -> Self {
+        Self::new(lines.len(), 0, reason)
+    }
+
+    /// An error about a grid whose first row is the line at `first_line_index`. Rows count from
+    /// the grid, and so do the lines of cell errors, which is how `Grid::parse` hands them out.
+    #[must_use]
+    pub fn from_grid_error(grid_error: GridError<ParseError>, first_line_index: usize) -> Self {
+        match grid_error {
+            GridError::Ragged {
+                row,
+                expected,
+                found,
+            } => ParseError::new(
+                first_line_index + row,
+                expected.min(found),
+                format!("expected {} cells, got {}", expected, found),
+            ),
+            GridError::Cell { error, .. } => ParseError {
+                line: error.line + first_line_index,
+                ..error
+            },
+        }
+    }
+
+    /// An error about a bit stream read from the hex on the line at `line_index`, pointing at
+    /// the digit holding the bit.
+    #[must_use]
+    pub fn from_bit_error(bit_error: &BitError, line_index: usize) -> Self {
+        let column_index = match *bit_error {
+            BitError::EndOfStream { position, .. } => position / 4,
+            BitError::TooWide { .. } => 0,
+            BitError::NotHex { column, .. } => column,
+        };
+
+        ParseError::new(line_index, column_index, bit_error.to_string())
+    }
+}
+
+/// For a grid that starts on the first line, see `ParseError::from_grid_error` for others.
+impl From<GridError<ParseError>> for ParseError {
+    fn from(grid_error: GridError<ParseError>) -> Self {
+        ParseError::from_grid_error(grid_error, 0)
+    }
+}
+
+/// For a bit stream on the first line, see `ParseError::from_bit_error` for others.
+impl From<BitError> for ParseError {
+    fn from(bit_error: BitError) -> Self {
+        ParseError::from_bit_error(&bit_error, 0)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `token`, which is a subslice of `line`, the line at `line_index`.
+///
+/// # Errors
+///
+/// When `token` doesn't parse as a `T`, pointing at where `token` starts.
+pub fn parse_token<T>(line_index: usize, line: &str, token: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    token.parse::<T>().map_err(|error| {
+        ParseError::at_token(
+            line_index,
+            line,
+            token,
+            format!("could not parse `{}`: {}", token, error),
+        )
+    })
+}
+
+/// Parses the decimal digit `c`, found at `column_index` of the line at `line_index`.
+///
+/// # Errors
+///
+/// When `c` isn't a digit.
+pub fn parse_digit(line_index: usize, column_index: usize, c: char) -> Result<u32, ParseError> {
+    c.to_digit(10)
+        .ok_or_else(|| ParseError::new(line_index, column_index, format!("`{}` is not a digit", c)))
+}
+
+/// Gets `line` at `line_index`, or an error pointing at the end of the input.
+///
+/// # Errors
+///
+/// When there are no more than `line_index` lines.
+pub fn get_line<'l>(lines: &[&'l str], line_index: usize) -> Result<&'l str, ParseError> {
+    lines.get(line_index).copied().ok_or_else(|| {
+        ParseError::end_of_input(lines, format!("expected at least {} lines", line_index + 1))
+    })
+}
+
+/// Parses every part of `line` between the `separator`s, like the `3,4,3,1,2` of a list of
+/// numbers. Whitespace around the parts is ignored.
+///
+/// # Errors
+///
+/// At the first part that doesn't parse as a `T`, empty parts included.
+pub fn parse_separated<T>(
+    line_index: usize,
+    line: &str,
+    separator: char,
+) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    line.split(separator)
+        .map(|token| parse_token(line_index, line, token.trim()))
+        .collect()
+}
+
+/// Parses every whitespace separated part of `line`, like the `22 13 17 11  0` of a table of
+/// numbers aligned with extra spaces.
+///
+/// # Errors
+///
+/// At the first part that doesn't parse as a `T`.
+pub fn parse_whitespace_separated<T>(line_index: usize, line: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    <T as FromStr>::Err: Display,
+{
+    line.split_whitespace()
+        .map(|token| parse_token(line_index, line, token))
+        .collect()
+}
+
+/// A run of non-empty lines, separated from the next one by one or more empty lines.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Block<'l> {
+    /// 0-based index of the block's first line in the whole input.
+    pub first_line_index: usize,
+    pub lines: &'l [&'l str],
+}
+
+impl<'l> Block<'l> {
+    /// The lines with their index in the whole input, for `ParseError`s.
+    pub fn enumerate(&self) -> impl Iterator<Item = (usize, &'l str)> + use<'l> {
+        let first_line_index = self.first_line_index;
+
+        self.lines
+            .iter()
+            .enumerate()
+            .map(move |(index, &line)| (first_line_index + index, line))
+    }
+
+    /// Gets the line at `index` within the block.
+    ///
+    /// # Errors
+    ///
+    /// When the block has no more than `index` lines, pointing just past the block.
+    pub fn get_line(&self, index: usize) -> Result<&'l str, ParseError> {
+        self.lines.get(index).copied().ok_or_else(|| {
+            self.end_of_block(format!(
+                "expected at least {} lines in the block",
+                index + 1
+            ))
+        })
+    }
+
+    /// The block ended while we expected more, reported just past its last line.
+    #[must_use]
+    pub fn end_of_block(&self, reason: String) -> ParseError {
+        ParseError::new(self.first_line_index + self.lines.len(), 0, reason)
+    }
+}
+
+/// Splits `lines` on empty lines. Lines with only whitespace count as empty, and there are no
+/// empty blocks.
+#[must_use]
+pub fn blocks<'l>(lines: &'l [&'l str]) -> Vec<Block<'l>> {
+    let mut blocks = Vec::new();
+
+    let mut first_line_index = 0;
+
+    for (line_index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            if first_line_index < line_index {
+                blocks.push(Block {
+                    first_line_index,
+                    lines: &lines[first_line_index..line_index],
+                });
+            }
+
+            first_line_index = line_index + 1;
+        }
+    }
+
+    if first_line_index < lines.len() {
+        blocks.push(Block {
+            first_line_index,
+            lines: &lines[first_line_index..],
+        });
+    }
+
+    blocks
+}
+
+/// Lines that all look the same, described by a regular expression whose groups are the fields.
+#[derive(Debug, Clone)]
+pub struct RecordParser {
+    regex: Regex,
+    /// What a line should look like, for the error when one doesn't match.
+    expected: String,
+}
+
+impl RecordParser {
+    /// `pattern` has to match the whole line, leading and trailing whitespace aside.
+    ///
+    /// # Panics
+    ///
+    /// When `pattern` isn't a valid regular expression.
+    #[must_use]
+    pub fn new(pattern: &str, expected: &str) -> Self {
+        Self {
+            regex: Regex::new(pattern).expect("record patterns are valid regular expressions"),
+            expected: expected.to_owned(),
+        }
+    }
+
+    /// Matches `line`, the line at `line_index`.
+    ///
+    /// # Errors
+    ///
+    /// When `line` doesn't match.
+    pub fn parse<'l>(&self, line_index: usize, line: &'l str) -> Result<Record<'l>, ParseError> {
+        let captures = self.regex.captures(line.trim()).ok_or_else(|| {
+            ParseError::new(line_index, 0, format!("expected `{}`", self.expected))
+        })?;
+
+        Ok(Record {
+            line_index,
+            line,
+            captures,
+        })
+    }
+
+    /// Matches every one of `lines` and turns them into `T`s with `f`.
+    ///
+    /// # Errors
+    ///
+    /// At the first line that doesn't match, or that `f` fails on.
+    pub fn parse_all<'l, T, F>(&self, lines: &[&'l str], mut f: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&Record<'l>) -> Result<T, ParseError>,
+    {
+        lines
+            .iter()
+            .enumerate()
+            .map(|(line_index, &line)| f(&self.parse(line_index, line)?))
+            .collect()
+    }
+}
+
+/// A line matched by a `RecordParser`.
+#[derive(Debug)]
+pub struct Record<'l> {
+    line_index: usize,
+    line: &'l str,
+    captures: Captures<'l>,
+}
+
+impl<'l> Record<'l> {
+    /// 0-based index of the line in the input.
+    #[must_use]
+    pub fn line_index(&self) -> usize {
+        self.line_index
+    }
+
+    /// The text of capture `group`, empty when the group didn't take part in the match.
+    #[must_use]
+    pub fn text(&self, group: usize) -> &'l str {
+        self.captures.get(group).map_or("", |m| m.as_str())
+    }
+
+    /// Parses capture `group`.
+    ///
+    /// # Errors
+    ///
+    /// When the text of `group` doesn't parse as a `T`, pointing at where it starts.
+    pub fn get<T>(&self, group: usize) -> Result<T, ParseError>
+    where
+        T: FromStr,
+        <T as FromStr>::Err: Display,
+    {
+        parse_token(self.line_index, self.line, self.text(group))
+    }
+
+    /// An error about capture `group`.
+    #[must_use]
+    pub fn error(&self, group: usize, reason: String) -> ParseError {
+        ParseError::at_token(self.line_index, self.line, self.text(group), reason)
+    }
+}
+
+/// Column (0-based, in characters) where `token` starts in `line`, or 0 if `token` isn't part of
+/// `line`.
+fn column_of(line: &str, token: &str) -> usize {
+    token
+        .as_ptr()
+        .addr()
+        .checked_sub(line.as_ptr().addr())
+        .filter(|&offset| offset + token.len() <= line.len())
+        .and_then(|offset| line.get(..offset))
+        .map_or(0, |before| before.chars().count())
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::bits::BitReader;
+    use crate::grid::{Grid, GridError};
+    use crate::parse::{
+        Block, ParseError, RecordParser, blocks, get_line, parse_digit, parse_separated,
+        parse_token, parse_whitespace_separated,
+    };
+
+    #[test]
+    fn parse_token_reports_position() {
+        let line = "12,x4,7";
+
+        let token = line.split(',').nth(1).unwrap();
+
+        assert_eq!(
+            Err(ParseError {
+                line: 3,
+                column: 4,
+                reason: "could not parse `x4`: invalid digit found in string".to_owned(),
+            }),
+            parse_token::<u32>(2, line, token)
+        );
+
+        assert_eq!(Ok(7), parse_token::<u32>(2, line, "7"));
+    }
+
+    #[test]
+    fn parse_digit_reports_position() {
+        assert_eq!(Ok(7), parse_digit(0, 3, '7'));
+
+        assert_eq!(
+            "line 2, column 4: `x` is not a digit",
+            parse_digit(1, 3, 'x').unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn foreign_token_is_column_1() {
+        assert_eq!(
+            "line 1, column 1: oops",
+            ParseError::at_token(0, "abc", "xyz", "oops".to_owned()).to_string()
+        );
+    }
+
+    #[test]
+    fn get_line_past_the_end() {
+        assert_eq!(Ok("b"), get_line(&["a", "b"], 1));
+
+        assert_eq!(
+            "line 3, column 1: expected at least 3 lines",
+            get_line(&["a", "b"], 2).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn grid_error_into_parse_error() {
+        assert_eq!(
+            "line 3, column 4: expected 5 cells, got 3",
+            ParseError::from(GridError::Ragged {
+                row: 2,
+                expected: 5,
+                found: 3,
+            })
+            .to_string()
+        );
+
+        assert_eq!(
+            "line 1, column 2: `x` is not a digit",
+            ParseError::from(
+                Grid::parse("1x", |(row, column), c| parse_digit(row, column, c)).unwrap_err()
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn grid_error_further_down() {
+        assert_eq!(
+            "line 5, column 4: expected 5 cells, got 3",
+            ParseError::from_grid_error(
+                GridError::Ragged {
+                    row: 2,
+                    expected: 5,
+                    found: 3,
+                },
+                2
+            )
+            .to_string()
+        );
+
+        assert_eq!(
+            "line 4, column 1: `x` is not a digit",
+            ParseError::from_grid_error(
+                Grid::parse("12\nx3", |(row, column), c| parse_digit(row, column, c)).unwrap_err(),
+                2
+            )
+            .to_string()
+        );
+    }
+
+    #[test]
+    fn bit_error_on_a_line() {
+        let bit_error = BitReader::from_hex("F").unwrap().read(5).unwrap_err();
+
+        assert_eq!(
+            "line 1, column 1: asked for 5 bits at bit 0, but only 4 are left",
+            ParseError::from(bit_error.clone()).to_string()
+        );
+        assert_eq!(
+            "line 3, column 1: asked for 5 bits at bit 0, but only 4 are left",
+            ParseError::from_bit_error(&bit_error, 2).to_string()
+        );
+        assert_eq!(
+            "line 2, column 3: `G` is not a hex digit",
+            ParseError::from_bit_error(&BitReader::from_hex("12G4").unwrap_err(), 1).to_string()
+        );
+    }
+
+    #[test]
+    fn separated() {
+        assert_eq!(
+            Ok(vec![3, 4, 3, 1, 2]),
+            parse_separated::<u8>(0, "3,4,3,1,2", ',')
+        );
+        assert_eq!(Ok(vec![1, 2]), parse_separated::<u8>(0, "1, 2", ','));
+
+        assert_eq!(
+            "line 5, column 3: could not parse ``: cannot parse integer from empty string",
+            parse_separated::<u8>(4, "1,,2", ',')
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn whitespace_separated() {
+        assert_eq!(
+            Ok(vec![22, 13, 17, 11, 0]),
+            parse_whitespace_separated::<u32>(0, "22 13 17 11  0")
+        );
+
+        assert_eq!(
+            "line 1, column 5: could not parse `x`: invalid digit found in string",
+            parse_whitespace_separated::<u32>(0, " 1  x")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn split_into_blocks() {
+        let lines = ["a", "b", "", "", "c", "  ", "d", ""];
+
+        assert_eq!(
+            vec![
+                Block {
+                    first_line_index: 0,
+                    lines: &["a", "b"],
+                },
+                Block {
+                    first_line_index: 4,
+                    lines: &["c"],
+                },
+                Block {
+                    first_line_index: 6,
+                    lines: &["d"],
+                },
+            ],
+            blocks(&lines)
+        );
+
+        assert_eq!(Vec::<Block<'_>>::new(), blocks(&["", ""]));
+    }
+
+    #[test]
+    fn block_positions() {
+        let lines = ["a", "", "b", "c"];
+
+        let block = blocks(&lines)[1];
+
+        assert_eq!(
+            vec![(2, "b"), (3, "c")],
+            block.enumerate().collect::<Vec<_>>()
+        );
+        assert_eq!(Ok("c"), block.get_line(1));
+        assert_eq!(
+            "line 5, column 1: expected at least 3 lines in the block",
+            block.get_line(2).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn records() {
+        let parser = RecordParser::new(r"^(\w+) (-?\d+)$", "name number");
+
+        let lines = ["a 1", "  b -2 ", "c x"];
+
+        assert_eq!(
+            Ok(vec![("a", 1), ("b", -2)]),
+            parser.parse_all(&lines[..2], |record| Ok((
+                record.text(1),
+                record.get::<i32>(2)?
+            )))
+        );
+
+        assert_eq!(
+            "line 3, column 1: expected `name number`",
+            parser
+                .parse_all(&lines, |record| record.get::<i32>(2))
+                .unwrap_err()
+                .to_string()
+        );
+
+        let record = parser.parse(1, lines[1]).unwrap();
+
+        assert_eq!(1, record.line_index());
+        assert_eq!(
+            "line 2, column 5: too small",
+            record.error(2, "too small".to_owned()).to_string()
+        );
+        assert_eq!(
+            "line 2, column 5: could not parse `-2`: invalid digit found in string",
+            record.get::<u32>(2).unwrap_err().to_string()
+        );
+    }
+}
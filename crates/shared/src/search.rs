@@ -0,0 +1,368 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+use hashbrown::HashMap;
+
+/// Something to search through: nodes, and the cost of the edges between them.
+pub trait Graph {
+    type Node: Clone + Eq + Hash;
+    /// `Default` is the cost of going nowhere.
+    type Cost: Copy + Ord + Add<Output = Self::Cost> + Default;
+
+    /// The nodes reachable from `node` in one step, with the cost of that step.
+    fn neighbors(&self, node: &Self::Node) -> impl Iterator<Item = (Self::Node, Self::Cost)>;
+
+    /// An estimate of the cost from `node` to the goal, used by `a_star`. It must never
+    /// overestimate, or the path found isn't guaranteed to be the cheapest. The default doesn't
+    /// estimate at all, which makes `a_star` behave like `dijkstra`.
+    fn heuristic(&self, _node: &Self::Node) -> Self::Cost {
+        Self::Cost::default()
+    }
+}
+
+/// A path found by one of the searches.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Path<N, C> {
+    /// From the start up to and including the goal.
+    pub nodes: Vec<N>,
+    /// The sum of the costs of the edges along `nodes`.
+    pub cost: C,
+    /// How many nodes the search expanded before it reached the goal.
+    pub expanded: usize,
+}
+
+impl<N, C> Path<N, C> {
+    /// The node the path ends on.
+    ///
+    /// # Panics
+    ///
+    /// Never, a path always holds at least its start.
+    #[must_use]
+    pub fn goal(&self) -> &N {
+        self.nodes.last().expect("a path holds at least its start")
+    }
+}
+
+/// Every node seen so far, numbered in the order the search found them, so the queues and
+/// parent links can hold plain indices.
+struct Visited<N, C> {
+    indices: HashMap<N, usize>,
+    /// The node, the index of the node it was reached from and the cost to get there.
+    nodes: Vec<(N, Option<usize>, C)>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Visited<N, C> {
+    fn new(start: N, cost: C) -> Self {
+        Self {
+            indices: HashMap::from([(start.clone(), 0)]),
+            nodes: vec![(start, None, cost)],
+        }
+    }
+
+    fn path(&self, mut index: usize, expanded: usize) -> Path<N, C> {
+        let cost = self.nodes[index].2;
+
+        let mut nodes = vec![self.nodes[index].0.clone()];
+
+        while let Some(parent) = self.nodes[index].1 {
+            nodes.push(self.nodes[parent].0.clone());
+
+            index = parent;
+        }
+
+        nodes.reverse();
+
+        Path {
+            nodes,
+            cost,
+            expanded,
+        }
+    }
+}
+
+/// The path from `start` to the first node matching `is_goal` with the fewest steps, ignoring
+/// the costs of the edges while searching.
+pub fn bfs<G, F>(graph: &G, start: G::Node, mut is_goal: F) -> Option<Path<G::Node, G::Cost>>
+where
+    G: Graph,
+    F: FnMut(&G::Node) -> bool,
+{
+    let mut visited = Visited::new(start, G::Cost::default());
+    let mut queue = VecDeque::from([0]);
+    let mut expanded = 0;
+
+    while let Some(index) = queue.pop_front() {
+        let (node, _, cost) = visited.nodes[index].clone();
+
+        if is_goal(&node) {
+            return Some(visited.path(index, expanded));
+        }
+
+        expanded += 1;
+
+        for (neighbor, step_cost) in graph.neighbors(&node) {
+            if visited.indices.contains_key(&neighbor) {
+                continue;
+            }
+
+            let neighbor_index = visited.nodes.len();
+
+            visited.indices.insert(neighbor.clone(), neighbor_index);
+            visited
+                .nodes
+                .push((neighbor, Some(index), cost + step_cost));
+
+            queue.push_back(neighbor_index);
+        }
+    }
+
+    None
+}
+
+/// The cheapest path from `start` to the first node matching `is_goal`.
+pub fn dijkstra<G, F>(graph: &G, start: G::Node, is_goal: F) -> Option<Path<G::Node, G::Cost>>
+where
+    G: Graph,
+    F: FnMut(&G::Node) -> bool,
+{
+    best_first(graph, start, is_goal, |_| G::Cost::default())
+}
+
+/// The cheapest path from `start` to the first node matching `is_goal`, expanding the nodes
+/// `Graph::heuristic` deems closest to the goal first.
+pub fn a_star<G, F>(graph: &G, start: G::Node, is_goal: F) -> Option<Path<G::Node, G::Cost>>
+where
+    G: Graph,
+    F: FnMut(&G::Node) -> bool,
+{
+    best_first(graph, start, is_goal, |node| graph.heuristic(node))
+}
+
+fn best_first<G, F, H>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: F,
+    heuristic: H,
+) -> Option<Path<G::Node, G::Cost>>
+where
+    G: Graph,
+    F: FnMut(&G::Node) -> bool,
+    H: Fn(&G::Node) -> G::Cost,
+{
+    let mut open = BinaryHeap::from([Reverse((heuristic(&start), G::Cost::default(), 0))]);
+    let mut visited = Visited::new(start, G::Cost::default());
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, queued_cost, index))) = open.pop() {
+        let (node, _, cost) = visited.nodes[index].clone();
+
+        // a cheaper way to this node was found after this one was queued
+        if queued_cost > cost {
+            continue;
+        }
+
+        if is_goal(&node) {
+            return Some(visited.path(index, expanded));
+        }
+
+        expanded += 1;
+
+        for (neighbor, step_cost) in graph.neighbors(&node) {
+            let neighbor_cost = cost + step_cost;
+
+            let neighbor_index = match visited.indices.get(&neighbor) {
+                Some(&known) if visited.nodes[known].2 <= neighbor_cost => continue,
+                Some(&known) => {
+                    visited.nodes[known] = (neighbor.clone(), Some(index), neighbor_cost);
+
+                    known
+                },
+                None => {
+                    let new = visited.nodes.len();
+
+                    visited.indices.insert(neighbor.clone(), new);
+                    visited
+                        .nodes
+                        .push((neighbor.clone(), Some(index), neighbor_cost));
+
+                    new
+                },
+            };
+
+            open.push(Reverse((
+                neighbor_cost + heuristic(&neighbor),
+                neighbor_cost,
+                neighbor_index,
+            )));
+        }
+    }
+
+    None
+}
+
+/// Every path from `start` to a node matching `is_goal`, depth first. `can_visit` decides whether
+/// a neighbor can be stepped on given the path so far, and must keep the search from going around
+/// in circles.
+pub fn all_paths<G, F, V>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: F,
+    mut can_visit: V,
+) -> Vec<Vec<G::Node>>
+where
+    G: Graph,
+    F: FnMut(&G::Node) -> bool,
+    V: FnMut(&[G::Node], &G::Node) -> bool,
+{
+    let mut paths = Vec::new();
+
+    // the path so far, with the neighbors of every node on it still to try
+    let mut to_try = vec![graph.neighbors(&start).map(|(n, _)| n).collect::<Vec<_>>()];
+    let mut path = vec![start];
+
+    while let Some(candidates) = to_try.last_mut() {
+        let Some(next) = candidates.pop() else {
+            to_try.pop();
+            path.pop();
+
+            continue;
+        };
+
+        if !can_visit(&path, &next) {
+            continue;
+        }
+
+        path.push(next.clone());
+
+        if is_goal(&next) {
+            paths.push(path.clone());
+            path.pop();
+        } else {
+            to_try.push(graph.neighbors(&next).map(|(n, _)| n).collect());
+        }
+    }
+
+    paths
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::search::{Graph, Path, a_star, all_paths, bfs, dijkstra};
+
+    /// A line of `length` nodes where stepping right costs 1, and skipping ahead 2 costs 5.
+    struct Line {
+        length: u32,
+    }
+
+    impl Graph for Line {
+        type Node = u32;
+        type Cost = u32;
+
+        fn neighbors(&self, node: &u32) -> impl Iterator<Item = (u32, u32)> {
+            [(node + 1, 1), (node + 2, 5)]
+                .into_iter()
+                .filter(|&(next, _)| next < self.length)
+        }
+
+        fn heuristic(&self, node: &u32) -> u32 {
+            self.length - 1 - node
+        }
+    }
+
+    #[test]
+    fn bfs_takes_the_fewest_steps() {
+        let path = bfs(&Line { length: 5 }, 0, |&n| n == 4).unwrap();
+
+        assert_eq!(vec![0, 2, 4], path.nodes);
+        assert_eq!(10, path.cost);
+    }
+
+    #[test]
+    fn dijkstra_takes_the_cheapest_path() {
+        let path = dijkstra(&Line { length: 5 }, 0, |&n| n == 4).unwrap();
+
+        assert_eq!(
+            Path {
+                nodes: vec![0, 1, 2, 3, 4],
+                cost: 4,
+                expanded: 4,
+            },
+            path
+        );
+        assert_eq!(&4, path.goal());
+    }
+
+    #[test]
+    fn a_star_takes_the_cheapest_path() {
+        let path = a_star(&Line { length: 5 }, 0, |&n| n == 4).unwrap();
+
+        assert_eq!(vec![0, 1, 2, 3, 4], path.nodes);
+        assert_eq!(4, path.cost);
+    }
+
+    #[test]
+    fn a_star_expands_less_than_dijkstra() {
+        let line = Line { length: 50 };
+
+        let with_heuristic = a_star(&line, 0, |&n| n == 49).unwrap();
+        let without = dijkstra(&line, 0, |&n| n == 49).unwrap();
+
+        assert_eq!(without.cost, with_heuristic.cost);
+        assert!(with_heuristic.expanded <= without.expanded);
+    }
+
+    #[test]
+    fn start_is_the_goal() {
+        let path = dijkstra(&Line { length: 5 }, 3, |&n| n == 3).unwrap();
+
+        assert_eq!(
+            Path {
+                nodes: vec![3],
+                cost: 0,
+                expanded: 0,
+            },
+            path
+        );
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        assert_eq!(None, bfs(&Line { length: 5 }, 0, |&n| n == 7));
+        assert_eq!(None, dijkstra(&Line { length: 5 }, 0, |&n| n == 7));
+        assert_eq!(None, a_star(&Line { length: 5 }, 0, |&n| n == 7));
+    }
+
+    #[test]
+    fn every_path() {
+        let mut paths = all_paths(&Line { length: 5 }, 0, |&n| n == 4, |_, _| true);
+
+        paths.sort();
+
+        assert_eq!(
+            vec![
+                vec![0, 1, 2, 3, 4],
+                vec![0, 1, 2, 4],
+                vec![0, 1, 3, 4],
+                vec![0, 2, 3, 4],
+                vec![0, 2, 4],
+            ],
+            paths
+        );
+    }
+
+    #[test]
+    fn every_path_can_visit() {
+        let paths = all_paths(
+            &Line { length: 5 },
+            0,
+            |&n| n == 4,
+            |path, &next| next - path.last().unwrap() == 1,
+        );
+
+        assert_eq!(vec![vec![0, 1, 2, 3, 4]], paths);
+    }
+}